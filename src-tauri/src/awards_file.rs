@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+use crate::sources::TargetSource;
+use crate::usaspending::{AwardPage, AwardRecord, AwardSummary, RecipientCandidate, SearchFilters, TargetSearch};
+
+// Offline target source backed by a JSON array of award summaries, e.g. an
+// export from another award database. Compiled in with the
+// `source-awards-file` feature; the file is named by OPEN_SEASON_AWARDS_FILE.
+
+pub const SOURCE_ID: &str = "awards_file";
+pub const PATH_VAR: &str = "OPEN_SEASON_AWARDS_FILE";
+
+pub struct AwardsFile {
+    path: Option<PathBuf>,
+}
+
+impl AwardsFile {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var_os(PATH_VAR).map(PathBuf::from))
+    }
+
+    fn load(&self) -> Result<Vec<AwardSummary>, String> {
+        let path = self.path.as_ref().ok_or_else(|| format!("{} is not set", PATH_VAR))?;
+        let data = std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid awards file {}: {}", path.display(), e))
+    }
+}
+
+fn matches(award: &AwardSummary, query: &str) -> bool {
+    award.recipient_name.to_lowercase().contains(query)
+}
+
+impl TargetSource for AwardsFile {
+    fn id(&self) -> &'static str {
+        SOURCE_ID
+    }
+
+    // Filters are not applied; the file holds whatever the export selected
+    fn search(
+        &self,
+        target_name: &str,
+        _filters: &SearchFilters,
+        max_results: usize,
+        on_page: &mut dyn FnMut(&AwardPage),
+    ) -> Result<TargetSearch, String> {
+        let query = target_name.trim().to_lowercase();
+        let awards: Vec<AwardSummary> = self
+            .load()?
+            .into_iter()
+            .filter(|a| matches(a, &query))
+            .take(max_results)
+            .collect();
+        on_page(&AwardPage { group: "file".into(), page: 1, awards: awards.clone(), fetched: awards.len(), has_next: false });
+        Ok(TargetSearch { awards, fetched_at: Some(crate::db::now_timestamp()), ..Default::default() })
+    }
+
+    fn resolve_entity(&self, query: &str, limit: usize) -> Result<Vec<RecipientCandidate>, String> {
+        let query = query.trim().to_lowercase();
+        let mut names: Vec<String> = Vec::new();
+        for award in self.load()? {
+            if matches(&award, &query) && !names.contains(&award.recipient_name) {
+                names.push(award.recipient_name);
+            }
+        }
+        Ok(names
+            .into_iter()
+            .take(limit)
+            .map(|name| RecipientCandidate { name, uei: None, duns: None, level: None })
+            .collect())
+    }
+
+    fn fetch_award(&self, _award_id: &str) -> Result<AwardRecord, String> {
+        Err("The awards file has no award detail".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_searches_awards_file_by_recipient() {
+        let path = std::env::temp_dir().join(format!("os-awards-{}.json", uuid::Uuid::new_v4()));
+        let award = |id: &str, recipient: &str| AwardSummary {
+            generated_internal_id: id.into(),
+            date_signed: "2021-01-01".into(),
+            description: None,
+            total_obligation: 100.0,
+            awarding_agency: "USDA".into(),
+            recipient_name: recipient.into(),
+            raw: None,
+            sources: Vec::new(),
+        };
+        let awards = vec![award("A", "ACME LLC"), award("B", "Other Co"), award("C", "ACME LLC")];
+        std::fs::write(&path, serde_json::to_vec(&awards).unwrap()).unwrap();
+
+        let source = AwardsFile::new(Some(path.clone()));
+        let mut pages = 0;
+        let search = source.search(" acme", &SearchFilters::default(), 10, &mut |_| pages += 1).unwrap();
+        let ids: Vec<&str> = search.awards.iter().map(|a| a.generated_internal_id.as_str()).collect();
+        assert_eq!(ids, ["A", "C"]);
+        assert_eq!(pages, 1);
+        assert_eq!(source.search("acme", &SearchFilters::default(), 1, &mut |_| {}).unwrap().awards.len(), 1);
+        assert_eq!(source.resolve_entity("acme", 5).unwrap().len(), 1);
+
+        assert!(AwardsFile::new(None).search("acme", &SearchFilters::default(), 10, &mut |_| {}).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
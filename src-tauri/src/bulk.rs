@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+use crate::usaspending::AwardSummary;
+
+// Importer for USAspending award data archive downloads: zips of CSV files in
+// the contracts or assistance schema, either prime award summaries (one row
+// per award) or transactions (one row per action). Rows for the target
+// recipient are folded into one `AwardSummary` per award. The award unique
+// key columns hold the same id as `generated_internal_id` in the API, so
+// awards imported here and awards attached from a search line up.
+
+// Emit a progress event every this many rows
+const PROGRESS_INTERVAL: usize = 50_000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkSchema {
+    Contracts,
+    Assistance,
+}
+
+impl BulkSchema {
+    fn detect(header: &[String]) -> Option<Self> {
+        if header.iter().any(|h| h == "contract_award_unique_key") {
+            Some(BulkSchema::Contracts)
+        } else if header.iter().any(|h| h == "assistance_award_unique_key") {
+            Some(BulkSchema::Assistance)
+        } else {
+            None
+        }
+    }
+
+    fn key_column(self) -> &'static str {
+        match self {
+            BulkSchema::Contracts => "contract_award_unique_key",
+            BulkSchema::Assistance => "assistance_award_unique_key",
+        }
+    }
+}
+
+// Which rows belong to the target. A row matches if any given field does;
+// names are compared after folding case and punctuation, against both the
+// recipient and its parent.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RecipientFilter {
+    pub name: Option<String>,
+    pub uei: Option<String>,
+    pub duns: Option<String>,
+}
+
+impl RecipientFilter {
+    fn is_empty(&self) -> bool {
+        [&self.name, &self.uei, &self.duns]
+            .iter()
+            .all(|f| f.as_deref().is_none_or(|v| v.trim().is_empty()))
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BulkFile {
+    pub name: String,
+    // None for CSVs in neither schema, which are skipped
+    pub schema: Option<BulkSchema>,
+    pub rows_read: usize,
+    pub rows_matched: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BulkProgress {
+    pub file: String,
+    pub rows_read: usize,
+    pub rows_matched: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BulkImport {
+    pub files: Vec<BulkFile>,
+    pub awards: Vec<AwardSummary>,
+}
+
+pub fn normalize_name(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_uppercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Reads one CSV record (RFC 4180: quoted fields may hold commas, doubled
+// quotes and line breaks). Returns None at end of input.
+pub fn read_record<R: BufRead + ?Sized>(reader: &mut R, line: &mut String) -> std::io::Result<Option<Vec<String>>> {
+    line.clear();
+    if reader.read_line(line)? == 0 {
+        return Ok(None);
+    }
+    // An odd number of quotes means a quoted field continues on the next line
+    while line.matches('"').count() % 2 == 1 {
+        if reader.read_line(line)? == 0 {
+            break;
+        }
+    }
+    let record = line.strip_suffix('\n').unwrap_or(line);
+    let record = record.strip_suffix('\r').unwrap_or(record);
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    Ok(Some(fields))
+}
+
+struct Row<'a> {
+    columns: &'a HashMap<String, usize>,
+    values: &'a [String],
+}
+
+impl Row<'_> {
+    fn get(&self, column: &str) -> Option<&str> {
+        let value = self.values.get(*self.columns.get(column)?)?.trim();
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn first(&self, columns: &[&str]) -> Option<&str> {
+        columns.iter().find_map(|c| self.get(c))
+    }
+
+    fn amount(&self, column: &str) -> Option<f64> {
+        self.get(column)?.parse().ok()
+    }
+}
+
+#[derive(Default)]
+struct AwardTotals {
+    summary: Option<AwardSummary>,
+    // Award-level total as reported in the file, if the schema has one
+    reported_total: Option<f64>,
+    // Sum of per-transaction obligations, used otherwise
+    action_total: f64,
+}
+
+fn matches(filter: &RecipientFilter, name: &str, row: &Row) -> bool {
+    let eq = |want: &Option<String>, columns: &[&str]| {
+        want.as_deref().map(str::trim).filter(|w| !w.is_empty()).is_some_and(|w| {
+            columns.iter().filter_map(|c| row.get(c)).any(|v| v.eq_ignore_ascii_case(w))
+        })
+    };
+    let name_match = !name.is_empty()
+        && ["recipient_name", "recipient_parent_name", "recipient_name_raw"]
+            .iter()
+            .filter_map(|c| row.get(c))
+            .any(|v| normalize_name(v) == name);
+    name_match
+        || eq(&filter.uei, &["recipient_uei", "recipient_parent_uei"])
+        || eq(&filter.duns, &["recipient_duns", "recipient_parent_duns"])
+}
+
+fn fold_row(totals: &mut AwardTotals, id: &str, header: &[String], row: &Row) {
+    let date = row.first(&["award_base_action_date", "action_date", "period_of_performance_start_date"]);
+    let summary = totals.summary.get_or_insert_with(|| AwardSummary {
+        generated_internal_id: id.to_string(),
+        date_signed: String::new(),
+        description: None,
+        total_obligation: 0.0,
+        awarding_agency: String::new(),
+        recipient_name: String::new(),
+        raw: None,
+        sources: Vec::new(),
+    });
+
+    // The base (earliest) action describes the award
+    let earliest = summary.date_signed.is_empty() || date.is_some_and(|d| d < summary.date_signed.as_str());
+    if earliest {
+        if let Some(date) = date {
+            summary.date_signed = date.to_string();
+        }
+        summary.description = row
+            .first(&["prime_award_base_transaction_description", "transaction_description", "award_description"])
+            .map(str::to_string)
+            .or(summary.description.take());
+        let raw: serde_json::Map<String, serde_json::Value> = header
+            .iter()
+            .zip(row.values)
+            .filter(|(_, v)| !v.trim().is_empty())
+            .map(|(h, v)| (h.clone(), serde_json::Value::String(v.trim().to_string())))
+            .collect();
+        summary.raw = Some(serde_json::Value::Object(raw));
+    }
+    if summary.awarding_agency.is_empty() {
+        summary.awarding_agency = row.get("awarding_agency_name").unwrap_or_default().to_string();
+    }
+    if summary.recipient_name.is_empty() {
+        summary.recipient_name = row.first(&["recipient_name", "recipient_name_raw"]).unwrap_or_default().to_string();
+    }
+
+    if let Some(total) = ["total_obligated_amount", "total_dollars_obligated"].iter().find_map(|c| row.amount(c)) {
+        totals.reported_total = Some(total);
+    }
+    totals.action_total += row.amount("federal_action_obligation").unwrap_or(0.0);
+}
+
+// Reads every CSV in the archive and returns the target's awards, in the
+// order they first appear
+pub fn import_archive(
+    zip_path: &Path,
+    filter: &RecipientFilter,
+    mut on_progress: impl FnMut(&BulkProgress),
+) -> Result<BulkImport, String> {
+    if filter.is_empty() {
+        return Err("A recipient name, UEI or DUNS is required".to_string());
+    }
+    let name = filter.name.as_deref().map(normalize_name).unwrap_or_default();
+
+    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a zip archive: {}", e))?;
+
+    let mut files = Vec::new();
+    let mut order: Vec<String> = Vec::new();
+    let mut awards: HashMap<String, AwardTotals> = HashMap::new();
+
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let file_name = entry.name().to_string();
+        if entry.is_dir() || !file_name.to_lowercase().ends_with(".csv") {
+            continue;
+        }
+        let mut reader = BufReader::new(entry);
+        let mut line = String::new();
+
+        let Some(mut header) = read_record(&mut reader, &mut line).map_err(|e| e.to_string())? else {
+            continue;
+        };
+        if let Some(first) = header.first_mut() {
+            *first = first.trim_start_matches('\u{feff}').to_string();
+        }
+        let mut summary = BulkFile { name: file_name.clone(), schema: BulkSchema::detect(&header), rows_read: 0, rows_matched: 0 };
+        let Some(schema) = summary.schema else {
+            files.push(summary);
+            continue;
+        };
+        let columns: HashMap<String, usize> = header.iter().enumerate().map(|(i, h)| (h.clone(), i)).collect();
+
+        while let Some(values) = read_record(&mut reader, &mut line).map_err(|e| format!("{}: {}", file_name, e))? {
+            summary.rows_read += 1;
+            let row = Row { columns: &columns, values: &values };
+            if let Some(id) = row.get(schema.key_column()).filter(|_| matches(filter, &name, &row)) {
+                summary.rows_matched += 1;
+                let totals = awards.entry(id.to_string()).or_insert_with(|| {
+                    order.push(id.to_string());
+                    AwardTotals::default()
+                });
+                fold_row(totals, id, &header, &row);
+            }
+            if summary.rows_read.is_multiple_of(PROGRESS_INTERVAL) {
+                on_progress(&BulkProgress { file: file_name.clone(), rows_read: summary.rows_read, rows_matched: summary.rows_matched });
+            }
+        }
+        on_progress(&BulkProgress { file: file_name, rows_read: summary.rows_read, rows_matched: summary.rows_matched });
+        files.push(summary);
+    }
+
+    if !files.iter().any(|f| f.schema.is_some()) {
+        return Err("No USAspending contracts or assistance CSV files found in the archive".to_string());
+    }
+
+    let awards = order
+        .into_iter()
+        .filter_map(|id| {
+            let totals = awards.remove(&id)?;
+            let mut summary = totals.summary?;
+            summary.total_obligation = totals.reported_total.unwrap_or(totals.action_total);
+            Some(summary)
+        })
+        .collect();
+    Ok(BulkImport { files, awards })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_import_archive_filters_and_folds_awards() {
+        let base = std::env::temp_dir().join(format!("os-bulk-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&base).unwrap();
+        let zip_path = base.join("download.zip");
+
+        let contracts = "\u{feff}contract_award_unique_key,award_id_piid,total_dollars_obligated,federal_action_obligation,action_date,transaction_description,awarding_agency_name,recipient_name,recipient_uei,recipient_parent_name\r\n\
+            CONT_AWD_1,P1,300.5,100,2020-05-01,\"Mod, second\",Department of Defense,ACME CORP,UEI111,\r\n\
+            CONT_AWD_1,P1,300.5,200.5,2020-01-15,\"Base award \"\"alpha\"\"\nline two\",Department of Defense,ACME CORP,UEI111,\r\n\
+            CONT_AWD_2,P2,50,50,2021-03-01,Other,Department of Defense,SOMEONE ELSE,UEI999,\r\n\
+            CONT_AWD_3,P3,75,75,2022-03-01,Subsidiary,Department of Defense,ACME EAST,UEI333,\"Acme, Corp.\"\r\n";
+        let assistance = "assistance_award_unique_key,award_id_fain,federal_action_obligation,action_date,transaction_description,awarding_agency_name,recipient_name,recipient_uei\n\
+            ASST_NON_9,F9,40,2021-02-01,Meals,Department of Agriculture,Acme Corp.,UEI111\n\
+            ASST_NON_9,F9,60,2021-06-01,Meals mod,Department of Agriculture,Acme Corp.,UEI111\n";
+
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        for (name, body) in [("Contracts_1.csv", contracts), ("Assistance_1.csv", assistance), ("README.txt", "x")] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let filter = RecipientFilter { name: Some("acme corp".into()), ..Default::default() };
+        let import = import_archive(&zip_path, &filter, |_| {}).unwrap();
+        assert_eq!(import.files.len(), 2);
+        assert_eq!(import.files[0].schema, Some(BulkSchema::Contracts));
+        assert_eq!((import.files[0].rows_read, import.files[0].rows_matched), (4, 3));
+
+        let ids: Vec<&str> = import.awards.iter().map(|a| a.generated_internal_id.as_str()).collect();
+        assert_eq!(ids, ["CONT_AWD_1", "CONT_AWD_3", "ASST_NON_9"]);
+        let base_award = &import.awards[0];
+        assert_eq!(base_award.total_obligation, 300.5);
+        assert_eq!(base_award.date_signed, "2020-01-15");
+        assert_eq!(base_award.description.as_deref(), Some("Base award \"alpha\"\nline two"));
+        assert_eq!(import.awards[2].total_obligation, 100.0);
+
+        let by_uei = RecipientFilter { uei: Some("uei999".into()), ..Default::default() };
+        assert_eq!(import_archive(&zip_path, &by_uei, |_| {}).unwrap().awards.len(), 1);
+        assert!(import_archive(&zip_path, &RecipientFilter::default(), |_| {}).is_err());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -1,5 +1,5 @@
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::{Cursor, Read, Seek, Write};
 use std::path::Path;
 use zip::{ZipArchive, ZipWriter};
 use zip::write::SimpleFileOptions;
@@ -7,7 +7,37 @@ use walkdir::WalkDir;
 
 pub fn export_hunt(hunt_path: &Path, output_path: &Path) -> Result<(), String> {
     let file = File::create(output_path).map_err(|e| e.to_string())?;
-    let mut zip = ZipWriter::new(file);
+    write_archive(hunt_path, file, &|_| false, &[])
+}
+
+// In-memory archive of a hunt folder (used for the encrypted trash)
+pub fn pack_dir(hunt_path: &Path) -> Result<Vec<u8>, String> {
+    let mut buffer = Cursor::new(Vec::new());
+    write_archive(hunt_path, &mut buffer, &|_| false, &[])?;
+    Ok(buffer.into_inner())
+}
+
+// Archive of a hunt folder with the live database files swapped for a
+// consistent copy taken with SQLite's backup API (used for snapshots)
+pub fn pack_snapshot(hunt_path: &Path, db_copy: &[u8]) -> Result<Vec<u8>, String> {
+    let mut buffer = Cursor::new(Vec::new());
+    let is_live_db = |name: &str| name.starts_with("metadata.db");
+    write_archive(hunt_path, &mut buffer, &is_live_db, &[("metadata.db", db_copy)])?;
+    Ok(buffer.into_inner())
+}
+
+pub fn unpack_dir(data: &[u8], target_dir: &Path) -> Result<(), String> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| e.to_string())?;
+    extract_archive(&mut archive, target_dir)
+}
+
+fn write_archive<W: Write + Seek>(
+    hunt_path: &Path,
+    writer: W,
+    skip: &dyn Fn(&str) -> bool,
+    extra: &[(&str, &[u8])],
+) -> Result<(), String> {
+    let mut zip = ZipWriter::new(writer);
     let options = SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated)
         .unix_permissions(0o755);
@@ -21,6 +51,9 @@ pub fn export_hunt(hunt_path: &Path, output_path: &Path) -> Result<(), String> {
         let name = path.strip_prefix(hunt_path).map_err(|e| e.to_string())?;
         // Stick to string representation for zip compatibility
         let name_str = name.to_str().ok_or("Invalid UTF-8 in path")?;
+        if skip(name_str) {
+            continue;
+        }
 
         if path.is_file() {
             zip.start_file(name_str, options).map_err(|e| e.to_string())?;
@@ -34,6 +67,11 @@ pub fn export_hunt(hunt_path: &Path, output_path: &Path) -> Result<(), String> {
         }
     }
 
+    for (name, data) in extra {
+        zip.start_file(*name, options).map_err(|e| e.to_string())?;
+        zip.write_all(data).map_err(|e| e.to_string())?;
+    }
+
     zip.finish().map_err(|e| e.to_string())?;
     Ok(())
 }
@@ -56,7 +94,12 @@ pub fn import_hunt(osb_path: &Path, vaults_root: &Path) -> Result<String, String
         return Err(format!("Hunt '{}' already exists in vault.", hunt_name));
     }
 
-    // Extract
+    extract_archive(&mut archive, &target_dir)?;
+
+    Ok(hunt_name)
+}
+
+fn extract_archive<R: Read + Seek>(archive: &mut ZipArchive<R>, target_dir: &Path) -> Result<(), String> {
     for i in 0..archive.len() {
         let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
         
@@ -79,5 +122,5 @@ pub fn import_hunt(osb_path: &Path, vaults_root: &Path) -> Result<String, String
         }
     }
 
-    Ok(hunt_name)
+    Ok(())
 }
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::crypto::{self, SessionKey};
+use crate::evidence::sha256_hex;
+use crate::shred::{self, DeletionReport};
+
+// Encrypted cache of external API responses, so scouting results stay
+// reviewable offline. Each entry is sealed under the session key as
+// `<app data>/scout_cache/<sha256 of the normalized request>.enc`; the file
+// name reveals nothing about the request.
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CacheEntry<T> {
+    pub request: serde_json::Value,
+    pub fetched_at: String,
+    pub response: T,
+}
+
+pub fn cache_root(data_root: &Path) -> PathBuf {
+    data_root.join("scout_cache")
+}
+
+// Trims strings and drops nulls and empty strings, arrays and objects, so
+// requests that differ only in unset fields or stray whitespace share a key
+pub fn normalize(value: &serde_json::Value) -> Option<serde_json::Value> {
+    use serde_json::Value;
+    match value {
+        Value::Null => None,
+        Value::String(s) => {
+            let t = s.trim();
+            (!t.is_empty()).then(|| Value::String(t.to_string()))
+        }
+        Value::Array(items) => {
+            let items: Vec<Value> = items.iter().filter_map(normalize).collect();
+            (!items.is_empty()).then_some(Value::Array(items))
+        }
+        Value::Object(map) => {
+            let map: serde_json::Map<String, Value> = map
+                .iter()
+                .filter_map(|(k, v)| normalize(v).map(|v| (k.clone(), v)))
+                .collect();
+            (!map.is_empty()).then_some(Value::Object(map))
+        }
+        other => Some(other.clone()),
+    }
+}
+
+// JSON with object keys sorted, independent of how serde_json orders maps
+fn canonical_string(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+    match value {
+        Value::Array(items) => format!("[{}]", items.iter().map(canonical_string).collect::<Vec<_>>().join(",")),
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|k| format!("{}:{}", Value::String(k.clone()), canonical_string(&map[k])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+pub fn cache_key(request: &serde_json::Value) -> String {
+    let normalized = normalize(request).unwrap_or(serde_json::Value::Null);
+    sha256_hex(canonical_string(&normalized).as_bytes())
+}
+
+#[derive(Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    key: SessionKey,
+}
+
+impl ResponseCache {
+    pub fn new(data_root: &Path, key: SessionKey) -> Self {
+        Self { dir: cache_root(data_root), key }
+    }
+
+    fn path(&self, cache_key: &str) -> PathBuf {
+        self.dir.join(format!("{}.enc", cache_key))
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, request: &serde_json::Value) -> Option<CacheEntry<T>> {
+        self.get_by_key(&cache_key(request))
+    }
+
+    pub fn get_by_key<T: DeserializeOwned>(&self, cache_key: &str) -> Option<CacheEntry<T>> {
+        if !cache_key.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let data = crypto::read_sealed_file(&self.path(cache_key), &self.key).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    pub fn put<T: Serialize>(&self, request: &serde_json::Value, fetched_at: &str, response: &T) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let entry = CacheEntry {
+            request: normalize(request).unwrap_or(serde_json::Value::Null),
+            fetched_at: fetched_at.to_string(),
+            response,
+        };
+        let json = serde_json::to_vec(&entry).map_err(|e| e.to_string())?;
+        crypto::write_sealed_file(&self.path(&cache_key(request)), &json, &self.key)
+    }
+
+    // Every readable entry with its key; entries sealed under another key are skipped
+    pub fn entries<T: DeserializeOwned>(&self) -> Vec<(String, CacheEntry<T>)> {
+        let Ok(dir) = fs::read_dir(&self.dir) else { return Vec::new() };
+        let mut entries: Vec<(String, CacheEntry<T>)> = dir
+            .flatten()
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().into_owned();
+                let key = name.strip_suffix(".enc")?.to_string();
+                let entry = self.get_by_key(&key)?;
+                Some((key, entry))
+            })
+            .collect();
+        entries.sort_by(|a, b| b.1.fetched_at.cmp(&a.1.fetched_at));
+        entries
+    }
+
+    pub fn clear(&self, report: &mut DeletionReport) -> Result<(), String> {
+        shred::shred_dir(&self.dir, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_equivalent_requests_share_an_entry() {
+        let a = json!({ "target": " Acme ", "filters": { "naics_codes": [], "state": null, "start": "2020-01-01" } });
+        let b = json!({ "filters": { "start": "2020-01-01" }, "target": "Acme" });
+        assert_eq!(cache_key(&a), cache_key(&b));
+        assert_ne!(cache_key(&a), cache_key(&json!({ "target": "Acme Inc" })));
+
+        let base = std::env::temp_dir().join(format!("os-cache-{}", uuid::Uuid::new_v4()));
+        let cache = ResponseCache::new(&base, SessionKey([3u8; 32]));
+        cache.put(&a, "2026-01-01T00:00:00Z", &vec![1, 2, 3]).unwrap();
+        let entry: CacheEntry<Vec<i32>> = cache.get(&b).unwrap();
+        assert_eq!(entry.response, [1, 2, 3]);
+        assert_eq!(entry.request["target"], "Acme");
+
+        // Encrypted at rest, and unreadable under another key
+        let raw = fs::read(cache.path(&cache_key(&a))).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("Acme"));
+        assert!(ResponseCache::new(&base, SessionKey([4u8; 32])).get::<Vec<i32>>(&a).is_none());
+
+        let mut report = DeletionReport::default();
+        cache.clear(&mut report).unwrap();
+        assert!(cache.entries::<Vec<i32>>().is_empty());
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+// Case record kept in each hunt's `info` table: status lifecycle plus the
+// summary fields shown on the dashboard.
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CaseStatus {
+    Draft,
+    Investigating,
+    #[serde(rename = "Counsel Review")]
+    CounselReview,
+    Filed,
+    #[serde(rename = "Under Seal")]
+    UnderSeal,
+    Closed,
+}
+
+impl CaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaseStatus::Draft => "Draft",
+            CaseStatus::Investigating => "Investigating",
+            CaseStatus::CounselReview => "Counsel Review",
+            CaseStatus::Filed => "Filed",
+            CaseStatus::UnderSeal => "Under Seal",
+            CaseStatus::Closed => "Closed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "Draft" => Ok(CaseStatus::Draft),
+            "Investigating" => Ok(CaseStatus::Investigating),
+            "Counsel Review" => Ok(CaseStatus::CounselReview),
+            "Filed" => Ok(CaseStatus::Filed),
+            "Under Seal" => Ok(CaseStatus::UnderSeal),
+            "Closed" => Ok(CaseStatus::Closed),
+            _ => Err(format!("Unknown case status: {}", s)),
+        }
+    }
+
+    // Allowed moves: forward one step, back one step before filing, close from
+    // anywhere, and reopen a closed case as Investigating. A filed case cannot
+    // go back to an unfiled state.
+    pub fn can_transition_to(&self, next: CaseStatus) -> bool {
+        use CaseStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Investigating)
+                | (Investigating, CounselReview)
+                | (Investigating, Draft)
+                | (CounselReview, Filed)
+                | (CounselReview, Investigating)
+                | (Filed, UnderSeal)
+                | (Closed, Investigating)
+        ) || (next == Closed && *self != Closed)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CaseRecord {
+    pub name: String,
+    pub status: CaseStatus,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub status_changed_at: Option<String>,
+    pub target_entity: Option<String>,
+    pub estimated_value: Option<f64>,
+    pub jurisdiction: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StatusChange {
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub changed_at: String,
+    pub note: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_lifecycle() {
+        use CaseStatus::*;
+        assert!(Draft.can_transition_to(Investigating));
+        assert!(CounselReview.can_transition_to(Filed));
+        assert!(Filed.can_transition_to(Closed));
+        assert!(Closed.can_transition_to(Investigating));
+        assert!(!Draft.can_transition_to(Filed));
+        assert!(!Filed.can_transition_to(CounselReview));
+        assert!(!Closed.can_transition_to(Closed));
+        assert_eq!(CaseStatus::parse("Under Seal").unwrap(), UnderSeal);
+        assert_eq!(serde_json::to_string(&CounselReview).unwrap(), "\"Counsel Review\"");
+    }
+}
@@ -1,16 +1,43 @@
 use std::fs;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
 use tauri::State;
 use crate::crypto::{self, AppState};
 use crate::bundle;
-use crate::usaspending::{self, AwardSummary};
+use crate::usaspending::{self, ApiConfig, AwardRecord, AwardSummary, CachedSearchInfo, RecipientCandidate, RecipientProfile, SearchFilters, TargetSearch};
 use crate::db::HuntDatabase;
 use crate::pdf;
-
-use tauri::{AppHandle, Manager}; // Added Manager for path access if needed, or just AppHandle methods in v2
+use crate::metadata::{self, MetadataReport};
+use crate::evidence::{self, EvidenceVersion, IngestSummary};
+use crate::search::{self, SearchHit};
+use crate::email::{self, MailboxSummary};
+use crate::shred::{self, DeletionReport};
+use crate::trash::{self, TrashEntry, TrashedHunt};
+use crate::snapshot::{self, SnapshotInfo, SnapshotPolicy};
+use crate::case::{CaseStatus, StatusChange};
+use crate::bulk::{self, RecipientFilter};
+use crate::sources::{self, MergedSearch, SourceInfo, SourceOptions};
+use crate::sam;
+use crate::leie;
+use crate::screening::{self, Finding, ReferenceDataset, ReferenceDb};
+use crate::cache::{self, ResponseCache};
+use crate::template::{self, ChecklistItem, HuntTemplate, TemplateSummary};
+use base64::Engine;
+
+use tauri::{AppHandle, Emitter, Manager}; // Added Manager for path access if needed, or just AppHandle methods in v2
 
 // Helper for standard storage path
+fn get_data_root(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().app_local_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))
+}
+
+fn hunt_name(hunt_dir: &std::path::Path) -> Option<String> {
+    let db = HuntDatabase::open(hunt_dir.join("metadata.db")).ok()?;
+    db.case_record().ok().flatten().map(|r| r.name)
+}
+
 fn get_vault_root(app: &AppHandle) -> Result<PathBuf, String> {
     // Tauri v2: app.path().app_local_data_dir() typically resolves to:
     // Windows: C:\Users\User\AppData\Local\com.openseason.app
@@ -28,9 +55,8 @@ fn get_vault_root(app: &AppHandle) -> Result<PathBuf, String> {
 pub fn save_disclosure_cmd(
     app: AppHandle,
     hunt_id: String, 
-    target: String, 
+    target: Option<String>, // Used only when the case has no target entity recorded
     count: usize, // Ignored, kept for compatibility with Svelte invokes
-    value: f64
 ) -> Result<String, String> {
     let vaults_root = get_vault_root(&app)?;
     let hunt_dir = vaults_root.join(&hunt_id);
@@ -39,41 +65,80 @@ pub fn save_disclosure_cmd(
     }
 
     let db_path = hunt_dir.join("metadata.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = &db.conn;
+
+    let record = db.case_record().map_err(|e| e.to_string())?;
+    let target = record.as_ref()
+        .and_then(|r| r.target_entity.clone())
+        .or(target)
+        .or_else(|| record.as_ref().map(|r| r.name.clone()))
+        .unwrap_or_else(|| hunt_id.clone());
+
+    // Exposure is the sum of the attached awards, listed in the summary
+    let awards = db.target_awards().map_err(|e| e.to_string())?;
+    let value = db.award_exposure().map_err(|e| e.to_string())?;
+    let mut awards_markup = String::new();
+    for award in &awards {
+        let clean_id = award.generated_internal_id.replace("[", "\\[").replace("]", "\\]");
+        let clean_agency = award.awarding_agency.replace("[", "\\[").replace("]", "\\]");
+        awards_markup.push_str(&format!(
+            "  [{}], [{}], [{}], [\\${:.2}],\n",
+            clean_id, award.date_signed, clean_agency, award.total_obligation
+        ));
+    }
 
-    // 1. Fetch and format Timeline Events
+    // 1. Fetch and format Timeline Events, citing linked exhibits and parties
     let mut timeline_markup = String::new();
-    if let Ok(mut stmt) = conn.prepare("SELECT title, description, event_date FROM events ORDER BY event_date ASC") {
-        let rows = stmt.query_map([], |row| {
-            let title: String = row.get(0)?;
-            let desc: Option<String> = row.get(1)?;
-            let date: String = row.get(2)?;
-            Ok((date, title, desc.unwrap_or_default()))
-        });
-        if let Ok(r_iter) = rows {
-            for r in r_iter.flatten() {
-                let clean_title = r.1.replace("[", "\\[").replace("]", "\\]");
-                let clean_desc = r.2.replace("[", "\\[").replace("]", "\\]");
-                timeline_markup.push_str(&format!("- *{} - {}*: {}\n", r.0, clean_title, clean_desc));
-            }
-        }
+    let exhibits: HashMap<i64, i64> = query_evidence(&db, "", &[])
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|e| e.exhibit_number.map(|n| (e.id, n)))
+        .collect();
+    let party_names: HashMap<i64, String> = query_parties(&db, "", &[])
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| (p.id, format!("{} ({})", p.name, p.role)))
+        .collect();
+    for event in query_events(&db, "", &[]).unwrap_or_default() {
+        timeline_markup.push_str(&timeline_entry_markup(&event, &exhibits, &party_names));
     }
 
-    // 2. Fetch and format Evidence Files
+    // 2. Fetch and format Evidence Files, grouped by category in exhibit order
     let mut evidence_markup = String::new();
-    if let Ok(mut stmt) = conn.prepare("SELECT description, file_path, sha256_hash FROM evidence ORDER BY created_at ASC") {
+    if let Ok(mut stmt) = conn.prepare(
+        "SELECT description, file_path, sha256_hash, original_sha256_hash, exhibit_number, category FROM evidence \
+         ORDER BY category IS NULL, category COLLATE NOCASE ASC, exhibit_number ASC"
+    ) {
         let rows = stmt.query_map([], |row| {
             let desc: String = row.get(0)?;
             let path: String = row.get(1)?;
             let hash: Option<String> = row.get(2)?;
-            Ok((desc, path, hash.unwrap_or_else(|| "N/A".to_string())))
+            let original: Option<String> = row.get(3)?;
+            let exhibit: Option<i64> = row.get(4)?;
+            let category: Option<String> = row.get(5)?;
+            Ok((desc, path, hash.unwrap_or_else(|| "N/A".to_string()), original, exhibit, category))
         });
         if let Ok(r_iter) = rows {
+            let mut current_group: Option<String> = None;
             for r in r_iter.flatten() {
+                let group = r.5.clone().unwrap_or_else(|| "Uncategorized".to_string());
+                if current_group.as_deref() != Some(group.as_str()) {
+                    let clean_group = group.replace("[", "\\[").replace("]", "\\]");
+                    evidence_markup.push_str(&format!("  table.cell(colspan: 5, fill: gray.lighten(90%))[*{}*],\n", clean_group));
+                    current_group = Some(group);
+                }
+
+                let exhibit = r.4.map(|n| format!("Ex. {}", n)).unwrap_or_else(|| "-".to_string());
                 let clean_path = r.1.replace("[", "\\[").replace("]", "\\]");
                 let clean_desc = r.0.replace("[", "\\[").replace("]", "\\]");
                 let clean_hash = r.2.replace("[", "\\[").replace("]", "\\]");
-                evidence_markup.push_str(&format!("  [{}], [{}], [{}],\n", clean_path, clean_desc, clean_hash));
+                evidence_markup.push_str(&format!("  [{}], [{}], [{}], [Scrubbed copy], [{}],\n", exhibit, clean_path, clean_desc, clean_hash));
+                // A preserved original gets its own row so each hash is tied to one version
+                if let Some(original) = r.3 {
+                    let clean_original = original.replace("[", "\\[").replace("]", "\\]");
+                    evidence_markup.push_str(&format!("  [{}], [{}], [{}], [Unmodified original], [{}],\n", exhibit, clean_path, clean_desc, clean_original));
+                }
             }
         }
     }
@@ -102,7 +167,7 @@ pub fn save_disclosure_cmd(
         }
     }
 
-    let pdf_bytes = pdf::compile_report(&target, value, &timeline_markup, &evidence_markup, &complaint_markup)?;
+    let pdf_bytes = pdf::compile_report(&target, value, &awards_markup, &timeline_markup, &evidence_markup, &complaint_markup)?;
     
     // 1. Save to Vault (Archive)
     let vault_path = hunt_dir.join("disclosure_statement.pdf");
@@ -123,12 +188,168 @@ pub fn save_disclosure_cmd(
 
 
 #[tauri::command]
-pub async fn verify_target_cmd(name: String) -> Result<Vec<AwardSummary>, String> {
+pub async fn verify_target_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    filters: Option<SearchFilters>,
+    max_results: Option<usize>,
+    offline: Option<bool>,
+    sources: Option<Vec<String>>,
+) -> Result<MergedSearch, String> {
+    let filters = filters.unwrap_or_default();
+    let max_results = max_results.unwrap_or(usaspending::DEFAULT_MAX_RESULTS);
+    let offline = offline.unwrap_or(false);
+    // The cache is encrypted, so it is only used while the vault is unlocked
+    let cache = match state.get_key() {
+        Some(key) => Some(ResponseCache::new(&get_data_root(&app)?, key)),
+        None if offline => return Err("Vault Locked".to_string()),
+        None => None,
+    };
+    let options = SourceOptions { cache, offline };
+    let sources = sources
+        .unwrap_or_else(|| vec![sources::DEFAULT_SOURCE.to_string()])
+        .iter()
+        .map(|id| sources::open(id, &options))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        sources::search_all(&sources, &name, &filters, max_results, |page| {
+            let _ = app.emit("award-scout-progress", page);
+        })
+    }).await.map_err(|e| e.to_string())?
+}
+
+
+// Loads a public reference list from a local extract file (or its zip),
+// replacing any earlier import of the same dataset
+#[tauri::command]
+pub async fn import_reference_extract(app: AppHandle, dataset: String, path: String) -> Result<ReferenceDataset, String> {
+    let data_root = get_data_root(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let reference = ReferenceDb::open_at(&data_root)?;
+        let path = Path::new(&path);
+        match dataset.as_str() {
+            sam::EXCLUSIONS => sam::import_exclusions(&reference, path),
+            sam::ENTITIES => sam::import_entities(&reference, path),
+            leie::LEIE => leie::import_leie(&reference, path),
+            leie::NPPES => leie::import_nppes(&reference, path),
+            _ => Err(format!("Unknown reference dataset '{}'", dataset)),
+        }
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn list_reference_datasets(app: AppHandle) -> Result<Vec<ReferenceDataset>, String> {
+    let reference = ReferenceDb::open_at(&get_data_root(&app)?)?;
+    reference.datasets().map_err(|e| e.to_string())
+}
+
+// Screens the hunt against the imported reference lists and returns the hits
+// of this run
+#[tauri::command]
+pub async fn screen_hunt(app: AppHandle, hunt_id: String) -> Result<Vec<Finding>, String> {
+    let data_root = get_data_root(&app)?;
+    let db_path = get_vault_root(&app)?.join(&hunt_id).join("metadata.db");
+    if !db_path.exists() {
+        return Err("Hunt not found".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let reference = ReferenceDb::open_at(&data_root)?;
+        if reference.datasets().map_err(|e| e.to_string())?.is_empty() {
+            return Err("Import a reference list first".to_string());
+        }
+        let db = HuntDatabase::open(&db_path).map_err(|e| e.to_string())?;
+        let has_profile = db.target_profile().map_err(|e| e.to_string())?.is_some();
+        if !has_profile && screening::subjects(&db).map_err(|e| e.to_string())?.is_empty() {
+            return Err("The hunt has no target entity or parties to screen".to_string());
+        }
+        let mut findings = sam::screen_target(&reference, &db)?;
+        findings.extend(leie::screen_hunt(&reference, &db)?);
+        Ok(findings)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn get_hunt_findings(app: AppHandle, hunt_id: String) -> Result<Vec<Finding>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.findings().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_finding_status(app: AppHandle, hunt_id: String, finding_id: i64, status: String) -> Result<(), String> {
+    if !screening::FINDING_STATUSES.contains(&status.as_str()) {
+        return Err(format!("Unknown finding status '{}'", status));
+    }
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    if !db.set_finding_status(finding_id, &status).map_err(|e| e.to_string())? {
+        return Err("Finding not found".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_target_sources() -> Vec<SourceInfo> {
+    sources::available()
+}
+
+#[tauri::command]
+pub fn list_cached_searches(app: AppHandle, state: State<'_, AppState>) -> Result<Vec<CachedSearchInfo>, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    Ok(usaspending::cached_searches(&ResponseCache::new(&get_data_root(&app)?, key)))
+}
+
+#[tauri::command]
+pub fn get_cached_search(app: AppHandle, state: State<'_, AppState>, cache_key: String) -> Result<TargetSearch, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    usaspending::cached_search(&ResponseCache::new(&get_data_root(&app)?, key), &cache_key)
+        .ok_or_else(|| "Cached search not found".to_string())
+}
+
+#[tauri::command]
+pub fn clear_scout_cache(app: AppHandle, state: State<'_, AppState>) -> Result<DeletionReport, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let mut report = DeletionReport::default();
+    ResponseCache::new(&get_data_root(&app)?, key).clear(&mut report)?;
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn search_recipients(
+    query: String,
+    limit: Option<usize>,
+    source: Option<String>,
+) -> Result<Vec<RecipientCandidate>, String> {
+    let source = sources::open(source.as_deref().unwrap_or(sources::DEFAULT_SOURCE), &SourceOptions::default())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        source.resolve_entity(&query, limit.unwrap_or(10))
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_recipient_profile(identifier: String, level: Option<String>) -> Result<RecipientProfile, String> {
     tauri::async_runtime::spawn_blocking(move || {
-        usaspending::check_target(&name)
+        usaspending::recipient_profile(&ApiConfig::default(), &identifier, level.as_deref())
+            .map_err(|e| e.to_string())
     }).await.map_err(|e| e.to_string())?
 }
 
+// Stores the recipient the user confirmed; award searches can then filter on its UEI
+#[tauri::command]
+pub fn confirm_target_entity(app: AppHandle, hunt_id: String, profile: RecipientProfile) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.set_target_profile(&profile).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_target_entity(app: AppHandle, hunt_id: String) -> Result<Option<RecipientProfile>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.target_profile().map_err(|e| e.to_string())
+}
 
 #[tauri::command]
 pub fn export_hunt_cmd(app: AppHandle, hunt_id: String, target_path: String) -> Result<String, String> {
@@ -144,17 +365,7 @@ pub fn export_hunt_cmd(app: AppHandle, hunt_id: String, target_path: String) ->
             .map_err(|e| e.to_string())?;
             
         // Get hunt name from database for filename
-        let db_path = hunt_path.join("metadata.db");
-        let mut name = hunt_id.clone();
-        
-        if let Ok(conn) = rusqlite::Connection::open(&db_path) {
-             let mut stmt = conn.prepare("SELECT name FROM info LIMIT 1").ok();
-             if let Some(mut s) = stmt {
-                 if let Ok(n) = s.query_row([], |row| row.get(0)) {
-                     name = n;
-                 }
-             }
-        }
+        let name = hunt_name(&hunt_path).unwrap_or_else(|| hunt_id.clone());
         
         let sanitized_name = name.replace(" ", "_").replace("/", "-");
         let filename = format!("{}.osb", sanitized_name);
@@ -186,7 +397,25 @@ pub fn import_hunt_cmd(app: AppHandle, osb_path: String) -> Result<String, Strin
 pub struct HuntMetadata {
     id: String,
     name: String,
-    created: String,
+    status: CaseStatus,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    status_changed_at: Option<String>,
+    target_entity: Option<String>,
+    estimated_value: Option<f64>,
+    jurisdiction: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct HuntFilter {
+    // Only hunts in one of these statuses
+    pub statuses: Option<Vec<CaseStatus>>,
+    pub jurisdiction: Option<String>,
+    // Case-insensitive match on name or target entity
+    pub text: Option<String>,
+    // name | status | created_at | updated_at | estimated_value | target_entity
+    pub sort_by: Option<String>,
+    pub descending: Option<bool>,
 }
 
 // --- Auth / Key Management ---
@@ -233,8 +462,9 @@ pub fn is_locked(state: State<'_, AppState>) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub fn list_hunts(app: AppHandle) -> Result<Vec<HuntMetadata>, String> {
+pub fn list_hunts(app: AppHandle, filter: Option<HuntFilter>) -> Result<Vec<HuntMetadata>, String> {
     let vaults_root = get_vault_root(&app)?;
+    let filter = filter.unwrap_or_default();
     
     if !vaults_root.exists() {
         return Ok(Vec::new());
@@ -245,44 +475,101 @@ pub fn list_hunts(app: AppHandle) -> Result<Vec<HuntMetadata>, String> {
 
     for entry in entries {
         let entry = entry.map_err(|e| e.to_string())?;
-        if entry.path().is_dir() {
-            let id = entry.file_name().into_string().unwrap_or_default();
-            let mut name = id.clone();
-            
-            // Try to read name from metadata.db if possible, or just use ID for now.
-            // For MVP + speed, we'll try to look for a specific simple metadata file if DB is too heavy, 
-            // but prompt asked for metadata.db. 
-            // Let's rely on just ID for now or basic DB check if we had a lightweight way.
-            // Actually, let's try to query the DB for the name.
-            let db_path = entry.path().join("metadata.db");
-            if db_path.exists() {
-                 if let Ok(conn) = rusqlite::Connection::open(&db_path) {
-                     let mut stmt = conn.prepare("SELECT name FROM info LIMIT 1").ok();
-                     if let Some(mut s) = stmt {
-                         if let Ok(n) = s.query_row([], |row| row.get(0)) {
-                             name = n;
-                         }
-                     }
-                 }
-            }
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let id = entry.file_name().into_string().unwrap_or_default();
+        let db_path = entry.path().join("metadata.db");
+        if !db_path.exists() {
+            continue;
+        }
 
-            hunts.push(HuntMetadata {
+        let record = HuntDatabase::open(&db_path).ok().and_then(|db| db.case_record().ok().flatten());
+        let hunt = match record {
+            Some(r) => HuntMetadata {
                 id,
-                name,
-                created: "Unknown".to_string(), // Placeholder or read from DB
-            });
+                name: r.name,
+                status: r.status,
+                created_at: r.created_at,
+                updated_at: r.updated_at,
+                status_changed_at: r.status_changed_at,
+                target_entity: r.target_entity,
+                estimated_value: r.estimated_value,
+                jurisdiction: r.jurisdiction,
+            },
+            None => HuntMetadata {
+                name: id.clone(),
+                id,
+                status: CaseStatus::Draft,
+                created_at: None,
+                updated_at: None,
+                status_changed_at: None,
+                target_entity: None,
+                estimated_value: None,
+                jurisdiction: None,
+            },
+        };
+        if hunt_matches(&hunt, &filter) {
+            hunts.push(hunt);
         }
     }
+
+    sort_hunts(&mut hunts, filter.sort_by.as_deref().unwrap_or("updated_at"), filter.descending.unwrap_or(true));
     Ok(hunts)
 }
 
+fn hunt_matches(hunt: &HuntMetadata, filter: &HuntFilter) -> bool {
+    if let Some(statuses) = &filter.statuses {
+        if !statuses.is_empty() && !statuses.contains(&hunt.status) {
+            return false;
+        }
+    }
+    if let Some(j) = filter.jurisdiction.as_deref().filter(|j| !j.is_empty()) {
+        if !hunt.jurisdiction.as_deref().is_some_and(|hj| hj.eq_ignore_ascii_case(j)) {
+            return false;
+        }
+    }
+    if let Some(text) = filter.text.as_deref().filter(|t| !t.is_empty()) {
+        let needle = text.to_lowercase();
+        let in_name = hunt.name.to_lowercase().contains(&needle);
+        let in_target = hunt.target_entity.as_deref().is_some_and(|t| t.to_lowercase().contains(&needle));
+        if !in_name && !in_target {
+            return false;
+        }
+    }
+    true
+}
+
+fn sort_hunts(hunts: &mut [HuntMetadata], sort_by: &str, descending: bool) {
+    hunts.sort_by(|a, b| {
+        let ord = match sort_by {
+            "name" => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            "status" => a.status.cmp(&b.status),
+            "created_at" => a.created_at.cmp(&b.created_at),
+            "estimated_value" => a.estimated_value.partial_cmp(&b.estimated_value).unwrap_or(std::cmp::Ordering::Equal),
+            "target_entity" => a.target_entity.cmp(&b.target_entity),
+            _ => a.updated_at.cmp(&b.updated_at),
+        };
+        if descending { ord.reverse() } else { ord }
+    });
+}
+
 #[tauri::command]
-pub fn create_new_hunt(app: AppHandle, name: String, state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+pub fn create_new_hunt(
+    app: AppHandle,
+    name: String,
+    template_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
     use uuid::Uuid;
      // Ensure unlocked
-    if state.get_key().is_none() {
-        return Err("Vault Locked".to_string());
-    }
+    let key = state.get_key().ok_or("Vault Locked")?;
+
+    // Resolve the template first so a bad id doesn't leave an empty hunt behind
+    let hunt_template = match template_id.as_deref() {
+        Some(id) => Some(template::find(&get_data_root(&app)?, id)?),
+        None => None,
+    };
 
     let uuid = Uuid::new_v4();
     let vaults_root = get_vault_root(&app)?;
@@ -291,25 +578,22 @@ pub fn create_new_hunt(app: AppHandle, name: String, state: State<'_, AppState>)
     fs::create_dir_all(&hunt_dir.join("evidence"))
         .map_err(|e| format!("Failed to create dir: {}", e))?;
 
-    // Create and init metadata.db
+    // Create and init metadata.db (full schema plus the case record)
     let db_path = hunt_dir.join("metadata.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS info (name TEXT, created_at TEXT, status TEXT)", 
-        []
-    ).map_err(|e| e.to_string())?;
-    
-    let created_at = chrono::Utc::now().to_rfc3339();
-    conn.execute(
-        "INSERT INTO info (name, created_at, status) VALUES (?1, ?2, 'Draft')",
-        [&name, &created_at]
-    ).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(&db_path).map_err(|e| e.to_string())?;
+    let created_at = db.init_case(&name).map_err(|e| e.to_string())?;
+    if let Some(t) = &hunt_template {
+        db.apply_template(t).map_err(|e| e.to_string())?;
+        for section_id in t.complaint_sections.keys() {
+            search::index_record(&db.conn, &key, search::KIND_SECTION, section_id)?;
+        }
+    }
 
     Ok(serde_json::json!({
         "id": uuid.to_string(),
         "name": name,
-        "created_at": created_at
+        "created_at": created_at,
+        "template_id": template_id
     }))
 }
 
@@ -318,26 +602,213 @@ pub async fn update_hunt(app: AppHandle, hunt_id: String, name: String) -> Resul
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
 
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
-    
-    conn.execute(
-        "UPDATE info SET name = ?1",
-        rusqlite::params![name],
-    ).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
+    db.set_case_name(&name).map_err(|e| e.to_string())
+}
 
-    Ok(())
+#[tauri::command]
+pub fn update_hunt_case(
+    app: AppHandle,
+    hunt_id: String,
+    target_entity: Option<String>,
+    estimated_value: Option<f64>,
+    jurisdiction: Option<String>,
+) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    let clean = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    db.set_case_details(clean(target_entity).as_deref(), estimated_value, clean(jurisdiction).as_deref())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn delete_hunt(app: AppHandle, hunt_id: String) -> Result<(), String> {
+pub fn set_hunt_status(
+    app: AppHandle,
+    hunt_id: String,
+    status: CaseStatus,
+    note: Option<String>,
+) -> Result<(), String> {
     let vault_path = get_vault_root(&app)?;
-    let hunt_path = vault_path.join(&hunt_id);
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    let record = db.case_record().map_err(|e| e.to_string())?.ok_or("Hunt has no case record")?;
+
+    if !record.status.can_transition_to(status) {
+        return Err(format!(
+            "Cannot move a case from '{}' to '{}'",
+            record.status.as_str(),
+            status.as_str()
+        ));
+    }
+    db.set_case_status(record.status, status, note.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_hunt_status_history(app: AppHandle, hunt_id: String) -> Result<Vec<StatusChange>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.status_history().map_err(|e| e.to_string())
+}
+
+// Stores the awards picked from `verify_target_cmd`; `target` records the scouted
+// entity on the case record when given
+#[tauri::command]
+pub fn attach_target_awards(
+    app: AppHandle,
+    hunt_id: String,
+    awards: Vec<AwardSummary>,
+    target: Option<String>,
+) -> Result<f64, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
 
-    if hunt_path.exists() {
-        std::fs::remove_dir_all(hunt_path).map_err(|e| e.to_string())?;
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for award in &awards {
+        db.attach_award(award).map_err(|e| e.to_string())?;
     }
-    
-    Ok(())
+    if let Some(target) = target.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        let record = db.case_record().map_err(|e| e.to_string())?.ok_or("Hunt has no case record")?;
+        db.set_case_details(Some(&target), record.estimated_value, record.jurisdiction.as_deref())
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    db.award_exposure().map_err(|e| e.to_string())
+}
+
+#[derive(serde::Serialize)]
+pub struct BulkImportResult {
+    pub files: Vec<bulk::BulkFile>,
+    pub imported: usize,
+    pub exposure: f64,
+}
+
+// Loads the target's awards from a USAspending bulk download zip into the
+// hunt, as `attach_target_awards` would
+#[tauri::command]
+pub async fn import_bulk_awards(
+    app: AppHandle,
+    hunt_id: String,
+    zip_path: String,
+    recipient: RecipientFilter,
+) -> Result<BulkImportResult, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db_path = vault_path.join(&hunt_id).join("metadata.db");
+    if !db_path.exists() {
+        return Err("Hunt not found".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let import = bulk::import_archive(Path::new(&zip_path), &recipient, |progress| {
+            let _ = app.emit("bulk-import-progress", progress);
+        })?;
+
+        let db = HuntDatabase::open(&db_path).map_err(|e| e.to_string())?;
+        let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        for award in &import.awards {
+            db.attach_award(award).map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        Ok(BulkImportResult {
+            files: import.files,
+            imported: import.awards.len(),
+            exposure: db.award_exposure().map_err(|e| e.to_string())?,
+        })
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn detach_target_award(app: AppHandle, hunt_id: String, award_id: String) -> Result<f64, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    if !db.detach_award(&award_id).map_err(|e| e.to_string())? {
+        return Err("Award not attached to this hunt".to_string());
+    }
+    db.award_exposure().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_target_awards(app: AppHandle, hunt_id: String) -> Result<Vec<AwardSummary>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.target_awards().map_err(|e| e.to_string())
+}
+
+// Downloads an award's full detail, modifications and sub-awards into the hunt
+#[tauri::command]
+pub async fn fetch_award_detail(
+    app: AppHandle,
+    hunt_id: String,
+    award_id: String,
+    source: Option<String>,
+) -> Result<AwardRecord, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db_path = vault_path.join(&hunt_id).join("metadata.db");
+    if !db_path.exists() {
+        return Err("Hunt not found".to_string());
+    }
+    let source = sources::open(source.as_deref().unwrap_or(sources::DEFAULT_SOURCE), &SourceOptions::default())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let record = source.fetch_award(&award_id)?;
+        let db = HuntDatabase::open(&db_path).map_err(|e| e.to_string())?;
+        db.store_award_record(&record).map_err(|e| e.to_string())?;
+        db.award_record(&record.detail.generated_internal_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Award detail was not stored".to_string())
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn get_award_detail(app: AppHandle, hunt_id: String, award_id: String) -> Result<Option<AwardRecord>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.award_record(&award_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn plot_award_transactions(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    award_id: String,
+) -> Result<usize, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    if db.award_record(&award_id).map_err(|e| e.to_string())?.is_none() {
+        return Err("Fetch the award detail before plotting it".to_string());
+    }
+    let plotted = db.plot_award_transactions(&award_id).map_err(|e| e.to_string())?;
+    for event_id in &plotted {
+        search::index_record(&db.conn, &key, search::KIND_EVENT, &event_id.to_string())?;
+    }
+    Ok(plotted.len())
+}
+
+// Evidence is encrypted under the vault master key; hunts have no key of their own to destroy
+const NO_HUNT_KEY_NOTE: &str =
+    "Crypto-shredding not available: hunt data is encrypted under the vault master key, not a per-hunt key";
+
+const TRASH_NOTE: &str = "An encrypted copy is kept in the trash until empty_trash runs";
+
+#[tauri::command]
+pub async fn delete_hunt(app: AppHandle, state: State<'_, AppState>, hunt_id: String) -> Result<DeletionReport, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+    let hunt_path = vault_path.join(&hunt_id);
+    if !hunt_path.exists() {
+        return Err("Hunt not found".to_string());
+    }
+
+    let name = hunt_name(&hunt_path).unwrap_or_else(|| hunt_id.clone());
+
+    let trash_dir = trash::hunt_trash_dir(&vault_path);
+    let mut report = DeletionReport::default();
+    trash::trash_hunt(&hunt_path, &trash_dir, &hunt_id, &name, &key, &mut report)?;
+    report.note(TRASH_NOTE);
+    report.note(NO_HUNT_KEY_NOTE);
+    Ok(report)
 }
 
 
@@ -348,6 +819,8 @@ pub struct EventEntry {
     pub description: Option<String>,
     pub event_date: String,
     pub event_type: String,
+    pub evidence_ids: Vec<i64>,
+    pub party_ids: Vec<i64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -358,6 +831,8 @@ pub struct PartyEntry {
     pub email: Option<String>,
     pub phone: Option<String>,
     pub notes: Option<String>,
+    pub npi: Option<String>,
+    pub address: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -370,18 +845,51 @@ pub struct SectionEntry {
 pub fn get_hunt_timeline(app: AppHandle, hunt_id: String) -> Result<Vec<EventEntry>, String> {
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
+    query_events(&db, "", &[])
+}
 
-    let mut stmt = conn.prepare("SELECT id, title, description, event_date, event_type FROM events ORDER BY event_date ASC")
-        .map_err(|e| e.to_string())?;
+// One timeline line of the report, citing the event's exhibits and parties
+fn timeline_entry_markup(event: &EventEntry, exhibits: &HashMap<i64, i64>, party_names: &HashMap<i64, String>) -> String {
+    let clean_title = event.title.replace("[", "\\[").replace("]", "\\]");
+    let clean_desc = event.description.clone().unwrap_or_default().replace("[", "\\[").replace("]", "\\]");
+    let mut markup = format!("- *{} - {}*: {}", event.event_date, clean_title, clean_desc);
+
+    let mut cited: Vec<i64> = event.evidence_ids.iter().filter_map(|id| exhibits.get(id).copied()).collect();
+    cited.sort_unstable();
+    let involved: Vec<&str> = event.party_ids.iter().filter_map(|id| party_names.get(id).map(|n| n.as_str())).collect();
+    if !cited.is_empty() {
+        let list: Vec<String> = cited.iter().map(|n| format!("Ex. {}", n)).collect();
+        markup.push_str(&format!(" \\ _Supported by {}._", list.join(", ")));
+    }
+    if !involved.is_empty() {
+        let clean_names = involved.join("; ").replace("[", "\\[").replace("]", "\\]");
+        markup.push_str(&format!(" \\ _Involving {}._", clean_names));
+    }
+    markup.push('\n');
+    markup
+}
+
+// Events matching an optional WHERE clause, with their linked evidence and party ids
+fn query_events(db: &HuntDatabase, filter: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<EventEntry>, String> {
+    let mut evidence_links = db.event_evidence_links().map_err(|e| e.to_string())?;
+    let mut party_links = db.event_party_links().map_err(|e| e.to_string())?;
+
+    let mut stmt = db.conn.prepare(&format!(
+        "SELECT id, title, description, event_date, event_type FROM events {} ORDER BY event_date ASC",
+        filter
+    )).map_err(|e| e.to_string())?;
     
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map(params, |row| {
+        let id: i64 = row.get(0)?;
         Ok(EventEntry {
-            id: row.get(0)?,
+            id,
             title: row.get(1)?,
             description: row.get(2)?,
             event_date: row.get(3)?,
             event_type: row.get(4)?,
+            evidence_ids: evidence_links.remove(&id).unwrap_or_default(),
+            party_ids: party_links.remove(&id).unwrap_or_default(),
         })
     }).map_err(|e| e.to_string())?;
 
@@ -393,46 +901,57 @@ pub fn get_hunt_timeline(app: AppHandle, hunt_id: String) -> Result<Vec<EventEnt
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn add_hunt_event(
     app: AppHandle,
+    state: State<'_, AppState>,
     hunt_id: String,
     title: String,
     description: String,
     event_date: String,
     event_type: String,
 ) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
 
-    conn.execute(
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
         "INSERT INTO events (title, description, event_date, event_type) VALUES (?1, ?2, ?3, ?4)",
         rusqlite::params![title, description, event_date, event_type],
     ).map_err(|e| e.to_string())?;
-    Ok(())
+    search::index_record(&tx, &key, search::KIND_EVENT, &tx.last_insert_rowid().to_string())?;
+    tx.commit().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn delete_hunt_event(app: AppHandle, hunt_id: String, event_id: i64) -> Result<(), String> {
+pub fn delete_hunt_event(app: AppHandle, state: State<'_, AppState>, hunt_id: String, event_id: i64) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
 
-    conn.execute("DELETE FROM events WHERE id = ?1", rusqlite::params![event_id])
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    // Moves the event and its links into the encrypted trash
+    trash::trash_record(&db, &key, trash::KIND_EVENT, event_id)?;
+    db.compact().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn get_hunt_parties(app: AppHandle, hunt_id: String) -> Result<Vec<PartyEntry>, String> {
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
+    query_parties(&db, "", &[])
+}
 
-    let mut stmt = conn.prepare("SELECT id, name, role, email, phone, notes FROM parties ORDER BY name ASC")
-        .map_err(|e| e.to_string())?;
+fn query_parties(db: &HuntDatabase, filter: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<PartyEntry>, String> {
+    let mut stmt = db.conn.prepare(&format!(
+        "SELECT id, name, role, email, phone, notes, npi, address FROM parties {} ORDER BY name ASC",
+        filter
+    )).map_err(|e| e.to_string())?;
     
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map(params, |row| {
         Ok(PartyEntry {
             id: row.get(0)?,
             name: row.get(1)?,
@@ -440,6 +959,8 @@ pub fn get_hunt_parties(app: AppHandle, hunt_id: String) -> Result<Vec<PartyEntr
             email: row.get(3)?,
             phone: row.get(4)?,
             notes: row.get(5)?,
+            npi: row.get(6)?,
+            address: row.get(7)?,
         })
     }).map_err(|e| e.to_string())?;
 
@@ -451,35 +972,42 @@ pub fn get_hunt_parties(app: AppHandle, hunt_id: String) -> Result<Vec<PartyEntr
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn add_hunt_party(
     app: AppHandle,
+    state: State<'_, AppState>,
     hunt_id: String,
     name: String,
     role: String,
     email: String,
     phone: String,
     notes: String,
+    npi: Option<String>,
+    address: Option<String>,
 ) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
 
-    conn.execute(
-        "INSERT INTO parties (name, role, email, phone, notes) VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![name, role, email, phone, notes],
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO parties (name, role, email, phone, notes, npi, address) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![name, role, email, phone, notes, npi, address],
     ).map_err(|e| e.to_string())?;
-    Ok(())
+    search::index_record(&tx, &key, search::KIND_PARTY, &tx.last_insert_rowid().to_string())?;
+    tx.commit().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn delete_hunt_party(app: AppHandle, hunt_id: String, party_id: i64) -> Result<(), String> {
+pub fn delete_hunt_party(app: AppHandle, state: State<'_, AppState>, hunt_id: String, party_id: i64) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
 
-    conn.execute("DELETE FROM parties WHERE id = ?1", rusqlite::params![party_id])
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    trash::trash_record(&db, &key, trash::KIND_PARTY, party_id)?;
+    db.compact().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -508,20 +1036,24 @@ pub fn get_complaint_sections(app: AppHandle, hunt_id: String) -> Result<Vec<Sec
 #[tauri::command]
 pub fn save_complaint_section(
     app: AppHandle,
+    state: State<'_, AppState>,
     hunt_id: String,
     section_id: String,
     content: String,
 ) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
 
-    conn.execute(
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
         "INSERT INTO complaint_sections (section_id, content) VALUES (?1, ?2) \
          ON CONFLICT(section_id) DO UPDATE SET content = excluded.content, updated_at = CURRENT_TIMESTAMP",
         rusqlite::params![section_id, content],
     ).map_err(|e| e.to_string())?;
-    Ok(())
+    search::index_record(&tx, &key, search::KIND_SECTION, &section_id)?;
+    tx.commit().map_err(|e| e.to_string())
 }
 
 #[derive(Serialize, Deserialize)]
@@ -530,25 +1062,43 @@ pub struct EvidenceEntry {
     pub description: String,
     pub file_path: String,
     pub sha256_hash: Option<String>,
+    pub original_sha256_hash: Option<String>,
     pub created_at: String,
+    pub exhibit_number: Option<i64>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub ocr_skipped: Option<String>,
 }
 
 #[tauri::command]
 pub fn get_hunt_evidence(app: AppHandle, hunt_id: String) -> Result<Vec<EvidenceEntry>, String> {
     let vault_path = get_vault_root(&app)?;
     let db_path = vault_path.join(&hunt_id).join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
+    query_evidence(&db, "", &[])
+}
 
-    let mut stmt = conn.prepare("SELECT id, description, file_path, sha256_hash, created_at FROM evidence ORDER BY created_at ASC")
-        .map_err(|e| e.to_string())?;
+fn query_evidence(db: &HuntDatabase, filter: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<EvidenceEntry>, String> {
+    let mut tags = db.all_evidence_tags().map_err(|e| e.to_string())?;
+    let mut stmt = db.conn.prepare(&format!(
+        "SELECT id, description, file_path, sha256_hash, original_sha256_hash, created_at, exhibit_number, category, ocr_skipped \
+         FROM evidence {} ORDER BY exhibit_number ASC, created_at ASC",
+        filter
+    )).map_err(|e| e.to_string())?;
     
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map(params, |row| {
+        let id: i64 = row.get(0)?;
         Ok(EvidenceEntry {
-            id: row.get(0)?,
+            id,
             description: row.get(1)?,
             file_path: row.get(2)?,
             sha256_hash: row.get(3)?,
-            created_at: row.get(4)?,
+            original_sha256_hash: row.get(4)?,
+            created_at: row.get(5)?,
+            exhibit_number: row.get(6)?,
+            category: row.get(7)?,
+            tags: tags.remove(&id).unwrap_or_default(),
+            ocr_skipped: row.get(8)?,
         })
     }).map_err(|e| e.to_string())?;
 
@@ -566,6 +1116,7 @@ pub fn add_hunt_evidence(
     hunt_id: String,
     file_path: String,
     description: String,
+    preserve_original: Option<bool>,
 ) -> Result<(), String> {
     // 1. Check unlocked
     let key = state.get_key().ok_or("Vault Locked")?;
@@ -577,129 +1128,646 @@ pub fn add_hunt_evidence(
     }
     let file_bytes = fs::read(&path).map_err(|e| format!("Failed to read source file: {}", e))?;
 
-    // 3. Strip metadata (JPEG/PNG)
-    let scrubbed_bytes = crypto::strip_metadata(&file_bytes);
-
-    // 4. Compute SHA-256 hash
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
-    hasher.update(&scrubbed_bytes);
-    let hash_hex = format!("{:x}", hasher.finalize());
-
-    // 5. Encrypt data
-    let (encrypted_bytes, nonce) = crypto::encrypt_data(&scrubbed_bytes, &key)?;
+    let original_filename = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
 
-    // 6. Write encrypted file to vault directory
+    // 3. Scrub, hash, encrypt and index (original kept too if requested)
     let vault_path = get_vault_root(&app)?;
     let hunt_dir = vault_path.join(&hunt_id);
-    let evidence_dir = hunt_dir.join("evidence");
-    fs::create_dir_all(&evidence_dir).map_err(|e| e.to_string())?;
+    evidence::ingest_bytes(
+        &hunt_dir,
+        &key,
+        &original_filename,
+        &file_bytes,
+        &description,
+        preserve_original.unwrap_or(false),
+    )?;
 
-    let enc_filename = format!("{}.enc", hash_hex);
-    let enc_dest_path = evidence_dir.join(&enc_filename);
-    fs::write(&enc_dest_path, &encrypted_bytes).map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    // 7. Add entry to SQLite database
-    let db_path = hunt_dir.join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn add_hunt_evidence_bytes(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    filename: String,
+    file_bytes: Vec<u8>,
+    description: String,
+    preserve_original: Option<bool>,
+) -> Result<(), String> {
+    // 1. Check unlocked
+    let key = state.get_key().ok_or("Vault Locked")?;
 
-    let original_filename = path
+    // 2. Scrub, hash, encrypt and index (original kept too if requested)
+    let vault_path = get_vault_root(&app)?;
+    let hunt_dir = vault_path.join(&hunt_id);
+    evidence::ingest_bytes(
+        &hunt_dir,
+        &key,
+        &filename,
+        &file_bytes,
+        &description,
+        preserve_original.unwrap_or(false),
+    )?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn export_evidence_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    evidence_id: i64,
+    version: String,
+    target_path: String,
+) -> Result<String, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let version = EvidenceVersion::parse(&version)?;
+
+    let vault_path = get_vault_root(&app)?;
+    let hunt_dir = vault_path.join(&hunt_id);
+    let db = HuntDatabase::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+
+    let plaintext = evidence::decrypt(&hunt_dir, &db, evidence_id, version, &key)?;
+
+    let output_path = if target_path == "DOWNLOADS" {
+        let (filename, exhibit): (String, Option<i64>) = db.conn.query_row(
+            "SELECT file_path, exhibit_number FROM evidence WHERE id = ?1",
+            rusqlite::params![evidence_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).map_err(|e| e.to_string())?;
+
+        let prefix = match version {
+            EvidenceVersion::Scrubbed => "SCRUBBED",
+            EvidenceVersion::Original => "ORIGINAL",
+        };
+        let sanitized = filename.replace("/", "-").replace("\\", "-");
+        let name = match exhibit {
+            Some(n) => format!("Ex{}_{}_{}", n, prefix, sanitized),
+            None => format!("{}_{}", prefix, sanitized),
+        };
+        app.path().download_dir()
+            .map_err(|e| e.to_string())?
+            .join(name)
+    } else {
+        PathBuf::from(&target_path)
+    };
+
+    fs::write(&output_path, &plaintext).map_err(|e| e.to_string())?;
+    Ok(output_path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+pub fn delete_hunt_evidence(app: AppHandle, state: State<'_, AppState>, hunt_id: String, evidence_id: i64) -> Result<DeletionReport, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+    let db_path = vault_path.join(&hunt_id).join("metadata.db");
+    let db = HuntDatabase::open(&db_path).map_err(|e| e.to_string())?;
+
+    // Files stay encrypted on disk until the trash is emptied
+    trash::trash_record(&db, &key, trash::KIND_EVIDENCE, evidence_id)?;
+    db.compact().map_err(|e| e.to_string())?;
+
+    let mut report = DeletionReport { database_compacted: true, ..Default::default() };
+    report.note(TRASH_NOTE);
+    report.note(NO_HUNT_KEY_NOTE);
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn purge_vault_cache(app: AppHandle) -> Result<DeletionReport, String> {
+    let root = app.path().app_local_data_dir()
+        .map_err(|e| e.to_string())?;
+    let vaults = root.join("vaults");
+    let mut report = DeletionReport::default();
+    if vaults.exists() {
+        shred::shred_dir(&vaults, &mut report)?;
+    }
+    trash::empty_hunts(&trash::hunt_trash_dir(&vaults), &mut report)?;
+    shred::shred_dir(&snapshot::snapshots_root(&root), &mut report)?;
+    shred::shred_dir(&cache::cache_root(&root), &mut report)?;
+    Ok(report)
+}
+
+
+#[tauri::command]
+pub fn inspect_file_metadata(state: State<'_, AppState>, file_path: String) -> Result<MetadataReport, String> {
+    // Read-only: the file is parsed in memory and never written to the vault
+    state.get_key().ok_or("Vault Locked")?;
+
+    let path = PathBuf::from(&file_path);
+    if !path.exists() {
+        return Err("Source file does not exist".to_string());
+    }
+    let file_bytes = fs::read(&path).map_err(|e| format!("Failed to read source file: {}", e))?;
+
+    Ok(metadata::inspect_metadata(&file_bytes))
+}
+
+#[tauri::command]
+pub fn search_hunt(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+
+    let vault_path = get_vault_root(&app)?;
+    let db_path = vault_path.join(&hunt_id).join("metadata.db");
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
+
+    search::search(&db.conn, &key, &query, limit.unwrap_or(50))
+}
+
+#[tauri::command]
+pub async fn ingest_mailbox(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    file_path: String,
+    description: String,
+    create_events: Option<bool>,
+    preserve_original: Option<bool>,
+) -> Result<MailboxSummary, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+
+    let path = PathBuf::from(&file_path);
+    if !path.exists() {
+        return Err("Source file does not exist".to_string());
+    }
+    let filename = path
         .file_name()
         .and_then(|s| s.to_str())
-        .unwrap_or("unknown")
+        .unwrap_or("mailbox")
         .to_string();
 
-    conn.execute(
-        "INSERT INTO evidence (description, file_path, encrypted_key_nonce, sha256_hash) VALUES (?1, ?2, ?3, ?4)",
-        rusqlite::params![description, original_filename, nonce, hash_hex],
+    let vault_path = get_vault_root(&app)?;
+    let hunt_dir = vault_path.join(&hunt_id);
+
+    // Large mailboxes take a while; keep the IPC thread free
+    tauri::async_runtime::spawn_blocking(move || {
+        let data = fs::read(&path).map_err(|e| format!("Failed to read source file: {}", e))?;
+        let db = HuntDatabase::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+        email::ingest_mail(
+            &hunt_dir,
+            &db,
+            &key,
+            &filename,
+            &data,
+            &description,
+            create_events.unwrap_or(false),
+            preserve_original.unwrap_or(false),
+        )
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailMessageEntry {
+    pub evidence_id: i64,
+    pub message_id: Option<String>,
+    pub from_addr: Option<String>,
+    pub to_addrs: Option<String>,
+    pub cc_addrs: Option<String>,
+    pub sent_at: Option<String>,
+    pub subject: Option<String>,
+    pub attachment_ids: Vec<i64>,
+}
+
+#[tauri::command]
+pub fn get_email_messages(app: AppHandle, hunt_id: String) -> Result<Vec<EmailMessageEntry>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db_path = vault_path.join(&hunt_id).join("metadata.db");
+    let db = HuntDatabase::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = db.conn.prepare(
+        "SELECT evidence_id, message_id, from_addr, to_addrs, cc_addrs, sent_at, subject \
+         FROM email_messages ORDER BY sent_at ASC"
     ).map_err(|e| e.to_string())?;
 
-    Ok(())
+    let rows = stmt.query_map([], |row| {
+        Ok(EmailMessageEntry {
+            evidence_id: row.get(0)?,
+            message_id: row.get(1)?,
+            from_addr: row.get(2)?,
+            to_addrs: row.get(3)?,
+            cc_addrs: row.get(4)?,
+            sent_at: row.get(5)?,
+            subject: row.get(6)?,
+            attachment_ids: Vec::new(),
+        })
+    }).map_err(|e| e.to_string())?;
+
+    let mut messages = Vec::new();
+    for r in rows {
+        messages.push(r.map_err(|e| e.to_string())?);
+    }
+
+    let mut attachment_stmt = db.conn.prepare("SELECT id FROM evidence WHERE parent_evidence_id = ?1 ORDER BY id ASC")
+        .map_err(|e| e.to_string())?;
+    for message in messages.iter_mut() {
+        let ids = attachment_stmt
+            .query_map(rusqlite::params![message.evidence_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        for id in ids {
+            message.attachment_ids.push(id.map_err(|e| e.to_string())?);
+        }
+    }
+
+    Ok(messages)
 }
 
 #[tauri::command]
-pub fn add_hunt_evidence_bytes(
+pub async fn ingest_directory(
     app: AppHandle,
     state: State<'_, AppState>,
     hunt_id: String,
-    filename: String,
-    file_bytes: Vec<u8>,
+    dir_path: String,
     description: String,
-) -> Result<(), String> {
-    // 1. Check unlocked
+    preserve_original: Option<bool>,
+) -> Result<IngestSummary, String> {
     let key = state.get_key().ok_or("Vault Locked")?;
 
-    // 2. Strip metadata (JPEG/PNG)
-    let scrubbed_bytes = crypto::strip_metadata(&file_bytes);
+    let root = PathBuf::from(&dir_path);
+    if !root.is_dir() {
+        return Err("Source directory does not exist".to_string());
+    }
 
-    // 3. Compute SHA-256 hash
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
-    hasher.update(&scrubbed_bytes);
-    let hash_hex = format!("{:x}", hasher.finalize());
+    let vault_path = get_vault_root(&app)?;
+    let hunt_dir = vault_path.join(&hunt_id);
+    if !hunt_dir.exists() {
+        return Err("Hunt not found".to_string());
+    }
 
-    // 4. Encrypt data
-    let (encrypted_bytes, nonce) = crypto::encrypt_data(&scrubbed_bytes, &key)?;
+    let cancel = state.ingest_cancel.clone();
+    cancel.store(false, std::sync::atomic::Ordering::Relaxed);
 
-    // 5. Write encrypted file to vault directory
+    tauri::async_runtime::spawn_blocking(move || {
+        evidence::ingest_directory(
+            &hunt_dir,
+            &key,
+            &root,
+            &description,
+            preserve_original.unwrap_or(false),
+            &cancel,
+            |progress| {
+                let _ = app.emit("ingest-progress", progress);
+            },
+        )
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn cancel_ingest(state: State<'_, AppState>) -> Result<(), String> {
+    state.ingest_cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn run_evidence_ocr(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    evidence_id: i64,
+) -> Result<bool, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
     let vault_path = get_vault_root(&app)?;
     let hunt_dir = vault_path.join(&hunt_id);
-    let evidence_dir = hunt_dir.join("evidence");
-    fs::create_dir_all(&evidence_dir).map_err(|e| e.to_string())?;
 
-    let enc_filename = format!("{}.enc", hash_hex);
-    let enc_dest_path = evidence_dir.join(&enc_filename);
-    fs::write(&enc_dest_path, &encrypted_bytes).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let db = HuntDatabase::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+        evidence::run_ocr(&hunt_dir, &db, evidence_id, &key)
+    }).await.map_err(|e| e.to_string())?
+}
 
-    // 6. Add entry to SQLite database
-    let db_path = hunt_dir.join("metadata.db");
-    let conn = rusqlite::Connection::open(db_path).map_err(|e| e.to_string())?;
+#[derive(Serialize, Deserialize)]
+pub struct EvidencePreview {
+    pub mime_type: String,
+    pub data_base64: String,
+}
 
-    conn.execute(
-        "INSERT INTO evidence (description, file_path, encrypted_key_nonce, sha256_hash) VALUES (?1, ?2, ?3, ?4)",
-        rusqlite::params![description, filename, nonce, hash_hex],
-    ).map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn get_evidence_preview(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    evidence_id: i64,
+) -> Result<Option<EvidencePreview>, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+    let hunt_dir = vault_path.join(&hunt_id);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let db = HuntDatabase::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+        let preview = evidence::load_preview(&hunt_dir, &db, evidence_id, &key)?;
+        Ok(preview.map(|p| EvidencePreview {
+            mime_type: p.mime_type,
+            data_base64: base64::engine::general_purpose::STANDARD.encode(p.data),
+        }))
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn set_evidence_category(
+    app: AppHandle,
+    hunt_id: String,
+    evidence_id: i64,
+    category: Option<String>,
+) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    let category = category.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    db.set_evidence_category(evidence_id, category.as_deref()).map_err(|e| e.to_string())
+}
 
+#[tauri::command]
+pub fn set_evidence_tags(
+    app: AppHandle,
+    hunt_id: String,
+    evidence_id: i64,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.set_evidence_tags(evidence_id, &tags).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn reorder_exhibits(app: AppHandle, hunt_id: String, evidence_ids: Vec<i64>) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.reorder_exhibits(&evidence_ids).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn link_event_evidence(app: AppHandle, hunt_id: String, event_id: i64, evidence_id: i64) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    if !db.link_event_evidence(event_id, evidence_id).map_err(|e| e.to_string())? {
+        return Err("Event or evidence item not found".to_string());
+    }
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_hunt_evidence(app: AppHandle, hunt_id: String, evidence_id: i64) -> Result<(), String> {
+pub fn unlink_event_evidence(app: AppHandle, hunt_id: String, event_id: i64, evidence_id: i64) -> Result<(), String> {
     let vault_path = get_vault_root(&app)?;
-    let hunt_dir = vault_path.join(&hunt_id);
-    let db_path = hunt_dir.join("metadata.db");
-    let conn = rusqlite::Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.unlink_event_evidence(event_id, evidence_id).map_err(|e| e.to_string())
+}
 
-    // 1. Get SHA-256 hash from DB to delete the file
-    let mut stmt = conn.prepare("SELECT sha256_hash FROM evidence WHERE id = ?1")
-        .map_err(|e| e.to_string())?;
-    let hash_opt: Option<String> = stmt.query_row(rusqlite::params![evidence_id], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+pub fn link_event_party(app: AppHandle, hunt_id: String, event_id: i64, party_id: i64) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    if !db.link_event_party(event_id, party_id).map_err(|e| e.to_string())? {
+        return Err("Event or party not found".to_string());
+    }
+    Ok(())
+}
 
-    // 2. Delete file if it exists
-    if let Some(hash_hex) = hash_opt {
-        let enc_filename = format!("{}.enc", hash_hex);
-        let enc_path = hunt_dir.join("evidence").join(&enc_filename);
-        if enc_path.exists() {
-            let _ = fs::remove_file(enc_path);
+#[tauri::command]
+pub fn unlink_event_party(app: AppHandle, hunt_id: String, event_id: i64, party_id: i64) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.unlink_event_party(event_id, party_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_event_evidence(app: AppHandle, hunt_id: String, event_id: i64) -> Result<Vec<EvidenceEntry>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    query_evidence(&db, "WHERE id IN (SELECT evidence_id FROM event_evidence WHERE event_id = ?1)", &[&event_id])
+}
+
+#[tauri::command]
+pub fn get_event_parties(app: AppHandle, hunt_id: String, event_id: i64) -> Result<Vec<PartyEntry>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    query_parties(&db, "WHERE id IN (SELECT party_id FROM event_parties WHERE event_id = ?1)", &[&event_id])
+}
+
+#[tauri::command]
+pub fn get_evidence_events(app: AppHandle, hunt_id: String, evidence_id: i64) -> Result<Vec<EventEntry>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    query_events(&db, "WHERE id IN (SELECT event_id FROM event_evidence WHERE evidence_id = ?1)", &[&evidence_id])
+}
+
+#[tauri::command]
+pub fn get_party_events(app: AppHandle, hunt_id: String, party_id: i64) -> Result<Vec<EventEntry>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    query_events(&db, "WHERE id IN (SELECT event_id FROM event_parties WHERE party_id = ?1)", &[&party_id])
+}
+
+#[tauri::command]
+pub fn list_trash(app: AppHandle, state: State<'_, AppState>, hunt_id: String) -> Result<Vec<TrashEntry>, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    trash::list_records(&db, &key)
+}
+
+fn restore_trashed(app: &AppHandle, state: &State<'_, AppState>, hunt_id: &str, kind: &str, id: i64) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(app)?;
+    let db = HuntDatabase::open(vault_path.join(hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    trash::restore_record(&db, &key, kind, id)
+}
+
+#[tauri::command]
+pub fn restore_event(app: AppHandle, state: State<'_, AppState>, hunt_id: String, event_id: i64) -> Result<(), String> {
+    restore_trashed(&app, &state, &hunt_id, trash::KIND_EVENT, event_id)
+}
+
+#[tauri::command]
+pub fn restore_party(app: AppHandle, state: State<'_, AppState>, hunt_id: String, party_id: i64) -> Result<(), String> {
+    restore_trashed(&app, &state, &hunt_id, trash::KIND_PARTY, party_id)
+}
+
+#[tauri::command]
+pub fn restore_evidence(app: AppHandle, state: State<'_, AppState>, hunt_id: String, evidence_id: i64) -> Result<(), String> {
+    restore_trashed(&app, &state, &hunt_id, trash::KIND_EVIDENCE, evidence_id)
+}
+
+#[tauri::command]
+pub fn list_trashed_hunts(app: AppHandle, state: State<'_, AppState>) -> Result<Vec<TrashedHunt>, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+    trash::list_hunts(&trash::hunt_trash_dir(&vault_path), &key)
+}
+
+#[tauri::command]
+pub async fn restore_hunt(app: AppHandle, state: State<'_, AppState>, hunt_id: String) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+    trash::restore_hunt(&trash::hunt_trash_dir(&vault_path), &vault_path, &hunt_id, &key)
+}
+
+// Permanently deletes trashed items: one hunt's trashed records, or (without
+// a hunt id) every trashed hunt and the trashed records of every hunt
+#[tauri::command]
+pub async fn empty_trash(app: AppHandle, state: State<'_, AppState>, hunt_id: Option<String>) -> Result<DeletionReport, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let vault_path = get_vault_root(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut report = DeletionReport::default();
+        let hunt_dirs: Vec<PathBuf> = match &hunt_id {
+            Some(id) => vec![vault_path.join(id)],
+            None => fs::read_dir(&vault_path)
+                .map_err(|e| e.to_string())?
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.join("metadata.db").exists())
+                .collect(),
+        };
+
+        for hunt_dir in hunt_dirs {
+            let db = HuntDatabase::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+            trash::empty_records(&hunt_dir, &db, &key, &mut report)?;
+        }
+        if hunt_id.is_none() {
+            let trash_dir = trash::hunt_trash_dir(&vault_path);
+            // Snapshots of a hunt go with it
+            if let Some(data_root) = vault_path.parent() {
+                for hunt in trash::list_hunts(&trash_dir, &key)? {
+                    shred::shred_dir(&snapshot::snapshots_root(data_root).join(&hunt.hunt_id), &mut report)?;
+                }
+            }
+            trash::empty_hunts(&trash_dir, &mut report)?;
         }
+        report.note(NO_HUNT_KEY_NOTE);
+        Ok(report)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn create_snapshot(app: AppHandle, state: State<'_, AppState>, hunt_id: String) -> Result<SnapshotInfo, String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let data_root = get_data_root(&app)?;
+    let hunt_dir = get_vault_root(&app)?.join(&hunt_id);
+    if !hunt_dir.exists() {
+        return Err("Hunt not found".to_string());
     }
 
-    // 3. Delete from DB
-    conn.execute("DELETE FROM evidence WHERE id = ?1", rusqlite::params![evidence_id])
-        .map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let info = snapshot::create_snapshot(&data_root, &hunt_dir, &hunt_id, &key)?;
+        snapshot::apply_retention(&data_root, &hunt_id, &snapshot::load_policy(&data_root))?;
+        Ok(info)
+    }).await.map_err(|e| e.to_string())?
+}
 
-    Ok(())
+#[tauri::command]
+pub fn list_snapshots(app: AppHandle, hunt_id: String) -> Result<Vec<SnapshotInfo>, String> {
+    let data_root = get_data_root(&app)?;
+    snapshot::list_snapshots(&data_root, &hunt_id)
 }
 
 #[tauri::command]
-pub fn purge_vault_cache(app: AppHandle) -> Result<(), String> {
-    let root = app.path().app_local_data_dir()
-        .map_err(|e| e.to_string())?;
-    let vaults = root.join("vaults");
-    if vaults.exists() {
-        std::fs::remove_dir_all(&vaults).map_err(|e| e.to_string())?;
+pub async fn restore_snapshot(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hunt_id: String,
+    snapshot_id: String,
+) -> Result<(), String> {
+    let key = state.get_key().ok_or("Vault Locked")?;
+    let data_root = get_data_root(&app)?;
+    let hunt_dir = get_vault_root(&app)?.join(&hunt_id);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        snapshot::restore_snapshot(&data_root, &hunt_dir, &hunt_id, &snapshot_id, &key)
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn get_snapshot_policy(app: AppHandle) -> Result<SnapshotPolicy, String> {
+    Ok(snapshot::load_policy(&get_data_root(&app)?))
+}
+
+#[tauri::command]
+pub fn set_snapshot_policy(app: AppHandle, policy: SnapshotPolicy) -> Result<(), String> {
+    if policy.keep_last == 0 && policy.keep_daily == 0 {
+        return Err("Retention policy must keep at least one snapshot".to_string());
+    }
+    snapshot::save_policy(&get_data_root(&app)?, &policy)
+}
+
+#[tauri::command]
+pub fn list_hunt_templates(app: AppHandle) -> Result<Vec<TemplateSummary>, String> {
+    Ok(template::list(&get_data_root(&app)?))
+}
+
+#[tauri::command]
+pub fn import_hunt_template(app: AppHandle, path: String) -> Result<TemplateSummary, String> {
+    template::import(&get_data_root(&app)?, std::path::Path::new(&path))
+}
+
+// The template the hunt was created from (event types, party roles and so on),
+// or null for hunts created without one
+#[tauri::command]
+pub fn get_hunt_template(app: AppHandle, hunt_id: String) -> Result<Option<HuntTemplate>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.applied_template().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_hunt_checklist(app: AppHandle, hunt_id: String) -> Result<Vec<ChecklistItem>, String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    db.checklist().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_checklist_item(app: AppHandle, hunt_id: String, item_id: i64, done: bool) -> Result<(), String> {
+    let vault_path = get_vault_root(&app)?;
+    let db = HuntDatabase::open(vault_path.join(&hunt_id).join("metadata.db")).map_err(|e| e.to_string())?;
+    if !db.set_checklist_done(item_id, done).map_err(|e| e.to_string())? {
+        return Err("Checklist item not found".to_string());
     }
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_queries_and_report_citations() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        db.conn.execute(
+            "INSERT INTO events (title, description, event_date, event_type) VALUES ('Invoice [draft] sent', 'x', '2024-01-02', 'billing')",
+            [],
+        ).unwrap();
+        let event = db.conn.last_insert_rowid();
+        let a = db.insert_evidence("a", "a.pdf", b"n", "h1").unwrap();
+        let b = db.insert_evidence("b", "b.pdf", b"n", "h2").unwrap();
+        db.conn.execute("INSERT INTO parties (name, role) VALUES ('Jane Roe', 'Billing clerk')", []).unwrap();
+        let party = db.conn.last_insert_rowid();
+        assert!(db.link_event_evidence(event, b).unwrap());
+        assert!(db.link_event_evidence(event, a).unwrap());
+        assert!(db.link_event_party(event, party).unwrap());
+
+        let linked = query_evidence(&db, "WHERE id IN (SELECT evidence_id FROM event_evidence WHERE event_id = ?1)", &[&event]).unwrap();
+        assert_eq!(linked.iter().map(|e| e.id).collect::<Vec<_>>(), [a, b]);
+        let involved = query_parties(&db, "WHERE id IN (SELECT party_id FROM event_parties WHERE event_id = ?1)", &[&event]).unwrap();
+        assert_eq!(involved[0].name, "Jane Roe");
+        let events = query_events(&db, "WHERE id IN (SELECT event_id FROM event_evidence WHERE evidence_id = ?1)", &[&b]).unwrap();
+        assert_eq!((events[0].evidence_ids.clone(), events[0].party_ids.clone()), (vec![a, b], vec![party]));
+
+        let exhibits = HashMap::from([(a, 1), (b, 2)]);
+        let names = HashMap::from([(party, "Jane Roe (Billing clerk)".to_string())]);
+        assert_eq!(
+            timeline_entry_markup(&events[0], &exhibits, &names),
+            "- *2024-01-02 - Invoice \\[draft\\] sent*: x \\ _Supported by Ex. 1, Ex. 2._ \\ _Involving Jane Roe (Billing clerk)._\n"
+        );
+    }
+}
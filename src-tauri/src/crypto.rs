@@ -7,6 +7,7 @@ use chacha20poly1305::{
     XChaCha20Poly1305, XNonce, Key
 };
 use rand::RngCore;
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -15,12 +16,15 @@ pub struct SessionKey(pub [u8; 32]);
 
 pub struct AppState {
     pub key: Arc<Mutex<Option<SessionKey>>>,
+    // Set by `cancel_ingest` to stop a running bulk ingest
+    pub ingest_cancel: Arc<AtomicBool>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
             key: Arc::new(Mutex::new(None)),
+            ingest_cancel: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -87,6 +91,23 @@ pub fn decrypt_data(ciphertext: &[u8], nonce_bytes: &[u8], key: &SessionKey) ->
     Ok(plaintext)
 }
 
+// Self-contained encrypted file: 24-byte nonce followed by the ciphertext
+pub fn write_sealed_file(path: &std::path::Path, data: &[u8], key: &SessionKey) -> Result<(), String> {
+    let (ciphertext, nonce) = encrypt_data(data, key)?;
+    let mut out = nonce;
+    out.extend_from_slice(&ciphertext);
+    std::fs::write(path, out).map_err(|e| e.to_string())
+}
+
+pub fn read_sealed_file(path: &std::path::Path, key: &SessionKey) -> Result<Vec<u8>, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    if data.len() < 24 {
+        return Err("Encrypted file is truncated".to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(24);
+    decrypt_data(ciphertext, nonce, key)
+}
+
 pub fn strip_jpeg_metadata(data: &[u8]) -> Vec<u8> {
     if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
         return data.to_vec();
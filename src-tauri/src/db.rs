@@ -1,5 +1,36 @@
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use crate::case::{CaseRecord, CaseStatus, StatusChange};
+use crate::screening::Finding;
+use crate::template::{ChecklistItem, HuntTemplate};
+use crate::usaspending::{AwardDetail, AwardRecord, AwardSummary, AwardTransaction, RecipientProfile, Subaward};
+
+// UTC, second precision; matches the format written by the updated_at triggers
+pub fn now_timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+// Reserves the next exhibit number; numbers only ever go up
+pub fn next_exhibit_number(conn: &Connection) -> Result<i64> {
+    conn.query_row(
+        "UPDATE counters SET value = value + 1 WHERE name = 'exhibit_number' RETURNING value",
+        [],
+        |row| row.get(0),
+    )
+}
+
+// Adds an evidence row under the next exhibit number. Callers run this inside
+// a transaction so a failed insert doesn't use up a number.
+pub fn insert_evidence_row(conn: &Connection, desc: &str, file_path: &str, nonce: &[u8], sha256_hash: &str) -> Result<i64> {
+    let exhibit_number = next_exhibit_number(conn)?;
+    conn.execute(
+        "INSERT INTO evidence (description, file_path, encrypted_key_nonce, sha256_hash, exhibit_number) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![desc, file_path, nonce, sha256_hash, exhibit_number],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
 
 pub struct HuntDatabase {
     pub conn: Connection,
@@ -9,8 +40,8 @@ impl HuntDatabase {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let conn = Connection::open(path)?;
         
-        // Enable WAL mode for better concurrency/safety
-        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        // Enable WAL mode for better concurrency/safety; zero freed pages on delete
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL; PRAGMA secure_delete = ON;")?;
         
         // Init tables
         conn.execute(
@@ -28,6 +59,10 @@ impl HuntDatabase {
         // Ensure sha256_hash column is added if database already existed
         let _ = conn.execute("ALTER TABLE evidence ADD COLUMN sha256_hash TEXT", []);
 
+        // Unmodified original kept alongside the scrubbed copy (preserve-original ingest)
+        let _ = conn.execute("ALTER TABLE evidence ADD COLUMN original_sha256_hash TEXT", []);
+        let _ = conn.execute("ALTER TABLE evidence ADD COLUMN original_nonce BLOB", []);
+
         conn.execute(
             "CREATE TABLE IF NOT EXISTS events (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -61,15 +96,1018 @@ impl HuntDatabase {
             )",
             [],
         )?;
+
+        // Items split out of a container (e.g. email attachments) point at their parent
+        let _ = conn.execute("ALTER TABLE evidence ADD COLUMN parent_evidence_id INTEGER", []);
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS email_messages (
+                evidence_id INTEGER PRIMARY KEY,
+                message_id TEXT,
+                from_addr TEXT,
+                to_addrs TEXT,
+                cc_addrs TEXT,
+                sent_at TEXT,
+                subject TEXT
+            )",
+            [],
+        )?;
+
+        // Extracted evidence text, encrypted under the session key
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS evidence_text (
+                evidence_id INTEGER NOT NULL,
+                source TEXT NOT NULL, -- 'extracted' or 'ocr'
+                ciphertext BLOB NOT NULL,
+                nonce BLOB NOT NULL,
+                PRIMARY KEY (evidence_id, source)
+            )",
+            [],
+        )?;
+
+        // Full-text index over keyed token hashes (see search.rs); rowid = search_docs.id
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS search_docs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                ref_id TEXT NOT NULL,
+                UNIQUE (kind, ref_id)
+            )",
+            [],
+        )?;
+
+        // Encrypted previews live in previews/<evidence id>.enc; this holds their nonce
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS evidence_previews (
+                evidence_id INTEGER PRIMARY KEY,
+                mime_type TEXT NOT NULL,
+                nonce BLOB NOT NULL
+            )",
+            [],
+        )?;
+
+        // Categorization and stable exhibit numbers (Ex. 1, Ex. 2, ...)
+        let _ = conn.execute("ALTER TABLE evidence ADD COLUMN category TEXT", []);
+        let _ = conn.execute("ALTER TABLE evidence ADD COLUMN exhibit_number INTEGER", []);
+        // Why a scanned item has no OCR text, until OCR succeeds on it
+        let _ = conn.execute("ALTER TABLE evidence ADD COLUMN ocr_skipped TEXT", []);
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS evidence_tags (
+                evidence_id INTEGER NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (evidence_id, tag)
+            )",
+            [],
+        )?;
+        // Number pre-existing evidence after any already numbered items, in ingest order
+        let unnumbered: Vec<i64> = conn
+            .prepare("SELECT id FROM evidence WHERE exhibit_number IS NULL ORDER BY id ASC")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_>>()?;
+        for id in unnumbered {
+            conn.execute(
+                "UPDATE evidence SET exhibit_number = (SELECT COALESCE(MAX(exhibit_number), 0) + 1 FROM evidence) WHERE id = ?1",
+                params![id],
+            )?;
+        }
+        // Last exhibit number handed out, so numbers of deleted items are never reused
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS counters (name TEXT PRIMARY KEY, value INTEGER NOT NULL)",
+            [],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO counters (name, value) \
+             SELECT 'exhibit_number', COALESCE(MAX(exhibit_number), 0) FROM evidence",
+            [],
+        )?;
+
+        // Many-to-many links from timeline events to supporting evidence and involved parties
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS event_evidence (
+                event_id INTEGER NOT NULL,
+                evidence_id INTEGER NOT NULL,
+                PRIMARY KEY (event_id, evidence_id)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS event_parties (
+                event_id INTEGER NOT NULL,
+                party_id INTEGER NOT NULL,
+                PRIMARY KEY (event_id, party_id)
+            )",
+            [],
+        )?;
+
+        // Soft-deleted records, one encrypted JSON payload each (see trash.rs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS trash (
+                kind TEXT NOT NULL,
+                ref_id INTEGER NOT NULL,
+                ciphertext BLOB NOT NULL,
+                nonce BLOB NOT NULL,
+                deleted_at TEXT NOT NULL,
+                PRIMARY KEY (kind, ref_id)
+            )",
+            [],
+        )?;
+
+        // Federal awards the user tied to this case; the report's exposure value is their sum
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS target_awards (
+                generated_internal_id TEXT PRIMARY KEY,
+                date_signed TEXT,
+                description TEXT,
+                total_obligation REAL NOT NULL DEFAULT 0,
+                awarding_agency TEXT,
+                recipient_name TEXT,
+                raw_json TEXT,
+                attached_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Full detail, modification history and sub-awards fetched for an award
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS award_details (
+                generated_internal_id TEXT PRIMARY KEY,
+                detail_json TEXT NOT NULL,
+                fetched_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS award_transactions (
+                transaction_id TEXT PRIMARY KEY,
+                generated_internal_id TEXT NOT NULL,
+                modification_number TEXT,
+                action_date TEXT,
+                action_type TEXT,
+                action_type_description TEXT,
+                description TEXT,
+                federal_action_obligation REAL,
+                event_id INTEGER
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS award_subawards (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                generated_internal_id TEXT NOT NULL,
+                subaward_number TEXT,
+                action_date TEXT,
+                amount REAL,
+                recipient_name TEXT,
+                description TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(body, content='', contentless_delete=1)",
+            [],
+        )?;
+
+        // Case record (a single row) and its status history
+        conn.execute("CREATE TABLE IF NOT EXISTS info (name TEXT, created_at TEXT, status TEXT)", [])?;
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN updated_at TEXT", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN status_changed_at TEXT", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN target_entity TEXT", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN estimated_value REAL", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN jurisdiction TEXT", []);
+        // The USAspending recipient the user confirmed as the target
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN target_uei TEXT", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN target_duns TEXT", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN target_profile_json TEXT", []);
+        // The template a hunt was created from, as it was at the time
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN template_id TEXT", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN template_version INTEGER", []);
+        let _ = conn.execute("ALTER TABLE info ADD COLUMN template_json TEXT", []);
+        // Identifiers used to screen parties against provider registries
+        let _ = conn.execute("ALTER TABLE parties ADD COLUMN npi TEXT", []);
+        let _ = conn.execute("ALTER TABLE parties ADD COLUMN address TEXT", []);
+        // Data sources that reported each award (JSON array)
+        let _ = conn.execute("ALTER TABLE target_awards ADD COLUMN sources_json TEXT", []);
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS status_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                from_status TEXT,
+                to_status TEXT NOT NULL,
+                changed_at TEXT NOT NULL,
+                note TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS checklist_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                text TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                done INTEGER NOT NULL DEFAULT 0,
+                done_at TEXT
+            )",
+            [],
+        )?;
+
+        // Hits from screening the target and parties against reference lists
+        // (see screening.rs); subject_id is '' for the target
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS findings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                check_kind TEXT NOT NULL,
+                severity TEXT NOT NULL,
+                subject_type TEXT NOT NULL,
+                subject_id TEXT NOT NULL DEFAULT '',
+                subject_name TEXT NOT NULL,
+                source TEXT NOT NULL,
+                record_key TEXT NOT NULL,
+                source_ref TEXT NOT NULL,
+                matched_on_json TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                detail_json TEXT,
+                status TEXT NOT NULL DEFAULT 'open',
+                found_at TEXT NOT NULL,
+                UNIQUE(check_kind, subject_type, subject_id, source, record_key)
+            )",
+            [],
+        )?;
+
+        // Any change to the case contents bumps info.updated_at
+        for table in ["events", "parties", "evidence", "complaint_sections", "target_awards", "checklist_items", "findings"] {
+            for op in ["INSERT", "UPDATE", "DELETE"] {
+                conn.execute(
+                    &format!(
+                        "CREATE TRIGGER IF NOT EXISTS touch_{table}_{op} AFTER {op} ON {table} \
+                         BEGIN UPDATE info SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'); END",
+                        table = table,
+                        op = op.to_lowercase(),
+                    ),
+                    [],
+                )?;
+            }
+        }
         
         Ok(Self { conn })
     }
 
+    // Flushes the WAL into the main file and truncates it, then rebuilds the
+    // file so deleted rows are not left behind in free pages
+    pub fn compact(&self) -> Result<()> {
+        self.conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE); VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")
+    }
+
+    // --- Case record ---
+
+    pub fn init_case(&self, name: &str) -> Result<String> {
+        let now = now_timestamp();
+        self.conn.execute(
+            "INSERT INTO info (name, created_at, updated_at, status, status_changed_at) VALUES (?1, ?2, ?2, ?3, ?2)",
+            params![name, now, CaseStatus::Draft.as_str()],
+        )?;
+        self.conn.execute(
+            "INSERT INTO status_history (from_status, to_status, changed_at) VALUES (NULL, ?1, ?2)",
+            params![CaseStatus::Draft.as_str(), now],
+        )?;
+        Ok(now)
+    }
+
+    pub fn case_record(&self) -> Result<Option<CaseRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, status, created_at, updated_at, status_changed_at, target_entity, estimated_value, jurisdiction \
+             FROM info LIMIT 1",
+        )?;
+        let mut rows = stmt.query([])?;
+        let Some(row) = rows.next()? else { return Ok(None) };
+
+        let status: Option<String> = row.get(1)?;
+        Ok(Some(CaseRecord {
+            name: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+            // Older or hand-edited rows fall back to Draft
+            status: status.and_then(|s| CaseStatus::parse(&s).ok()).unwrap_or(CaseStatus::Draft),
+            created_at: row.get(2)?,
+            updated_at: row.get(3)?,
+            status_changed_at: row.get(4)?,
+            target_entity: row.get(5)?,
+            estimated_value: row.get(6)?,
+            jurisdiction: row.get(7)?,
+        }))
+    }
+
+    pub fn set_case_name(&self, name: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE info SET name = ?1, updated_at = ?2",
+            params![name, now_timestamp()],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_case_details(&self, target_entity: Option<&str>, estimated_value: Option<f64>, jurisdiction: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE info SET target_entity = ?1, estimated_value = ?2, jurisdiction = ?3, updated_at = ?4",
+            params![target_entity, estimated_value, jurisdiction, now_timestamp()],
+        )?;
+        Ok(())
+    }
+
+    // Records the change in status_history; callers validate the transition
+    pub fn set_case_status(&self, from: CaseStatus, to: CaseStatus, note: Option<&str>) -> Result<()> {
+        let now = now_timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE info SET status = ?1, status_changed_at = ?2, updated_at = ?2",
+            params![to.as_str(), now],
+        )?;
+        tx.execute(
+            "INSERT INTO status_history (from_status, to_status, changed_at, note) VALUES (?1, ?2, ?3, ?4)",
+            params![from.as_str(), to.as_str(), now, note],
+        )?;
+        tx.commit()
+    }
+
+    pub fn status_history(&self) -> Result<Vec<StatusChange>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT from_status, to_status, changed_at, note FROM status_history ORDER BY id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(StatusChange {
+                from_status: row.get(0)?,
+                to_status: row.get(1)?,
+                changed_at: row.get(2)?,
+                note: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    // Records a confirmed recipient; its legal name becomes the target entity
+    pub fn set_target_profile(&self, profile: &RecipientProfile) -> Result<()> {
+        let json = serde_json::to_string(profile).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.conn.execute(
+            "UPDATE info SET target_entity = ?1, target_uei = ?2, target_duns = ?3, target_profile_json = ?4, updated_at = ?5",
+            params![profile.name, profile.uei, profile.duns, json, now_timestamp()],
+        )?;
+        Ok(())
+    }
+
+    pub fn target_profile(&self) -> Result<Option<RecipientProfile>> {
+        let json: Option<String> = self.conn
+            .query_row("SELECT target_profile_json FROM info LIMIT 1", [], |r| r.get(0))
+            .optional()?
+            .flatten();
+        Ok(json.and_then(|j| serde_json::from_str(&j).ok()))
+    }
+
+    // --- Templates ---
+
+    // Seeds complaint sections and checklist items and records the template on
+    // the case record; sections already drafted are left alone
+    pub fn apply_template(&self, template: &HuntTemplate) -> Result<()> {
+        let json = serde_json::to_string(template).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let tx = self.conn.unchecked_transaction()?;
+        for (section_id, content) in &template.complaint_sections {
+            tx.execute(
+                "INSERT OR IGNORE INTO complaint_sections (section_id, content) VALUES (?1, ?2)",
+                params![section_id, content],
+            )?;
+        }
+        for text in &template.checklist {
+            tx.execute(
+                "INSERT INTO checklist_items (text, position) \
+                 VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM checklist_items))",
+                params![text],
+            )?;
+        }
+        tx.execute(
+            "UPDATE info SET template_id = ?1, template_version = ?2, template_json = ?3",
+            params![template.id, template.version, json],
+        )?;
+        tx.commit()
+    }
+
+    pub fn applied_template(&self) -> Result<Option<HuntTemplate>> {
+        let json: Option<String> = self.conn
+            .query_row("SELECT template_json FROM info LIMIT 1", [], |r| r.get(0))
+            .optional()?
+            .flatten();
+        Ok(json.and_then(|j| serde_json::from_str(&j).ok()))
+    }
+
+    pub fn checklist(&self) -> Result<Vec<ChecklistItem>> {
+        let mut stmt = self.conn.prepare("SELECT id, text, done, done_at FROM checklist_items ORDER BY position ASC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ChecklistItem {
+                id: row.get(0)?,
+                text: row.get(1)?,
+                done: row.get(2)?,
+                done_at: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    // Returns false if the item does not exist
+    pub fn set_checklist_done(&self, item_id: i64, done: bool) -> Result<bool> {
+        let done_at = done.then(now_timestamp);
+        let n = self.conn.execute(
+            "UPDATE checklist_items SET done = ?1, done_at = ?2 WHERE id = ?3",
+            params![done, done_at, item_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    // --- Target awards ---
+
+    // Re-attaching an award refreshes its fields but keeps the original attach time
+    pub fn attach_award(&self, award: &AwardSummary) -> Result<()> {
+        let raw = award.raw.as_ref().map(|v| v.to_string());
+        let sources = (!award.sources.is_empty()).then(|| serde_json::json!(award.sources).to_string());
+        self.conn.execute(
+            "INSERT INTO target_awards \
+             (generated_internal_id, date_signed, description, total_obligation, awarding_agency, recipient_name, raw_json, attached_at, sources_json) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9) \
+             ON CONFLICT(generated_internal_id) DO UPDATE SET \
+             date_signed = excluded.date_signed, description = excluded.description, \
+             total_obligation = excluded.total_obligation, awarding_agency = excluded.awarding_agency, \
+             recipient_name = excluded.recipient_name, raw_json = COALESCE(excluded.raw_json, raw_json), \
+             sources_json = COALESCE(excluded.sources_json, sources_json)",
+            params![
+                award.generated_internal_id,
+                award.date_signed,
+                award.description,
+                award.total_obligation,
+                award.awarding_agency,
+                award.recipient_name,
+                raw,
+                now_timestamp(),
+                sources
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Returns false if the award was not attached
+    pub fn detach_award(&self, generated_internal_id: &str) -> Result<bool> {
+        let n = self.conn.execute(
+            "DELETE FROM target_awards WHERE generated_internal_id = ?1",
+            params![generated_internal_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    // Newest award first
+    pub fn target_awards(&self) -> Result<Vec<AwardSummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT generated_internal_id, date_signed, description, total_obligation, awarding_agency, recipient_name, raw_json, sources_json \
+             FROM target_awards ORDER BY date_signed DESC, generated_internal_id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let raw: Option<String> = row.get(6)?;
+            let sources: Option<String> = row.get(7)?;
+            Ok(AwardSummary {
+                generated_internal_id: row.get(0)?,
+                date_signed: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                description: row.get(2)?,
+                total_obligation: row.get(3)?,
+                awarding_agency: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                recipient_name: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                raw: raw.and_then(|s| serde_json::from_str(&s).ok()),
+                sources: sources.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+            })
+        })?;
+        rows.collect()
+    }
+
+    // Re-recording the same hit refreshes it but keeps the user's status
+    pub fn record_finding(&self, finding: &Finding) -> Result<i64> {
+        let matched_on = serde_json::json!(finding.matched_on).to_string();
+        let subject_id = finding.subject_id.clone().unwrap_or_default();
+        self.conn.execute(
+            "INSERT INTO findings \
+             (check_kind, severity, subject_type, subject_id, subject_name, source, record_key, source_ref, matched_on_json, summary, detail_json, found_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12) \
+             ON CONFLICT(check_kind, subject_type, subject_id, source, record_key) DO UPDATE SET \
+             severity = excluded.severity, subject_name = excluded.subject_name, source_ref = excluded.source_ref, \
+             matched_on_json = excluded.matched_on_json, summary = excluded.summary, detail_json = excluded.detail_json, \
+             found_at = excluded.found_at",
+            params![
+                finding.check,
+                finding.severity,
+                finding.subject_type,
+                subject_id,
+                finding.subject_name,
+                finding.source,
+                finding.record_key,
+                finding.source_ref,
+                matched_on,
+                finding.summary,
+                finding.detail.to_string(),
+                finding.found_at
+            ],
+        )?;
+        self.conn.query_row(
+            "SELECT id FROM findings WHERE check_kind = ?1 AND subject_type = ?2 AND subject_id = ?3 AND source = ?4 AND record_key = ?5",
+            params![finding.check, finding.subject_type, subject_id, finding.source, finding.record_key],
+            |r| r.get(0),
+        )
+    }
+
+    // Most severe first, then newest
+    pub fn findings(&self) -> Result<Vec<Finding>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, check_kind, severity, subject_type, subject_id, subject_name, source, record_key, source_ref, \
+             matched_on_json, summary, detail_json, status, found_at FROM findings \
+             ORDER BY CASE severity WHEN 'high' THEN 0 WHEN 'medium' THEN 1 ELSE 2 END, found_at DESC, id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let subject_id: String = row.get(4)?;
+            let matched_on: String = row.get(9)?;
+            let detail: Option<String> = row.get(11)?;
+            Ok(Finding {
+                id: row.get(0)?,
+                check: row.get(1)?,
+                severity: row.get(2)?,
+                subject_type: row.get(3)?,
+                subject_id: (!subject_id.is_empty()).then_some(subject_id),
+                subject_name: row.get(5)?,
+                source: row.get(6)?,
+                record_key: row.get(7)?,
+                source_ref: row.get(8)?,
+                matched_on: serde_json::from_str(&matched_on).unwrap_or_default(),
+                summary: row.get(10)?,
+                detail: detail.and_then(|d| serde_json::from_str(&d).ok()).unwrap_or_default(),
+                status: row.get(12)?,
+                found_at: row.get(13)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    // Returns false if there is no such finding
+    pub fn set_finding_status(&self, id: i64, status: &str) -> Result<bool> {
+        let changed = self.conn.execute("UPDATE findings SET status = ?1 WHERE id = ?2", params![status, id])?;
+        Ok(changed > 0)
+    }
+
+    pub fn award_exposure(&self) -> Result<f64> {
+        self.conn.query_row("SELECT COALESCE(SUM(total_obligation), 0) FROM target_awards", [], |r| r.get(0))
+    }
+
+    // Replaces the stored detail and sub-awards; transactions already plotted
+    // on the timeline keep their event link
+    pub fn store_award_record(&self, record: &AwardRecord) -> Result<String> {
+        let id = &record.detail.generated_internal_id;
+        let json = serde_json::to_string(&record.detail).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let now = now_timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO award_details (generated_internal_id, detail_json, fetched_at) VALUES (?1, ?2, ?3)",
+            params![id, json, now],
+        )?;
+        for t in &record.transactions {
+            tx.execute(
+                "INSERT INTO award_transactions \
+                 (transaction_id, generated_internal_id, modification_number, action_date, action_type, action_type_description, description, federal_action_obligation) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8) \
+                 ON CONFLICT(transaction_id) DO UPDATE SET \
+                 modification_number = excluded.modification_number, action_date = excluded.action_date, \
+                 action_type = excluded.action_type, action_type_description = excluded.action_type_description, \
+                 description = excluded.description, federal_action_obligation = excluded.federal_action_obligation",
+                params![
+                    t.transaction_id,
+                    id,
+                    t.modification_number,
+                    t.action_date,
+                    t.action_type,
+                    t.action_type_description,
+                    t.description,
+                    t.federal_action_obligation
+                ],
+            )?;
+        }
+        tx.execute("DELETE FROM award_subawards WHERE generated_internal_id = ?1", params![id])?;
+        for sub in &record.subawards {
+            tx.execute(
+                "INSERT INTO award_subawards (generated_internal_id, subaward_number, action_date, amount, recipient_name, description) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![id, sub.subaward_number, sub.action_date, sub.amount, sub.recipient_name, sub.description],
+            )?;
+        }
+        tx.commit()?;
+        Ok(now)
+    }
+
+    pub fn award_record(&self, generated_internal_id: &str) -> Result<Option<AwardRecord>> {
+        let stored: Option<(String, String)> = self.conn
+            .query_row(
+                "SELECT detail_json, fetched_at FROM award_details WHERE generated_internal_id = ?1",
+                params![generated_internal_id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?;
+        let Some((json, fetched_at)) = stored else { return Ok(None) };
+        let detail: AwardDetail = serde_json::from_str(&json)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?;
+
+        // A plotted event that has since been deleted no longer counts as plotted
+        let mut stmt = self.conn.prepare(
+            "SELECT t.transaction_id, t.modification_number, t.action_date, t.action_type, t.action_type_description, \
+             t.description, t.federal_action_obligation, e.id \
+             FROM award_transactions t LEFT JOIN events e ON e.id = t.event_id \
+             WHERE t.generated_internal_id = ?1 ORDER BY t.action_date ASC, t.modification_number ASC",
+        )?;
+        let transactions = stmt
+            .query_map(params![generated_internal_id], |row| {
+                Ok(AwardTransaction {
+                    transaction_id: row.get(0)?,
+                    modification_number: row.get(1)?,
+                    action_date: row.get(2)?,
+                    action_type: row.get(3)?,
+                    action_type_description: row.get(4)?,
+                    description: row.get(5)?,
+                    federal_action_obligation: row.get(6)?,
+                    event_id: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT subaward_number, action_date, amount, recipient_name, description FROM award_subawards \
+             WHERE generated_internal_id = ?1 ORDER BY id ASC",
+        )?;
+        let subawards = stmt
+            .query_map(params![generated_internal_id], |row| {
+                Ok(Subaward {
+                    subaward_number: row.get(0)?,
+                    action_date: row.get(1)?,
+                    amount: row.get(2)?,
+                    recipient_name: row.get(3)?,
+                    description: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(AwardRecord { detail, transactions, subawards, fetched_at: Some(fetched_at) }))
+    }
+
+    // Adds a timeline event for each stored modification of the award that
+    // isn't on the timeline yet; returns the new events' ids
+    pub fn plot_award_transactions(&self, generated_internal_id: &str) -> Result<Vec<i64>> {
+        let Some(record) = self.award_record(generated_internal_id)? else { return Ok(Vec::new()) };
+        let award_label = record.detail.award_id.clone().unwrap_or_else(|| generated_internal_id.to_string());
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut added = Vec::new();
+        for t in record.transactions.iter().filter(|t| t.event_id.is_none()) {
+            let Some(date) = &t.action_date else { continue };
+            let title = match t.modification_number.as_deref() {
+                Some(m) if m != "0" => format!("Award {} modification {}", award_label, m),
+                _ => format!("Award {} base action", award_label),
+            };
+            let mut parts = Vec::new();
+            if let Some(kind) = t.action_type_description.as_ref().or(t.action_type.as_ref()) {
+                parts.push(kind.clone());
+            }
+            if let Some(amount) = t.federal_action_obligation {
+                parts.push(format!("obligation ${:.2}", amount));
+            }
+            if let Some(desc) = &t.description {
+                parts.push(desc.clone());
+            }
+
+            tx.execute(
+                "INSERT INTO events (title, description, event_date, event_type) VALUES (?1, ?2, ?3, 'award_modification')",
+                params![title, parts.join("; "), date],
+            )?;
+            let event_id = tx.last_insert_rowid();
+            tx.execute(
+                "UPDATE award_transactions SET event_id = ?1 WHERE transaction_id = ?2",
+                params![event_id, t.transaction_id],
+            )?;
+            added.push(event_id);
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    // --- Evidence ---
+
     pub fn insert_evidence(&self, desc: &str, file_path: &str, nonce: &[u8], sha256_hash: &str) -> Result<i64> {
+        let tx = self.conn.unchecked_transaction()?;
+        let id = insert_evidence_row(&tx, desc, file_path, nonce, sha256_hash)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    pub fn set_evidence_original(&self, evidence_id: i64, sha256_hash: &str, nonce: &[u8]) -> Result<()> {
+        self.conn.execute(
+            "UPDATE evidence SET original_sha256_hash = ?1, original_nonce = ?2 WHERE id = ?3",
+            params![sha256_hash, nonce, evidence_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_evidence_ocr_skipped(&self, evidence_id: i64, reason: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE evidence SET ocr_skipped = ?1 WHERE id = ?2",
+            params![reason, evidence_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_evidence_parent(&self, evidence_id: i64, parent_id: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE evidence SET parent_evidence_id = ?1 WHERE id = ?2",
+            params![parent_id, evidence_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_evidence_category(&self, evidence_id: i64, category: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE evidence SET category = ?1 WHERE id = ?2",
+            params![category, evidence_id],
+        )?;
+        Ok(())
+    }
+
+    // Replaces the item's tag set; tags are trimmed, lowercased and de-duplicated
+    pub fn set_evidence_tags(&self, evidence_id: i64, tags: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM evidence_tags WHERE evidence_id = ?1", params![evidence_id])?;
+        for tag in tags {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() {
+                tx.execute(
+                    "INSERT OR IGNORE INTO evidence_tags (evidence_id, tag) VALUES (?1, ?2)",
+                    params![evidence_id, tag],
+                )?;
+            }
+        }
+        tx.commit()
+    }
+
+    pub fn all_evidence_tags(&self) -> Result<HashMap<i64, Vec<String>>> {
+        let mut stmt = self.conn.prepare("SELECT evidence_id, tag FROM evidence_tags ORDER BY tag ASC")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for r in rows {
+            let (id, tag) = r?;
+            tags.entry(id).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
+    // Renumbers exhibits: the given ids become Ex. 1..n in that order and all
+    // other items follow in their current order. Numbers never change otherwise.
+    pub fn reorder_exhibits(&self, ordered_ids: &[i64]) -> Result<()> {
+        let mut stmt = self.conn.prepare("SELECT id FROM evidence ORDER BY exhibit_number ASC, id ASC")?;
+        let current: Vec<i64> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_>>()?;
+
+        let mut seen = HashSet::new();
+        let mut order: Vec<i64> = ordered_ids
+            .iter()
+            .copied()
+            .filter(|id| current.contains(id) && seen.insert(*id))
+            .collect();
+        order.extend(current.iter().copied().filter(|id| !seen.contains(id)));
+
+        let tx = self.conn.unchecked_transaction()?;
+        for (i, id) in order.iter().enumerate() {
+            tx.execute(
+                "UPDATE evidence SET exhibit_number = ?1 WHERE id = ?2",
+                params![i as i64 + 1, id],
+            )?;
+        }
+        tx.execute(
+            "UPDATE counters SET value = MAX(value, ?1) WHERE name = 'exhibit_number'",
+            params![order.len() as i64],
+        )?;
+        tx.commit()
+    }
+
+    // Returns false, linking nothing, if the event or the evidence item does not exist
+    pub fn link_event_evidence(&self, event_id: i64, evidence_id: i64) -> Result<bool> {
+        if !self.row_exists("events", event_id)? || !self.row_exists("evidence", evidence_id)? {
+            return Ok(false);
+        }
+        self.conn.execute(
+            "INSERT OR IGNORE INTO event_evidence (event_id, evidence_id) VALUES (?1, ?2)",
+            params![event_id, evidence_id],
+        )?;
+        Ok(true)
+    }
+
+    pub fn unlink_event_evidence(&self, event_id: i64, evidence_id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM event_evidence WHERE event_id = ?1 AND evidence_id = ?2",
+            params![event_id, evidence_id],
+        )?;
+        Ok(())
+    }
+
+    // Returns false, linking nothing, if the event or the party does not exist
+    pub fn link_event_party(&self, event_id: i64, party_id: i64) -> Result<bool> {
+        if !self.row_exists("events", event_id)? || !self.row_exists("parties", party_id)? {
+            return Ok(false);
+        }
+        self.conn.execute(
+            "INSERT OR IGNORE INTO event_parties (event_id, party_id) VALUES (?1, ?2)",
+            params![event_id, party_id],
+        )?;
+        Ok(true)
+    }
+
+    fn row_exists(&self, table: &str, id: i64) -> Result<bool> {
+        self.conn.query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", table),
+            params![id],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn unlink_event_party(&self, event_id: i64, party_id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM event_parties WHERE event_id = ?1 AND party_id = ?2",
+            params![event_id, party_id],
+        )?;
+        Ok(())
+    }
+
+    // event_id -> linked evidence ids
+    pub fn event_evidence_links(&self) -> Result<HashMap<i64, Vec<i64>>> {
+        self.link_map("SELECT event_id, evidence_id FROM event_evidence ORDER BY event_id, evidence_id")
+    }
+
+    // event_id -> linked party ids
+    pub fn event_party_links(&self) -> Result<HashMap<i64, Vec<i64>>> {
+        self.link_map("SELECT event_id, party_id FROM event_parties ORDER BY event_id, party_id")
+    }
+
+    fn link_map(&self, sql: &str) -> Result<HashMap<i64, Vec<i64>>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+        let mut links: HashMap<i64, Vec<i64>> = HashMap::new();
+        for r in rows {
+            let (event_id, other) = r?;
+            links.entry(event_id).or_default().push(other);
+        }
+        Ok(links)
+    }
+
+    pub fn set_evidence_preview(&self, evidence_id: i64, mime_type: &str, nonce: &[u8]) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO evidence (description, file_path, encrypted_key_nonce, sha256_hash) VALUES (?1, ?2, ?3, ?4)",
-            params![desc, file_path, nonce, sha256_hash],
+            "INSERT OR REPLACE INTO evidence_previews (evidence_id, mime_type, nonce) VALUES (?1, ?2, ?3)",
+            params![evidence_id, mime_type, nonce],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert_email_message(
+        &self,
+        evidence_id: i64,
+        message_id: &str,
+        from: &str,
+        to: &str,
+        cc: &str,
+        sent_at: Option<&str>,
+        subject: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO email_messages (evidence_id, message_id, from_addr, to_addrs, cc_addrs, sent_at, subject) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![evidence_id, message_id, from, to, cc, sent_at, subject],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exhibit_numbers_are_stable_and_reorderable() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let a = db.insert_evidence("a", "a.pdf", b"n", "h1").unwrap();
+        let b = db.insert_evidence("b", "b.pdf", b"n", "h2").unwrap();
+        let c = db.insert_evidence("c", "c.pdf", b"n", "h3").unwrap();
+        let number = |id: i64| -> i64 {
+            db.conn.query_row("SELECT exhibit_number FROM evidence WHERE id = ?1", params![id], |r| r.get(0)).unwrap()
+        };
+
+        // Deleting an exhibit leaves a gap rather than renumbering the rest
+        db.conn.execute("DELETE FROM evidence WHERE id = ?1", params![b]).unwrap();
+        let d = db.insert_evidence("d", "d.pdf", b"n", "h4").unwrap();
+        assert_eq!((number(a), number(c), number(d)), (1, 3, 4));
+
+        // The number of the last exhibit is not handed out again either
+        db.conn.execute("DELETE FROM evidence WHERE id = ?1", params![d]).unwrap();
+        let e = db.insert_evidence("e", "e.pdf", b"n", "h5").unwrap();
+        assert_eq!(number(e), 5);
+
+        db.reorder_exhibits(&[e, 999]).unwrap();
+        assert_eq!((number(e), number(a), number(c)), (1, 2, 3));
+
+        // Repeated ids keep their first position
+        db.reorder_exhibits(&[c, a, c]).unwrap();
+        assert_eq!((number(c), number(a), number(e)), (1, 2, 3));
+        let f = db.insert_evidence("f", "f.pdf", b"n", "h6").unwrap();
+        assert_eq!(number(f), 6);
+    }
+
+    #[test]
+    fn test_event_links() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        db.conn.execute(
+            "INSERT INTO events (title, description, event_date, event_type) VALUES ('Invoice sent', 'x', '2024-01-02', 'billing')",
+            [],
+        ).unwrap();
+        let event = db.conn.last_insert_rowid();
+        let evidence = db.insert_evidence("a", "a.pdf", b"n", "h1").unwrap();
+        db.conn.execute("INSERT INTO parties (name, role) VALUES ('Jane Roe', 'Witness')", []).unwrap();
+        let party = db.conn.last_insert_rowid();
+
+        // Linking twice has no effect; unknown ids are refused
+        assert!(db.link_event_evidence(event, evidence).unwrap());
+        assert!(db.link_event_evidence(event, evidence).unwrap());
+        assert!(db.link_event_party(event, party).unwrap());
+        assert!(!db.link_event_evidence(event, 999).unwrap());
+        assert!(!db.link_event_evidence(999, evidence).unwrap());
+        assert!(!db.link_event_party(event, 999).unwrap());
+        assert_eq!(db.event_evidence_links().unwrap(), HashMap::from([(event, vec![evidence])]));
+        assert_eq!(db.event_party_links().unwrap(), HashMap::from([(event, vec![party])]));
+
+        db.unlink_event_evidence(event, evidence).unwrap();
+        db.unlink_event_party(event, party).unwrap();
+        assert!(db.event_evidence_links().unwrap().is_empty());
+        assert!(db.event_party_links().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_target_awards_drive_exposure() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let award = |id: &str, value: f64| AwardSummary {
+            generated_internal_id: id.to_string(),
+            date_signed: "2021-03-01".to_string(),
+            description: None,
+            total_obligation: value,
+            awarding_agency: "USDA".to_string(),
+            recipient_name: "Acme".to_string(),
+            raw: Some(serde_json::json!({ "Award ID": id })),
+            sources: vec!["usaspending".to_string()],
+        };
+        db.attach_award(&award("A1", 100.0)).unwrap();
+        db.attach_award(&award("A2", 250.5)).unwrap();
+        // Re-attaching updates rather than duplicating
+        db.attach_award(&award("A1", 150.0)).unwrap();
+        assert_eq!(db.award_exposure().unwrap(), 400.5);
+        assert_eq!(db.target_awards().unwrap()[0].raw.as_ref().unwrap()["Award ID"], "A1");
+        assert_eq!(db.target_awards().unwrap()[0].sources, ["usaspending"]);
+
+        assert!(db.detach_award("A2").unwrap());
+        assert!(!db.detach_award("A2").unwrap());
+        assert_eq!(db.award_exposure().unwrap(), 150.0);
+    }
+
+    #[test]
+    fn test_award_modifications_plot_once() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let detail: AwardDetail = serde_json::from_value(serde_json::json!({
+            "generated_internal_id": "CONT_AWD_1", "award_id": "W91",
+            "place_of_performance": {}
+        })).unwrap();
+        let transaction = |id: &str, m: &str, date: &str| AwardTransaction {
+            transaction_id: id.to_string(),
+            modification_number: Some(m.to_string()),
+            action_date: Some(date.to_string()),
+            action_type: None,
+            action_type_description: Some("SUPPLEMENTAL AGREEMENT".to_string()),
+            description: None,
+            federal_action_obligation: Some(10.0),
+            event_id: None,
+        };
+        let record = AwardRecord {
+            detail,
+            transactions: vec![transaction("T0", "0", "2020-01-01"), transaction("T1", "P00001", "2020-06-01")],
+            subawards: vec![],
+            fetched_at: None,
+        };
+        db.store_award_record(&record).unwrap();
+
+        assert_eq!(db.plot_award_transactions("CONT_AWD_1").unwrap().len(), 2);
+        assert_eq!(db.plot_award_transactions("CONT_AWD_1").unwrap().len(), 0);
+        let title: String = db.conn
+            .query_row("SELECT title FROM events WHERE event_date = '2020-06-01'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(title, "Award W91 modification P00001");
+
+        // Refetching keeps the link; deleting the event makes it plottable again
+        db.store_award_record(&record).unwrap();
+        assert_eq!(db.plot_award_transactions("CONT_AWD_1").unwrap().len(), 0);
+        db.conn.execute("DELETE FROM events WHERE event_date = '2020-01-01'", []).unwrap();
+        assert_eq!(db.plot_award_transactions("CONT_AWD_1").unwrap().len(), 1);
     }
 }
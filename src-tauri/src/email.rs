@@ -0,0 +1,628 @@
+use base64::Engine;
+use serde::Serialize;
+use std::path::Path;
+use crate::crypto::SessionKey;
+use crate::db::HuntDatabase;
+use crate::{evidence, search};
+use crate::metadata::latin1_to_string;
+
+// Minimal RFC 5322 / MIME reader for exported mail evidence.
+// Only what is needed to recover headers, readable bodies and attachments.
+
+#[derive(Debug, Default)]
+pub struct ParsedMessage {
+    pub headers: Vec<(String, String)>,
+    pub text_body: Option<String>,
+    pub html_body: Option<String>,
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Debug)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl ParsedMessage {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+pub fn parse_message(raw: &[u8]) -> ParsedMessage {
+    let mut message = ParsedMessage::default();
+    let (headers, body) = split_headers(raw);
+    message.headers = headers;
+    let headers = message.headers.clone();
+    walk_part(&headers, body, &mut message);
+    message
+}
+
+fn split_headers(raw: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let (head, body) = match find_blank_line(raw) {
+        Some((head_end, body_start)) => (&raw[..head_end], &raw[body_start..]),
+        None => (raw, &raw[raw.len()..]),
+    };
+
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in latin1_or_utf8(head).lines() {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            // Folded continuation of the previous header
+            if let Some(last) = headers.last_mut() {
+                last.1.push(' ');
+                last.1.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    for header in headers.iter_mut() {
+        header.1 = decode_encoded_words(&header.1);
+    }
+
+    (headers, body)
+}
+
+fn find_blank_line(raw: &[u8]) -> Option<(usize, usize)> {
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i..].starts_with(b"\r\n\r\n") {
+            return Some((i, i + 4));
+        }
+        if raw[i..].starts_with(b"\n\n") {
+            return Some((i, i + 2));
+        }
+        i += 1;
+    }
+    None
+}
+
+fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+// Splits `type/subtype; key=value; ...` into the lowercased type and a parameter lookup
+fn parse_content_value(value: &str) -> (String, Vec<(String, String)>) {
+    let mut parts = value.split(';');
+    let main = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+    let params = parts
+        .filter_map(|p| {
+            let (k, v) = p.split_once('=')?;
+            Some((k.trim().to_ascii_lowercase(), v.trim().trim_matches('"').to_string()))
+        })
+        .collect();
+    (main, params)
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+}
+
+fn walk_part(headers: &[(String, String)], body: &[u8], message: &mut ParsedMessage) {
+    let (content_type, type_params) = parse_content_value(header_value(headers, "Content-Type").unwrap_or("text/plain"));
+    let (disposition, disposition_params) = parse_content_value(header_value(headers, "Content-Disposition").unwrap_or(""));
+
+    if content_type.starts_with("multipart/") {
+        if let Some(boundary) = param(&type_params, "boundary") {
+            for part in split_multipart(body, boundary) {
+                let (part_headers, part_body) = split_headers(part);
+                walk_part(&part_headers, part_body, message);
+            }
+        }
+        return;
+    }
+
+    let encoding = header_value(headers, "Content-Transfer-Encoding").unwrap_or("").to_ascii_lowercase();
+    let data = decode_transfer(body, &encoding);
+
+    let filename = param(&disposition_params, "filename")
+        .or_else(|| param(&type_params, "name"))
+        .map(decode_encoded_words);
+
+    let is_attachment = disposition == "attachment" || filename.is_some() || content_type == "message/rfc822";
+    if is_attachment {
+        let filename = filename.unwrap_or_else(|| {
+            if content_type == "message/rfc822" {
+                "attached_message.eml".to_string()
+            } else {
+                format!("attachment_{}", message.attachments.len() + 1)
+            }
+        });
+        message.attachments.push(Attachment { filename, content_type, data });
+        return;
+    }
+
+    let charset = param(&type_params, "charset").unwrap_or("utf-8");
+    if content_type == "text/html" {
+        if message.html_body.is_none() {
+            message.html_body = Some(decode_charset(&data, charset));
+        }
+    } else if content_type.starts_with("text/") && message.text_body.is_none() {
+        message.text_body = Some(decode_charset(&data, charset));
+    }
+}
+
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let delimiter = delimiter.as_bytes();
+    let mut parts = Vec::new();
+    let mut starts = Vec::new();
+
+    // Delimiters must begin a line
+    let mut i = 0;
+    while i + delimiter.len() <= body.len() {
+        let at_line_start = i == 0 || body[i - 1] == b'\n';
+        if at_line_start && body[i..].starts_with(delimiter) {
+            starts.push(i);
+            i += delimiter.len();
+        } else {
+            i += 1;
+        }
+    }
+
+    for (n, &start) in starts.iter().enumerate() {
+        let after = start + delimiter.len();
+        if body[after..].starts_with(b"--") {
+            break;
+        }
+        let content_start = match body[after..].iter().position(|&b| b == b'\n') {
+            Some(p) => after + p + 1,
+            None => continue,
+        };
+        let mut content_end = starts.get(n + 1).copied().unwrap_or(body.len());
+        // The line break before a delimiter belongs to the delimiter
+        if content_end > content_start && body[content_end - 1] == b'\n' {
+            content_end -= 1;
+            if content_end > content_start && body[content_end - 1] == b'\r' {
+                content_end -= 1;
+            }
+        }
+        if content_end >= content_start {
+            parts.push(&body[content_start..content_end]);
+        }
+    }
+
+    parts
+}
+
+fn decode_transfer(body: &[u8], encoding: &str) -> Vec<u8> {
+    match encoding {
+        "base64" => decode_base64(body).unwrap_or_else(|| body.to_vec()),
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.to_vec(),
+    }
+}
+
+fn decode_base64(data: &[u8]) -> Option<Vec<u8>> {
+    let cleaned: Vec<u8> = data.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(&cleaned)
+        .or_else(|_| {
+            let trimmed: Vec<u8> = cleaned.into_iter().filter(|&b| b != b'=').collect();
+            base64::engine::general_purpose::STANDARD_NO_PAD.decode(trimmed)
+        })
+        .ok()
+}
+
+// `underscore_is_space` is the RFC 2047 "Q" variant used inside headers
+fn decode_quoted_printable(data: &[u8], underscore_is_space: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'=' => {
+                // Soft line break
+                if data[i + 1..].starts_with(b"\r\n") {
+                    i += 3;
+                    continue;
+                }
+                if data[i + 1..].starts_with(b"\n") {
+                    i += 2;
+                    continue;
+                }
+                let hex = data.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 3;
+                    }
+                    None => {
+                        out.push(b'=');
+                        i += 1;
+                    }
+                }
+            }
+            b'_' if underscore_is_space => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn decode_charset(data: &[u8], charset: &str) -> String {
+    match charset.to_ascii_lowercase().as_str() {
+        "iso-8859-1" | "latin1" | "latin-1" | "windows-1252" | "us-ascii" => latin1_to_string(data),
+        _ => String::from_utf8_lossy(data).into_owned(),
+    }
+}
+
+fn latin1_or_utf8(data: &[u8]) -> String {
+    match std::str::from_utf8(data) {
+        Ok(s) => s.to_string(),
+        Err(_) => latin1_to_string(data),
+    }
+}
+
+// RFC 2047: =?charset?B?...?= and =?charset?Q?...?=
+fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut last_was_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let prefix = &rest[..start];
+        let candidate = &rest[start + 2..];
+        let decoded = candidate.split_once('?').and_then(|(charset, tail)| {
+            let (enc, tail) = tail.split_once('?')?;
+            let end = tail.find("?=")?;
+            let text = &tail[..end];
+            let bytes = match enc.to_ascii_uppercase().as_str() {
+                "B" => decode_base64(text.as_bytes())?,
+                "Q" => decode_quoted_printable(text.as_bytes(), true),
+                _ => return None,
+            };
+            let consumed = charset.len() + 1 + enc.len() + 1 + end + 2;
+            Some((decode_charset(&bytes, charset), consumed))
+        });
+
+        match decoded {
+            Some((text, consumed)) => {
+                // Whitespace between adjacent encoded words is dropped
+                if !(last_was_word && prefix.trim().is_empty()) {
+                    out.push_str(prefix);
+                }
+                out.push_str(&text);
+                rest = &candidate[consumed..];
+                last_was_word = true;
+            }
+            None => {
+                out.push_str(&rest[..start + 2]);
+                rest = candidate;
+                last_was_word = false;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// --- MBOX ---
+
+// Splits an mbox file on its "From " separator lines and undoes >From quoting
+pub fn split_mbox(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+
+    for line in data.split_inclusive(|&b| b == b'\n') {
+        if line.starts_with(b"From ") {
+            if let Some(msg) = current.take() {
+                messages.push(msg);
+            }
+            current = Some(Vec::new());
+            continue;
+        }
+        let msg = match current.as_mut() {
+            Some(m) => m,
+            // Content before the first separator is not a message
+            None => continue,
+        };
+        let unquoted = line.iter().position(|&b| b != b'>').is_some_and(|p| p > 0 && line[p..].starts_with(b"From "));
+        if unquoted {
+            msg.extend_from_slice(&line[1..]);
+        } else {
+            msg.extend_from_slice(line);
+        }
+    }
+    if let Some(msg) = current {
+        messages.push(msg);
+    }
+
+    messages.into_iter().filter(|m| !m.iter().all(|b| b.is_ascii_whitespace())).collect()
+}
+
+pub fn is_mbox(filename: &str, data: &[u8]) -> bool {
+    filename.to_ascii_lowercase().ends_with(".mbox") || data.starts_with(b"From ")
+}
+
+pub fn parse_date(value: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    // Strip trailing comments such as "(UTC)" or "(Pacific Standard Time)"
+    let cleaned = match value.find('(') {
+        Some(p) => value[..p].trim(),
+        None => value.trim(),
+    };
+    chrono::DateTime::parse_from_rfc2822(cleaned).ok()
+}
+
+// --- Ingestion ---
+
+#[derive(Serialize, Debug, Default)]
+pub struct MailboxSummary {
+    pub messages: usize,
+    pub attachments: usize,
+    pub events_created: usize,
+    pub failures: Vec<String>,
+}
+
+fn message_filename(index: usize, subject: &str) -> String {
+    let stem: String = subject
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' { c } else { '_' })
+        .take(60)
+        .collect();
+    let stem = stem.trim();
+    if stem.is_empty() {
+        format!("message_{:04}.eml", index + 1)
+    } else {
+        format!("{:04}_{}.eml", index + 1, stem)
+    }
+}
+
+// Stores every message (and each attachment) as its own evidence item
+#[allow(clippy::too_many_arguments)]
+pub fn ingest_mail(
+    hunt_dir: &Path,
+    db: &HuntDatabase,
+    key: &SessionKey,
+    filename: &str,
+    data: &[u8],
+    description: &str,
+    create_events: bool,
+    preserve_original: bool,
+) -> Result<MailboxSummary, String> {
+    let raw_messages = if is_mbox(filename, data) {
+        split_mbox(data)
+    } else {
+        vec![data.to_vec()]
+    };
+
+    let mut summary = MailboxSummary::default();
+
+    for (index, raw) in raw_messages.iter().enumerate() {
+        let message = parse_message(raw);
+        let subject = message.header("Subject").unwrap_or("").to_string();
+        let from = message.header("From").unwrap_or("").to_string();
+        let to = message.header("To").unwrap_or("").to_string();
+        let cc = message.header("Cc").unwrap_or("").to_string();
+        let message_id = message.header("Message-ID").unwrap_or("").to_string();
+        let date = message.header("Date").and_then(parse_date);
+
+        let label = if subject.is_empty() { "(no subject)" } else { subject.as_str() };
+        let message_description = format!("{} — Email: {}", description, label);
+        let message_filename = message_filename(index, &subject);
+
+        let prepared = match evidence::prepare(&message_filename, &message_description, raw, key, preserve_original) {
+            Ok(p) => p,
+            Err(e) => {
+                summary.failures.push(format!("{}: {}", message_filename, e));
+                continue;
+            }
+        };
+        let evidence_id = match evidence::store(hunt_dir, db, key, &prepared) {
+            Ok(id) => id,
+            Err(e) => {
+                summary.failures.push(format!("{}: {}", message_filename, e));
+                continue;
+            }
+        };
+        summary.messages += 1;
+
+        db.insert_email_message(
+            evidence_id,
+            &message_id,
+            &from,
+            &to,
+            &cc,
+            date.map(|d| d.to_rfc3339()).as_deref(),
+            &subject,
+        ).map_err(|e| e.to_string())?;
+
+        for attachment in &message.attachments {
+            let attachment_description = format!("Attachment to email: {}", label);
+            let stored = evidence::prepare(&attachment.filename, &attachment_description, &attachment.data, key, preserve_original)
+                .and_then(|p| evidence::store(hunt_dir, db, key, &p));
+            match stored {
+                Ok(attachment_id) => {
+                    db.set_evidence_parent(attachment_id, evidence_id).map_err(|e| e.to_string())?;
+                    summary.attachments += 1;
+                }
+                Err(e) => summary.failures.push(format!("{} / {}: {}", message_filename, attachment.filename, e)),
+            }
+        }
+
+        if create_events {
+            if let Some(date) = date {
+                let title = format!("Email: {}", label);
+                let event_description = format!("From {} to {}", from, to);
+                db.conn.execute(
+                    "INSERT INTO events (title, description, event_date, event_type) VALUES (?1, ?2, ?3, 'email')",
+                    rusqlite::params![title, event_description, date.format("%Y-%m-%d").to_string()],
+                ).map_err(|e| e.to_string())?;
+                search::index_record(&db.conn, key, search::KIND_EVENT, &db.conn.last_insert_rowid().to_string())?;
+                summary.events_created += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_with_attachment() {
+        let raw = b"From: CFO <cfo@example.com>\r\n\
+Subject: =?UTF-8?Q?Invoice_INV-0042?=\r\n\
+Content-Type: multipart/mixed; boundary=\"XYZ\"\r\n\
+\r\n\
+--XYZ\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Please bill the full=\r\n amount.\r\n\
+--XYZ\r\n\
+Content-Type: application/pdf; name=\"inv.pdf\"\r\n\
+Content-Disposition: attachment; filename=\"inv.pdf\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+JVBERi0=\r\n\
+--XYZ--\r\n";
+
+        let msg = parse_message(raw);
+        assert_eq!(msg.header("subject"), Some("Invoice INV-0042"));
+        assert_eq!(msg.text_body.as_deref(), Some("Please bill the full amount."));
+        assert_eq!(msg.attachments.len(), 1);
+        assert_eq!(msg.attachments[0].filename, "inv.pdf");
+        assert_eq!(msg.attachments[0].data, b"%PDF-");
+    }
+
+    #[test]
+    fn test_split_mbox() {
+        let mbox = b"From a@example.com Mon Jan  1 00:00:00 2024\nSubject: one\n\n>From the top\n\nFrom b@example.com Tue Jan  2 00:00:00 2024\nSubject: two\n\nbody\n";
+        let messages = split_mbox(mbox);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(parse_message(&messages[0]).text_body.as_deref(), Some("From the top\n\n"));
+        assert_eq!(parse_message(&messages[1]).header("Subject"), Some("two"));
+    }
+
+    #[test]
+    fn test_ingest_mail_records_headers_attachments_and_events() {
+        let mbox = b"From cfo@example.com Mon Mar  4 09:15:00 2024\n\
+From: CFO <cfo@example.com>\n\
+To: Billing <billing@example.com>\n\
+Cc: Audit <audit@example.com>\n\
+Subject: Invoice INV-0042\n\
+Message-ID: <inv42@example.com>\n\
+Date: Mon, 4 Mar 2024 09:15:00 -0500\n\
+Content-Type: multipart/mixed; boundary=\"XYZ\"\n\
+\n\
+--XYZ\n\
+Content-Type: text/plain\n\
+\n\
+Please bill the full amount.\n\
+--XYZ\n\
+Content-Type: text/plain; name=\"hours.txt\"\n\
+Content-Disposition: attachment; filename=\"hours.txt\"\n\
+\n\
+40 hours billed, 12 worked\n\
+--XYZ--\n\
+\n\
+From billing@example.com Tue Mar  5 10:00:00 2024\n\
+From: Billing <billing@example.com>\n\
+To: CFO <cfo@example.com>\n\
+Subject: Re: Invoice INV-0042\n\
+\n\
+Done.\n";
+
+        let hunt_dir = std::env::temp_dir().join(format!("os-mail-{}", uuid::Uuid::new_v4()));
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([1u8; 32]);
+        let summary = ingest_mail(&hunt_dir, &db, &key, "export.mbox", mbox, "Billing inbox", true, false).unwrap();
+        assert_eq!((summary.messages, summary.attachments, summary.events_created), (2, 1, 1));
+        assert!(summary.failures.is_empty());
+
+        let headers: Vec<(i64, String, String, String, Option<String>, String)> = db.conn
+            .prepare("SELECT evidence_id, message_id, from_addr, cc_addrs, sent_at, subject FROM email_messages ORDER BY evidence_id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(headers.len(), 2);
+        let (first_id, message_id, from, cc, sent_at, subject) = &headers[0];
+        assert_eq!(message_id, "<inv42@example.com>");
+        assert_eq!(from, "CFO <cfo@example.com>");
+        assert_eq!(cc, "Audit <audit@example.com>");
+        assert_eq!(sent_at.as_deref(), Some("2024-03-04T09:15:00-05:00"));
+        assert_eq!(subject, "Invoice INV-0042");
+        // The reply has no Date header, so it gets no event
+        assert_eq!(headers[1].4, None);
+
+        let (attachment, parent): (String, i64) = db.conn
+            .query_row("SELECT file_path, parent_evidence_id FROM evidence WHERE parent_evidence_id IS NOT NULL", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!(attachment, "hours.txt");
+        assert_eq!(parent, *first_id);
+
+        let events: Vec<(String, String, String)> = db.conn
+            .prepare("SELECT title, event_date, event_type FROM events")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(events, [("Email: Invoice INV-0042".to_string(), "2024-03-04".to_string(), "email".to_string())]);
+        let _ = std::fs::remove_dir_all(&hunt_dir);
+    }
+
+    #[test]
+    fn test_attachment_shared_by_a_thread_stays_readable_in_every_message() {
+        let message = |subject: &str| format!(
+            "From a@example.com Mon Mar  4 09:15:00 2024\n\
+From: a@example.com\n\
+Subject: {}\n\
+Content-Type: multipart/mixed; boundary=\"XYZ\"\n\
+\n\
+--XYZ\n\
+Content-Type: text/plain\n\
+\n\
+See attached.\n\
+--XYZ\n\
+Content-Type: application/pdf; name=\"inv.pdf\"\n\
+Content-Disposition: attachment; filename=\"inv.pdf\"\n\
+Content-Transfer-Encoding: base64\n\
+\n\
+JVBERi0=\n\
+--XYZ--\n\
+\n",
+            subject
+        );
+        let mbox = format!("{}{}", message("Invoice"), message("Re: Invoice"));
+
+        let hunt_dir = std::env::temp_dir().join(format!("os-mail-{}", uuid::Uuid::new_v4()));
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([1u8; 32]);
+        let summary = ingest_mail(&hunt_dir, &db, &key, "thread.mbox", mbox.as_bytes(), "", false, true).unwrap();
+        assert_eq!((summary.messages, summary.attachments), (2, 2));
+
+        let attachments: Vec<i64> = db.conn
+            .prepare("SELECT id FROM evidence WHERE parent_evidence_id IS NOT NULL")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(attachments.len(), 2);
+        for id in attachments {
+            for version in [evidence::EvidenceVersion::Scrubbed, evidence::EvidenceVersion::Original] {
+                assert_eq!(evidence::decrypt(&hunt_dir, &db, id, version, &key).unwrap(), b"%PDF-");
+            }
+        }
+        let _ = std::fs::remove_dir_all(&hunt_dir);
+    }
+}
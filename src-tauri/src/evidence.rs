@@ -0,0 +1,736 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+use rusqlite::OptionalExtension;
+use crate::crypto::{self, SessionKey};
+use crate::db::HuntDatabase;
+use crate::{extract, ocr, preview, search, trash};
+
+// Ingest pipeline shared by every evidence entry point:
+// scrub -> hash -> encrypt -> extract/OCR text -> write `evidence/<sha256>.enc` -> index row.
+
+pub struct EncryptedBlob {
+    pub sha256_hash: String,
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+}
+
+pub struct PreparedEvidence {
+    pub filename: String,
+    pub description: String,
+    pub scrubbed: EncryptedBlob,
+    // Unmodified original, kept only when the caller asked to preserve it
+    pub original: Option<EncryptedBlob>,
+    // Extracted and OCR text (encrypted) and the blind search-index body
+    pub text: Option<EncryptedBlob>,
+    pub ocr_text: Option<EncryptedBlob>,
+    // Why a scan got no OCR text (OCR not built in, or it failed)
+    pub ocr_skipped: Option<String>,
+    pub index_body: String,
+    // Encrypted thumbnail / text preview and its MIME type
+    pub preview: Option<(String, EncryptedBlob)>,
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+fn seal(data: &[u8], key: &SessionKey) -> Result<EncryptedBlob, String> {
+    let sha256_hash = sha256_hex(data);
+    let (ciphertext, nonce) = crypto::encrypt_data(data, key)?;
+    Ok(EncryptedBlob { sha256_hash, ciphertext, nonce })
+}
+
+// Pure CPU work (no I/O), so callers may run it on worker threads
+pub fn prepare(
+    filename: &str,
+    description: &str,
+    file_bytes: &[u8],
+    key: &SessionKey,
+    preserve_original: bool,
+) -> Result<PreparedEvidence, String> {
+    let scrubbed_bytes = crypto::strip_metadata(file_bytes);
+    let scrubbed = seal(&scrubbed_bytes, key)?;
+
+    let original = if preserve_original {
+        Some(seal(file_bytes, key)?)
+    } else {
+        None
+    };
+
+    let extracted = extract::extract_text(filename, &scrubbed_bytes);
+    let text = match &extracted {
+        Some(t) => Some(seal(t.as_bytes(), key)?),
+        None => None,
+    };
+
+    // OCR problems must not block ingest; the item is still stored without OCR
+    // text, with the reason kept so OCR can be run on it later
+    let (recognized, ocr_skipped) = if !ocr::is_available() {
+        (None, ocr::wants_ocr(&scrubbed_bytes).then(|| ocr::UNAVAILABLE.to_string()))
+    } else {
+        match ocr::ocr_evidence(&scrubbed_bytes) {
+            Ok(text) => (text, None),
+            Err(e) => {
+                eprintln!("WARNING: OCR skipped for {}: {}", filename, e);
+                (None, Some(e))
+            }
+        }
+    };
+    let ocr_text = match &recognized {
+        Some(t) => Some(seal(t.as_bytes(), key)?),
+        None => None,
+    };
+
+    let index_body = search::blind_tokens(
+        &format!(
+            "{}\n{}\n{}\n{}",
+            filename,
+            description,
+            extracted.as_deref().unwrap_or(""),
+            recognized.as_deref().unwrap_or("")
+        ),
+        key,
+    );
+
+    let preview = match preview::generate(filename, &scrubbed_bytes) {
+        Some(p) => Some((p.mime_type, seal(&p.data, key)?)),
+        None => None,
+    };
+
+    Ok(PreparedEvidence {
+        filename: filename.to_string(),
+        description: description.to_string(),
+        scrubbed,
+        original,
+        text,
+        ocr_text,
+        ocr_skipped,
+        index_body,
+        preview,
+    })
+}
+
+pub fn scrubbed_path(hunt_dir: &Path, sha256_hash: &str) -> PathBuf {
+    hunt_dir.join("evidence").join(format!("{}.enc", sha256_hash))
+}
+
+pub fn original_path(hunt_dir: &Path, sha256_hash: &str) -> PathBuf {
+    hunt_dir.join("evidence").join(format!("{}.orig.enc", sha256_hash))
+}
+
+// Writes the blobs and then every row for the item in one transaction. If any
+// step fails the rows are rolled back and blob files this call created are
+// removed. Blobs are named by content hash, so an identical file stored before
+// (still live or in the trash) already has one: it is left untouched and the
+// new row records that blob's nonce.
+pub fn store(hunt_dir: &Path, db: &HuntDatabase, key: &SessionKey, prepared: &PreparedEvidence) -> Result<i64, String> {
+    fs::create_dir_all(hunt_dir.join("evidence")).map_err(|e| e.to_string())?;
+
+    let mut created = Vec::new();
+    let result = store_rows(hunt_dir, db, key, prepared, &mut created);
+    if result.is_err() {
+        for path in created {
+            let _ = fs::remove_file(path);
+        }
+    }
+    result
+}
+
+fn store_rows(
+    hunt_dir: &Path,
+    db: &HuntDatabase,
+    key: &SessionKey,
+    prepared: &PreparedEvidence,
+    created: &mut Vec<PathBuf>,
+) -> Result<i64, String> {
+    let scrubbed = &prepared.scrubbed;
+    let scrubbed_nonce = place_blob(
+        db, key, scrubbed_path(hunt_dir, &scrubbed.sha256_hash), scrubbed, SCRUBBED_COLUMNS, created,
+    )?;
+    let original_nonce = match &prepared.original {
+        Some(original) => Some(place_blob(
+            db, key, original_path(hunt_dir, &original.sha256_hash), original, ORIGINAL_COLUMNS, created,
+        )?),
+        None => None,
+    };
+
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let evidence_id = crate::db::insert_evidence_row(
+        &tx, &prepared.description, &prepared.filename, &scrubbed_nonce, &scrubbed.sha256_hash,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if let (Some(original), Some(nonce)) = (&prepared.original, &original_nonce) {
+        db.set_evidence_original(evidence_id, &original.sha256_hash, nonce)
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(text) = &prepared.text {
+        search::store_evidence_text(&db.conn, evidence_id, "extracted", &text.ciphertext, &text.nonce)?;
+    }
+    if let Some(text) = &prepared.ocr_text {
+        search::store_evidence_text(&db.conn, evidence_id, "ocr", &text.ciphertext, &text.nonce)?;
+    }
+    if let Some(reason) = &prepared.ocr_skipped {
+        db.set_evidence_ocr_skipped(evidence_id, Some(reason)).map_err(|e| e.to_string())?;
+    }
+    search::index_document(&db.conn, search::KIND_EVIDENCE, &evidence_id.to_string(), &prepared.index_body)?;
+
+    if let Some((mime_type, blob)) = &prepared.preview {
+        fs::create_dir_all(hunt_dir.join("previews")).map_err(|e| e.to_string())?;
+        write_blob(preview::preview_path(hunt_dir, evidence_id), &blob.ciphertext, created)?;
+        db.set_evidence_preview(evidence_id, mime_type, &blob.nonce).map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(evidence_id)
+}
+
+// (hash, nonce) columns of the evidence row for each stored copy
+const SCRUBBED_COLUMNS: (&str, &str) = ("sha256_hash", "encrypted_key_nonce");
+const ORIGINAL_COLUMNS: (&str, &str) = ("original_sha256_hash", "original_nonce");
+
+// Writes `blob` unless a row already owns a blob at `path`, and returns the
+// nonce that decrypts whatever is there afterwards
+fn place_blob(
+    db: &HuntDatabase,
+    key: &SessionKey,
+    path: PathBuf,
+    blob: &EncryptedBlob,
+    columns: (&str, &str),
+    created: &mut Vec<PathBuf>,
+) -> Result<Vec<u8>, String> {
+    if path.exists() {
+        if let Some(nonce) = stored_nonce(db, key, columns, &blob.sha256_hash)? {
+            return Ok(nonce);
+        }
+    }
+    write_blob(path, &blob.ciphertext, created)?;
+    Ok(blob.nonce.clone())
+}
+
+// Nonce recorded for the blob stored under `hash` by a live evidence row or
+// one in the trash
+fn stored_nonce(
+    db: &HuntDatabase,
+    key: &SessionKey,
+    (hash_column, nonce_column): (&str, &str),
+    hash: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let live: Option<Vec<u8>> = db
+        .conn
+        .query_row(
+            &format!(
+                "SELECT {1} FROM evidence WHERE {0} = ?1 AND {1} IS NOT NULL LIMIT 1",
+                hash_column, nonce_column
+            ),
+            rusqlite::params![hash],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match live {
+        Some(nonce) => Ok(Some(nonce)),
+        None => trash::trashed_evidence_nonce(db, key, hash_column, nonce_column, hash),
+    }
+}
+
+fn write_blob(path: PathBuf, data: &[u8], created: &mut Vec<PathBuf>) -> Result<(), String> {
+    if !path.exists() {
+        created.push(path.clone());
+    }
+    fs::write(&path, data).map_err(|e| e.to_string())
+}
+
+fn store_preview(
+    hunt_dir: &Path,
+    db: &HuntDatabase,
+    evidence_id: i64,
+    mime_type: &str,
+    blob: &EncryptedBlob,
+) -> Result<(), String> {
+    fs::create_dir_all(hunt_dir.join("previews")).map_err(|e| e.to_string())?;
+    fs::write(preview::preview_path(hunt_dir, evidence_id), &blob.ciphertext).map_err(|e| e.to_string())?;
+    db.set_evidence_preview(evidence_id, mime_type, &blob.nonce).map_err(|e| e.to_string())
+}
+
+// Decrypted preview for an item. Evidence stored before previews existed, or
+// before they were keyed by evidence id, gets one generated from its scrubbed
+// copy on first request.
+pub fn load_preview(
+    hunt_dir: &Path,
+    db: &HuntDatabase,
+    evidence_id: i64,
+    key: &SessionKey,
+) -> Result<Option<preview::PreviewData>, String> {
+    let (filename, stored): (String, Option<(String, Vec<u8>)>) = db
+        .conn
+        .query_row(
+            "SELECT e.file_path, p.mime_type, p.nonce FROM evidence e \
+             LEFT JOIN evidence_previews p ON p.evidence_id = e.id WHERE e.id = ?1",
+            rusqlite::params![evidence_id],
+            |row| {
+                let mime: Option<String> = row.get(1)?;
+                let nonce: Option<Vec<u8>> = row.get(2)?;
+                Ok((row.get(0)?, mime.zip(nonce)))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let path = preview::preview_path(hunt_dir, evidence_id);
+    if let Some((mime_type, nonce)) = stored {
+        if path.exists() {
+            let ciphertext = fs::read(&path).map_err(|e| e.to_string())?;
+            let data = crypto::decrypt_data(&ciphertext, &nonce, key)?;
+            return Ok(Some(preview::PreviewData { mime_type, data }));
+        }
+    }
+
+    let plaintext = decrypt(hunt_dir, db, evidence_id, EvidenceVersion::Scrubbed, key)?;
+    let generated = match preview::generate(&filename, &plaintext) {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+    let blob = seal(&generated.data, key)?;
+    store_preview(hunt_dir, db, evidence_id, &generated.mime_type, &blob)?;
+    Ok(Some(generated))
+}
+
+pub fn ingest_bytes(
+    hunt_dir: &Path,
+    key: &SessionKey,
+    filename: &str,
+    file_bytes: &[u8],
+    description: &str,
+    preserve_original: bool,
+) -> Result<i64, String> {
+    let prepared = prepare(filename, description, file_bytes, key, preserve_original)?;
+    let db = HuntDatabase::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+    store(hunt_dir, &db, key, &prepared)
+}
+
+// Re-runs OCR on an already stored item (e.g. ingested before OCR was enabled)
+pub fn run_ocr(hunt_dir: &Path, db: &HuntDatabase, evidence_id: i64, key: &SessionKey) -> Result<bool, String> {
+    if !ocr::is_available() {
+        return Err(ocr::UNAVAILABLE.to_string());
+    }
+    let plaintext = decrypt(hunt_dir, db, evidence_id, EvidenceVersion::Scrubbed, key)?;
+    let recognized = ocr::ocr_evidence(&plaintext);
+    db.set_evidence_ocr_skipped(evidence_id, recognized.as_ref().err().map(String::as_str))
+        .map_err(|e| e.to_string())?;
+    let text = match recognized? {
+        Some(t) => t,
+        None => return Ok(false),
+    };
+    let sealed = seal(text.as_bytes(), key)?;
+    search::store_evidence_text(&db.conn, evidence_id, "ocr", &sealed.ciphertext, &sealed.nonce)?;
+    search::reindex_evidence(&db.conn, evidence_id, key)?;
+    Ok(true)
+}
+
+// --- Bulk folder ingestion ---
+
+#[derive(Serialize, Clone, Debug)]
+pub struct IngestProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub path: String,
+    pub status: String, // "stored" | "duplicate" | "failed"
+}
+
+#[derive(Serialize, Debug)]
+pub struct IngestFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct IngestSummary {
+    pub total: usize,
+    pub stored: usize,
+    pub duplicates: Vec<String>,
+    pub failures: Vec<IngestFailure>,
+    pub cancelled: bool,
+}
+
+fn hash_already_stored(db: &HuntDatabase, sha256_hash: &str) -> Result<bool, String> {
+    db.conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM evidence WHERE sha256_hash = ?1)",
+            rusqlite::params![sha256_hash],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())
+}
+
+// Walks `root` and ingests every regular file. Reading, scrubbing, hashing and
+// encryption run on a worker pool; blob writes and SQLite inserts stay on the
+// calling thread so the hunt database only ever has one writer.
+pub fn ingest_directory(
+    hunt_dir: &Path,
+    key: &SessionKey,
+    root: &Path,
+    description: &str,
+    preserve_original: bool,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<IngestSummary, String> {
+    // Unreadable directories and entries are reported as failures, not skipped silently
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut walk_failures = Vec::new();
+    for entry in WalkDir::new(root).follow_links(false) {
+        match entry {
+            Ok(e) if e.file_type().is_file() => files.push(e.into_path()),
+            Ok(_) => {}
+            Err(e) => {
+                let path = e.path().unwrap_or(root);
+                let relative = path.strip_prefix(root).ok().filter(|p| !p.as_os_str().is_empty()).unwrap_or(path);
+                walk_failures.push(IngestFailure { path: relative.to_string_lossy().into_owned(), error: e.to_string() });
+            }
+        }
+    }
+
+    let db = HuntDatabase::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+    let mut summary = IngestSummary {
+        total: files.len() + walk_failures.len(),
+        ..Default::default()
+    };
+    let walk_failed = walk_failures.len();
+    for (index, failure) in walk_failures.into_iter().enumerate() {
+        on_progress(&IngestProgress {
+            processed: index + 1,
+            total: summary.total,
+            path: failure.path.clone(),
+            status: "failed".to_string(),
+        });
+        summary.failures.push(failure);
+    }
+
+    let next = AtomicUsize::new(0);
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4).min(8);
+    let (tx, rx) = mpsc::sync_channel::<(String, Result<PreparedEvidence, String>)>(workers * 2);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let files = &files;
+            let next = &next;
+            scope.spawn(move || loop {
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let path = match files.get(index) {
+                    Some(p) => p,
+                    None => break,
+                };
+                let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().into_owned();
+                let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("unknown");
+                let item_description = if description.is_empty() {
+                    relative.clone()
+                } else {
+                    format!("{} ({})", description, relative)
+                };
+                let result = fs::read(path)
+                    .map_err(|e| format!("Failed to read source file: {}", e))
+                    .and_then(|bytes| prepare(filename, &item_description, &bytes, key, preserve_original));
+                if tx.send((relative, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut seen = HashSet::new();
+        for (index, (relative, result)) in rx.into_iter().enumerate() {
+            let status = match result {
+                Ok(prepared) => {
+                    let hash = prepared.scrubbed.sha256_hash.clone();
+                    let duplicate = !seen.insert(hash.clone())
+                        || hash_already_stored(&db, &hash).unwrap_or(false);
+                    if duplicate {
+                        summary.duplicates.push(relative.clone());
+                        "duplicate"
+                    } else {
+                        match store(hunt_dir, &db, key, &prepared) {
+                            Ok(_) => {
+                                summary.stored += 1;
+                                "stored"
+                            }
+                            Err(error) => {
+                                summary.failures.push(IngestFailure { path: relative.clone(), error });
+                                "failed"
+                            }
+                        }
+                    }
+                }
+                Err(error) => {
+                    summary.failures.push(IngestFailure { path: relative.clone(), error });
+                    "failed"
+                }
+            };
+
+            on_progress(&IngestProgress {
+                processed: walk_failed + index + 1,
+                total: summary.total,
+                path: relative,
+                status: status.to_string(),
+            });
+        }
+    });
+
+    summary.cancelled = cancel.load(Ordering::Relaxed);
+    Ok(summary)
+}
+
+// Which stored copy of an evidence item to read back
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EvidenceVersion {
+    Scrubbed,
+    Original,
+}
+
+impl EvidenceVersion {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "scrubbed" => Ok(Self::Scrubbed),
+            "original" => Ok(Self::Original),
+            other => Err(format!("Unknown evidence version '{}'", other)),
+        }
+    }
+}
+
+pub fn decrypt(
+    hunt_dir: &Path,
+    db: &HuntDatabase,
+    evidence_id: i64,
+    version: EvidenceVersion,
+    key: &SessionKey,
+) -> Result<Vec<u8>, String> {
+    let (hash, nonce): (Option<String>, Option<Vec<u8>>) = match version {
+        EvidenceVersion::Scrubbed => db.conn.query_row(
+            "SELECT sha256_hash, encrypted_key_nonce FROM evidence WHERE id = ?1",
+            rusqlite::params![evidence_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ),
+        EvidenceVersion::Original => db.conn.query_row(
+            "SELECT original_sha256_hash, original_nonce FROM evidence WHERE id = ?1",
+            rusqlite::params![evidence_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let (hash, nonce) = match (hash, nonce) {
+        (Some(h), Some(n)) => (h, n),
+        _ if version == EvidenceVersion::Original => {
+            return Err("No unmodified original was preserved for this evidence".to_string())
+        }
+        _ => return Err("Evidence record is missing its hash or nonce".to_string()),
+    };
+
+    let path = match version {
+        EvidenceVersion::Scrubbed => scrubbed_path(hunt_dir, &hash),
+        EvidenceVersion::Original => original_path(hunt_dir, &hash),
+    };
+    let ciphertext = fs::read(&path).map_err(|e| format!("Failed to read evidence blob: {}", e))?;
+    let plaintext = crypto::decrypt_data(&ciphertext, &nonce, key)?;
+
+    // Refuse to hand back bytes that no longer match the logged hash
+    if sha256_hex(&plaintext) != hash {
+        return Err("Evidence integrity check failed: SHA-256 mismatch".to_string());
+    }
+
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ingest_directory_reports_duplicates() {
+        let base = std::env::temp_dir().join(format!("os-ingest-{}", uuid::Uuid::new_v4()));
+        let source = base.join("drop");
+        let hunt_dir = base.join("hunt");
+        fs::create_dir_all(source.join("nested")).unwrap();
+        fs::create_dir_all(&hunt_dir).unwrap();
+        fs::write(source.join("a.txt"), b"timesheet week 1").unwrap();
+        fs::write(source.join("nested").join("b.txt"), b"timesheet week 2").unwrap();
+        fs::write(source.join("nested").join("copy_of_a.txt"), b"timesheet week 1").unwrap();
+
+        let key = SessionKey([1u8; 32]);
+        let cancel = AtomicBool::new(false);
+        let mut events = 0;
+        let summary = ingest_directory(&hunt_dir, &key, &source, "Drop", false, &cancel, |_| events += 1).unwrap();
+
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.stored, 2);
+        assert_eq!(summary.duplicates.len(), 1);
+        assert!(summary.failures.is_empty());
+        assert_eq!(events, 3);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_ingest_directory_reports_walk_errors() {
+        let base = std::env::temp_dir().join(format!("os-ingest-{}", uuid::Uuid::new_v4()));
+        let hunt_dir = base.join("hunt");
+        fs::create_dir_all(&hunt_dir).unwrap();
+        let missing = base.join("not-mounted");
+
+        let key = SessionKey([1u8; 32]);
+        let mut statuses = Vec::new();
+        let summary = ingest_directory(&hunt_dir, &key, &missing, "", false, &AtomicBool::new(false), |p| {
+            statuses.push((p.processed, p.total, p.status.clone()))
+        })
+        .unwrap();
+
+        assert_eq!(summary.total, 1);
+        assert_eq!(summary.stored, 0);
+        assert_eq!(summary.failures.len(), 1);
+        assert_eq!(summary.failures[0].path, missing.to_string_lossy());
+        assert_eq!(statuses, [(1, 1, "failed".to_string())]);
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_previews_of_identical_files_stay_readable() {
+        let hunt_dir = std::env::temp_dir().join(format!("os-preview-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&hunt_dir).unwrap();
+        let key = SessionKey([1u8; 32]);
+        let first = ingest_bytes(&hunt_dir, &key, "notes.txt", b"invoice 42 paid twice", "first", false).unwrap();
+        let second = ingest_bytes(&hunt_dir, &key, "notes.txt", b"invoice 42 paid twice", "second", false).unwrap();
+
+        let db = HuntDatabase::open(hunt_dir.join("metadata.db")).unwrap();
+        for id in [first, second] {
+            let preview = load_preview(&hunt_dir, &db, id, &key).unwrap().unwrap();
+            assert_eq!(preview.data, b"invoice 42 paid twice");
+        }
+        fs::remove_dir_all(&hunt_dir).unwrap();
+    }
+
+    fn png() -> Vec<u8> {
+        let img: image::ImageBuffer<image::Rgb<u8>, Vec<u8>> = image::ImageBuffer::from_pixel(64, 32, image::Rgb([255, 255, 255]));
+        let mut png = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+        png
+    }
+
+    #[test]
+    fn test_preserved_original_and_scrubbed_copy_export_intact() {
+        // A PNG with an author chunk, which scrubbing drops
+        let clean = png();
+        let mut tagged = clean[..33].to_vec();
+        let body = b"Author\0Jane Relator";
+        tagged.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        tagged.extend_from_slice(b"tEXt");
+        tagged.extend_from_slice(body);
+        tagged.extend_from_slice(&[0, 0, 0, 0]);
+        tagged.extend_from_slice(&clean[33..]);
+
+        let hunt_dir = std::env::temp_dir().join(format!("os-export-{}", uuid::Uuid::new_v4()));
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([1u8; 32]);
+        let kept = store(&hunt_dir, &db, &key, &prepare("photo.png", "", &tagged, &key, true).unwrap()).unwrap();
+        let plain = store(&hunt_dir, &db, &key, &prepare("notes.txt", "", b"typed notes", &key, false).unwrap()).unwrap();
+
+        assert_eq!(decrypt(&hunt_dir, &db, kept, EvidenceVersion::Original, &key).unwrap(), tagged);
+        assert_eq!(decrypt(&hunt_dir, &db, kept, EvidenceVersion::Scrubbed, &key).unwrap(), clean);
+        assert!(decrypt(&hunt_dir, &db, plain, EvidenceVersion::Original, &key).is_err());
+        assert!(decrypt(&hunt_dir, &db, kept, EvidenceVersion::Scrubbed, &SessionKey([2u8; 32])).is_err());
+
+        // A blob that no longer matches its logged hash is refused
+        let hash = sha256_hex(&clean);
+        let forged = seal(b"forged", &key).unwrap();
+        fs::write(scrubbed_path(&hunt_dir, &hash), &forged.ciphertext).unwrap();
+        db.conn.execute("UPDATE evidence SET encrypted_key_nonce = ?1 WHERE id = ?2", rusqlite::params![forged.nonce, kept]).unwrap();
+        assert!(decrypt(&hunt_dir, &db, kept, EvidenceVersion::Scrubbed, &key).unwrap_err().contains("SHA-256 mismatch"));
+        fs::remove_dir_all(&hunt_dir).unwrap();
+    }
+
+    #[test]
+    fn test_identical_files_share_a_blob_both_rows_can_read() {
+        let hunt_dir = std::env::temp_dir().join(format!("os-dedupe-{}", uuid::Uuid::new_v4()));
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([1u8; 32]);
+        let ingest = || store(&hunt_dir, &db, &key, &prepare("memo.txt", "", b"void the audit", &key, true).unwrap()).unwrap();
+
+        let first = ingest();
+        let second = ingest();
+        // The first copy is in the trash when the third arrives, and comes back
+        trash::trash_record(&db, &key, trash::KIND_EVIDENCE, first).unwrap();
+        let third = ingest();
+        trash::restore_record(&db, &key, trash::KIND_EVIDENCE, first).unwrap();
+
+        for id in [first, second, third] {
+            for version in [EvidenceVersion::Scrubbed, EvidenceVersion::Original] {
+                assert_eq!(decrypt(&hunt_dir, &db, id, version, &key).unwrap(), b"void the audit");
+            }
+        }
+        fs::remove_dir_all(&hunt_dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_store_leaves_no_rows_or_new_blobs() {
+        let hunt_dir = std::env::temp_dir().join(format!("os-store-{}", uuid::Uuid::new_v4()));
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([1u8; 32]);
+        let earlier = prepare("a.txt", "", b"first page", &key, true).unwrap();
+        store(&hunt_dir, &db, &key, &earlier).unwrap();
+
+        db.conn.execute_batch("DROP TABLE search_docs").unwrap();
+        let failing = prepare("b.txt", "", b"second page", &key, true).unwrap();
+        assert!(store(&hunt_dir, &db, &key, &failing).is_err());
+        assert!(store(&hunt_dir, &db, &key, &earlier).is_err());
+
+        let rows: i64 = db.conn.query_row("SELECT COUNT(*) FROM evidence", [], |r| r.get(0)).unwrap();
+        assert_eq!(rows, 1);
+        assert!(!scrubbed_path(&hunt_dir, &failing.scrubbed.sha256_hash).exists());
+        assert!(!original_path(&hunt_dir, &failing.original.unwrap().sha256_hash).exists());
+        assert_eq!(fs::read_dir(hunt_dir.join("previews")).unwrap().count(), 1);
+        // The failed retry of an identical file keeps the blobs the first copy uses
+        assert!(scrubbed_path(&hunt_dir, &earlier.scrubbed.sha256_hash).exists());
+        assert!(original_path(&hunt_dir, &earlier.original.unwrap().sha256_hash).exists());
+        fs::remove_dir_all(&hunt_dir).unwrap();
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    #[test]
+    fn test_scan_is_stored_with_ocr_skip_when_ocr_is_not_built() {
+        let key = SessionKey([1u8; 32]);
+        let prepared = prepare("timesheet.png", "scan", &png(), &key, false).unwrap();
+        assert!(prepared.ocr_text.is_none());
+        assert_eq!(prepared.ocr_skipped.as_deref(), Some(ocr::UNAVAILABLE));
+        assert!(prepare("notes.txt", "", b"typed notes", &key, false).unwrap().ocr_skipped.is_none());
+
+        let hunt_dir = std::env::temp_dir().join(format!("os-ocr-{}", uuid::Uuid::new_v4()));
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let id = store(&hunt_dir, &db, &key, &prepared).unwrap();
+        let skipped: Option<String> = db.conn
+            .query_row("SELECT ocr_skipped FROM evidence WHERE id = ?1", [id], |r| r.get(0))
+            .unwrap();
+        assert_eq!(skipped.as_deref(), Some(ocr::UNAVAILABLE));
+        assert_eq!(run_ocr(&hunt_dir, &db, id, &key).unwrap_err(), ocr::UNAVAILABLE);
+        fs::remove_dir_all(&hunt_dir).unwrap();
+    }
+
+    #[cfg(feature = "ocr")]
+    #[test]
+    fn test_scan_is_stored_when_ocr_fails() {
+        // No language data here, so the engine cannot start
+        ocr::set_tessdata_dir(std::env::temp_dir().join("os-no-tessdata"));
+        let key = SessionKey([1u8; 32]);
+        let prepared = prepare("timesheet.png", "scan", &png(), &key, false).unwrap();
+        assert!(prepared.ocr_text.is_none());
+        assert!(prepared.ocr_skipped.unwrap().starts_with("OCR engine failed"));
+    }
+}
@@ -0,0 +1,456 @@
+use std::io::{Cursor, Read};
+use crate::email;
+use crate::metadata::{decode_pdf_text, find_bytes, inflate, lex_pdf_string, unescape_xml};
+
+// Plain-text extraction used to build the hunt search index.
+// Returns None when the format is unsupported or yields no text.
+
+pub fn extract_text(filename: &str, data: &[u8]) -> Option<String> {
+    let ext = filename
+        .rsplit('.')
+        .next()
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let text = if data.starts_with(b"%PDF") {
+        extract_pdf(data)
+    } else if data.starts_with(b"PK\x03\x04") {
+        extract_docx(data)
+    } else if ext == "eml" {
+        extract_eml(data)
+    } else if ext == "html" || ext == "htm" || looks_like_html(data) {
+        Some(html_to_text(&String::from_utf8_lossy(data)))
+    } else if is_plain_text(data) {
+        Some(String::from_utf8_lossy(data).into_owned())
+    } else {
+        None
+    }?;
+
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+fn is_plain_text(data: &[u8]) -> bool {
+    let sample = &data[..data.len().min(8192)];
+    !sample.contains(&0) && std::str::from_utf8(sample).is_ok()
+}
+
+fn looks_like_html(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(512)]).to_ascii_lowercase();
+    let head = head.trim_start();
+    head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+// --- PDF ---
+
+pub fn extract_pdf(data: &[u8]) -> Option<String> {
+    let mut out = String::new();
+    for stream in pdf_streams(data) {
+        if let Some(text) = content_stream_text(&stream) {
+            out.push_str(&text);
+            out.push('\n');
+        }
+    }
+    Some(out)
+}
+
+// (dictionary, raw body) of every `stream ... endstream` object in the file
+fn raw_pdf_streams(data: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut streams = Vec::new();
+    let mut pos = 0;
+
+    while let Some(rel) = find_bytes(&data[pos..], b"stream") {
+        let kw = pos + rel;
+        pos = kw + 6;
+        if kw >= 3 && &data[kw - 3..kw] == b"end" {
+            continue;
+        }
+
+        let dict_start = match rfind_bytes(&data[..kw], b"obj") {
+            Some(p) => p,
+            None => continue,
+        };
+        let dict = &data[dict_start..kw];
+
+        let mut body_start = kw + 6;
+        if data[body_start..].starts_with(b"\r\n") {
+            body_start += 2;
+        } else if data[body_start..].starts_with(b"\n") {
+            body_start += 1;
+        }
+        let body_end = match find_bytes(&data[body_start..], b"endstream") {
+            Some(p) => body_start + p,
+            None => break,
+        };
+        pos = body_end + 9;
+
+        streams.push((dict, &data[body_start..body_end]));
+    }
+
+    streams
+}
+
+// Decoded bodies of all Flate-compressed or unfiltered streams that are not images or fonts
+pub(crate) fn pdf_streams(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = Vec::new();
+
+    for (dict, body) in raw_pdf_streams(data) {
+        let is_binary_resource = find_bytes(dict, b"/Image").is_some()
+            || find_bytes(dict, b"/FontFile").is_some()
+            || find_bytes(dict, b"/Length1").is_some();
+        if is_binary_resource {
+            continue;
+        }
+
+        if find_bytes(dict, b"/FlateDecode").is_some() {
+            if let Some(decoded) = inflate(body) {
+                streams.push(decoded);
+            }
+        } else if find_bytes(dict, b"/Filter").is_none() {
+            streams.push(body.to_vec());
+        }
+    }
+
+    streams
+}
+
+// JPEG-encoded (DCTDecode) image XObjects, in file order. Scanners and phone
+// "scan to PDF" apps almost always embed each page this way.
+pub fn pdf_jpeg_images(data: &[u8]) -> Vec<Vec<u8>> {
+    raw_pdf_streams(data)
+        .into_iter()
+        .filter(|(dict, body)| {
+            find_bytes(dict, b"/Image").is_some()
+                && find_bytes(dict, b"/DCTDecode").is_some()
+                && body.starts_with(&[0xFF, 0xD8])
+        })
+        .map(|(_, body)| body.to_vec())
+        .collect()
+}
+
+fn rfind_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if haystack.len() < needle.len() {
+        return None;
+    }
+    (0..=haystack.len() - needle.len()).rev().find(|&i| &haystack[i..i + needle.len()] == needle)
+}
+
+enum Operand {
+    Text(Vec<u8>),
+    Number(f64),
+    Array(Vec<Operand>),
+    Other,
+}
+
+// Interprets the text-showing operators of a page content stream
+fn content_stream_text(stream: &[u8]) -> Option<String> {
+    let mut out = String::new();
+    let mut operands: Vec<Operand> = Vec::new();
+    let mut array_stack: Vec<Vec<Operand>> = Vec::new();
+    let mut saw_text_block = false;
+    let mut i = 0;
+
+    while i < stream.len() {
+        let b = stream[i];
+        if b.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let operand = match b {
+            b'%' => {
+                while i < stream.len() && stream[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'(' | b'<' if !stream[i..].starts_with(b"<<") => match lex_pdf_string(&stream[i..]) {
+                Some((bytes, consumed)) => {
+                    i += consumed;
+                    Operand::Text(bytes)
+                }
+                None => break,
+            },
+            b'[' => {
+                array_stack.push(Vec::new());
+                i += 1;
+                continue;
+            }
+            b']' => {
+                i += 1;
+                match array_stack.pop() {
+                    Some(items) => Operand::Array(items),
+                    None => continue,
+                }
+            }
+            b'/' => {
+                i += 1;
+                while i < stream.len() && !is_delimiter(stream[i]) {
+                    i += 1;
+                }
+                Operand::Other
+            }
+            b'<' | b'>' | b'{' | b'}' => {
+                i += if stream[i..].starts_with(b"<<") || stream[i..].starts_with(b">>") { 2 } else { 1 };
+                Operand::Other
+            }
+            b'0'..=b'9' | b'-' | b'+' | b'.' => {
+                let start = i;
+                i += 1;
+                while i < stream.len() && matches!(stream[i], b'0'..=b'9' | b'.') {
+                    i += 1;
+                }
+                let n = std::str::from_utf8(&stream[start..i]).ok().and_then(|s| s.parse().ok()).unwrap_or(0.0);
+                Operand::Number(n)
+            }
+            _ => {
+                let start = i;
+                while i < stream.len() && !is_delimiter(stream[i]) {
+                    i += 1;
+                }
+                if i == start {
+                    i += 1;
+                    continue;
+                }
+                let op = &stream[start..i];
+                apply_operator(op, &mut operands, &mut out, &mut saw_text_block);
+                operands.clear();
+                continue;
+            }
+        };
+
+        match array_stack.last_mut() {
+            Some(array) => array.push(operand),
+            None => operands.push(operand),
+        }
+    }
+
+    if saw_text_block {
+        Some(out)
+    } else {
+        None
+    }
+}
+
+fn is_delimiter(b: u8) -> bool {
+    b.is_ascii_whitespace() || matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+fn newline(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn apply_operator(op: &[u8], operands: &mut [Operand], out: &mut String, saw_text_block: &mut bool) {
+    match op {
+        b"BT" => *saw_text_block = true,
+        b"ET" | b"T*" => newline(out),
+        b"Td" | b"TD" => {
+            // Only a vertical move starts a new line
+            if let Some(Operand::Number(ty)) = operands.get(1) {
+                if *ty != 0.0 {
+                    newline(out);
+                }
+            }
+        }
+        b"Tm" => newline(out),
+        b"Tj" | b"'" | b"\"" => {
+            if op != b"Tj" {
+                newline(out);
+            }
+            if let Some(Operand::Text(bytes)) = operands.last() {
+                out.push_str(&decode_pdf_text(bytes));
+            }
+        }
+        b"TJ" => {
+            if let Some(Operand::Array(items)) = operands.last() {
+                for item in items {
+                    match item {
+                        Operand::Text(bytes) => out.push_str(&decode_pdf_text(bytes)),
+                        // Large negative kerning is how most writers encode a word gap
+                        Operand::Number(n) if *n < -200.0 => out.push(' '),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// --- DOCX ---
+
+fn extract_docx(data: &[u8]) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).ok()?;
+    let mut xml = String::new();
+    archive.by_name("word/document.xml").ok()?.read_to_string(&mut xml).ok()?;
+
+    let mut out = String::new();
+    let mut rest = xml.as_str();
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open..];
+        let close = match rest.find('>') {
+            Some(c) => c,
+            None => break,
+        };
+        let tag = &rest[1..close];
+        rest = &rest[close + 1..];
+
+        let name = tag.split_whitespace().next().unwrap_or("").trim_end_matches('/');
+        match name {
+            "w:t" if !tag.ends_with('/') => {
+                let end = rest.find("</w:t>").unwrap_or(rest.len());
+                out.push_str(&unescape_xml(&rest[..end]));
+                rest = &rest[end..];
+            }
+            "w:tab" => out.push('\t'),
+            "w:br" | "w:cr" | "/w:p" => out.push('\n'),
+            _ => {}
+        }
+    }
+
+    Some(out)
+}
+
+// --- EML / HTML ---
+
+fn extract_eml(data: &[u8]) -> Option<String> {
+    let message = email::parse_message(data);
+    let mut out = String::new();
+    for name in ["From", "To", "Cc", "Date", "Subject"] {
+        if let Some(value) = message.header(name) {
+            out.push_str(&format!("{}: {}\n", name, value));
+        }
+    }
+    out.push('\n');
+
+    if let Some(text) = &message.text_body {
+        out.push_str(text);
+    } else if let Some(html) = &message.html_body {
+        out.push_str(&html_to_text(html));
+    }
+
+    for attachment in &message.attachments {
+        out.push_str(&format!("\n[Attachment: {}]", attachment.filename));
+    }
+
+    Some(out)
+}
+
+pub fn html_to_text(html: &str) -> String {
+    let mut out = String::new();
+    let lower = html.to_ascii_lowercase();
+    let mut i = 0;
+
+    while i < html.len() {
+        if html.as_bytes()[i] != b'<' {
+            let next = html[i..].find('<').map(|p| i + p).unwrap_or(html.len());
+            out.push_str(&decode_entities(&html[i..next]));
+            i = next;
+            continue;
+        }
+
+        let close = match html[i..].find('>') {
+            Some(p) => i + p,
+            None => break,
+        };
+        let tag = lower[i + 1..close].trim_start_matches('/');
+        let name: String = tag.chars().take_while(|c| c.is_ascii_alphanumeric()).collect();
+
+        // Script and style bodies are never visible text
+        if (name == "script" || name == "style") && !lower[i + 1..close].starts_with('/') {
+            let end_tag = format!("</{}", name);
+            i = lower[close..].find(&end_tag).map(|p| close + p).unwrap_or(html.len());
+            continue;
+        }
+
+        if matches!(name.as_str(), "br" | "p" | "div" | "tr" | "li" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "table") {
+            newline(&mut out);
+        } else if name == "td" || name == "th" {
+            out.push('\t');
+        }
+        i = close + 1;
+    }
+
+    // Collapse runs of blank space left behind by markup
+    out.lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let semi = match rest.bytes().take(12).position(|b| b == b';') {
+            Some(p) => p,
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+            }
+            _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_content_stream_text() {
+        let pdf = b"%PDF-1.4\n4 0 obj\n<< /Length 60 >>\nstream\nBT /F1 12 Tf 72 700 Td (Invoice INV-0042) Tj 0 -14 Td [(Total) -250 (due)] TJ ET\nendstream\nendobj\n";
+        let text = extract_text("invoice.pdf", pdf).unwrap();
+        assert_eq!(text, "Invoice INV-0042\nTotal due");
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><style>p{}</style></head><body><p>Net&nbsp;30 &amp; more</p><div>Line two</div></body></html>";
+        assert_eq!(html_to_text(html), "Net 30 & more\nLine two");
+    }
+
+    #[test]
+    fn test_decode_entities_non_ascii() {
+        assert_eq!(decode_entities("Ромашка &Компания &amp; сын"), "Ромашка &Компания & сын");
+        assert_eq!(decode_entities("Fish &🐟🐟🐟; chips &#233;"), "Fish &🐟🐟🐟; chips é");
+    }
+}
@@ -0,0 +1,483 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use rusqlite::params;
+use serde_json::json;
+use crate::bulk::read_record;
+use crate::db::HuntDatabase;
+use crate::screening::{
+    self, address_key, address_key_from_text, fuzzy_key, iso_date, name_key, name_similarity, soundex, Finding,
+    ReferenceDataset, ReferenceDb, Subject,
+};
+
+// Healthcare provider checks. Two public files are imported:
+// - the HHS-OIG LEIE exclusion list (UPDATED.csv);
+// - the CMS NPPES NPI registry (npidata_pfile_*.csv, or the monthly zip).
+// The hunt's target and parties are matched against both by NPI, by fuzzy
+// name (Jaro-Winkler over names with legal forms, initials and titles
+// removed, among candidates sharing a Soundex code) and by address.
+
+pub const LEIE: &str = "oig_leie";
+pub const NPPES: &str = "nppes";
+
+// Minimum similarity for a fuzzy name match
+pub const NAME_THRESHOLD: f64 = 0.92;
+
+// Soundex block of a record: its last name for people, else the first word
+// of its name. Subjects are looked up under the code of each of their words.
+fn name_block(person_last: &str, name: &str) -> String {
+    let word = if person_last.trim().is_empty() { name } else { person_last };
+    name_key(word).split(' ').find(|w| w.len() > 1).map(soundex).unwrap_or_default()
+}
+
+fn header_lookup(header: &[String]) -> impl Fn(&[&str]) -> Option<usize> + '_ {
+    move |names: &[&str]| {
+        header.iter().position(|h| {
+            let h = h.trim().trim_start_matches('\u{feff}');
+            names.iter().any(|n| h.eq_ignore_ascii_case(n))
+        })
+    }
+}
+
+// LEIE NPIs are all zeros when unknown
+fn clean_npi(npi: &str) -> Option<String> {
+    let npi = npi.trim();
+    (!npi.is_empty() && npi.chars().any(|c| c != '0')).then(|| npi.to_string())
+}
+
+// Replaces the stored LEIE records with the file's
+pub fn import_leie(reference: &ReferenceDb, path: &Path) -> Result<ReferenceDataset, String> {
+    screening::read_extract(path, |n| n.ends_with(".csv"), |reader, file_name| {
+        let mut line = String::new();
+        let header = read_record(reader, &mut line).map_err(|e| e.to_string())?.ok_or("The file is empty")?;
+        let column = header_lookup(&header);
+        if column(&["EXCLTYPE"]).is_none() {
+            return Err("Not an LEIE file: no EXCLTYPE column".to_string());
+        }
+        let cols = [
+            "LASTNAME", "FIRSTNAME", "MIDNAME", "BUSNAME", "GENERAL", "SPECIALTY", "NPI", "DOB", "ADDRESS", "CITY", "STATE",
+            "ZIP", "EXCLTYPE", "EXCLDATE", "REINDATE", "WAIVERDATE",
+        ]
+        .map(|c| column(&[c]));
+
+        let tx = reference.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM leie_exclusions", []).map_err(|e| e.to_string())?;
+        let mut rows = 0;
+        let mut row_number = 1;
+        while let Some(values) = read_record(reader, &mut line).map_err(|e| e.to_string())? {
+            row_number += 1;
+            let get = |col: Option<usize>| col.and_then(|i| values.get(i)).map(|v| v.trim()).unwrap_or_default();
+            let [last, first, middle, business, general, specialty, npi, dob, address, city, state, zip, excl_type, excl_date, rein_date, waiver_date] =
+                cols.map(get);
+
+            let person = [first, middle, last].iter().filter(|p| !p.is_empty()).copied().collect::<Vec<_>>().join(" ");
+            let name = if business.is_empty() { person.as_str() } else { business };
+            let key = fuzzy_key(name);
+            if key.is_empty() {
+                continue;
+            }
+            let npi = clean_npi(npi);
+            // The list has no record id; NPI, or name, birth date and exclusion date identify a record
+            let record_key = npi.clone().unwrap_or_else(|| format!("{}|{}|{}", key, dob, excl_date));
+            tx.execute(
+                "INSERT INTO leie_exclusions (record_key, source_row, name, name_key, name_block, business_name, general, specialty, npi, dob, \
+                 address, city, state, zip, address_key, exclusion_type, exclusion_date, reinstatement_date, waiver_date) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+                params![
+                    record_key,
+                    row_number,
+                    name,
+                    key,
+                    name_block(if business.is_empty() { last } else { "" }, name),
+                    business,
+                    general,
+                    specialty,
+                    npi,
+                    iso_date(dob),
+                    address,
+                    city,
+                    state,
+                    zip,
+                    address_key(address, zip),
+                    excl_type,
+                    iso_date(excl_date),
+                    iso_date(rein_date),
+                    iso_date(waiver_date)
+                ],
+            ).map_err(|e| e.to_string())?;
+            rows += 1;
+        }
+        screening::finish_import(reference, tx, LEIE, file_name, rows)
+    })
+}
+
+// Replaces the stored NPI records with the file's
+pub fn import_nppes(reference: &ReferenceDb, path: &Path) -> Result<ReferenceDataset, String> {
+    let wanted = |n: &str| {
+        let base = n.rsplit('/').next().unwrap_or(n);
+        base.starts_with("npidata_pfile") && base.ends_with(".csv") && !base.contains("fileheader")
+    };
+    screening::read_extract(path, wanted, |reader, file_name| {
+        let mut line = String::new();
+        let header = read_record(reader, &mut line).map_err(|e| e.to_string())?.ok_or("The file is empty")?;
+        let column = header_lookup(&header);
+        let npi_col = column(&["NPI"]).ok_or("Not an NPPES file: no NPI column")?;
+        let cols = [
+            column(&["Entity Type Code"]),
+            column(&["Provider Organization Name (Legal Business Name)"]),
+            column(&["Provider Last Name (Legal Name)"]),
+            column(&["Provider First Name"]),
+            column(&["Provider Middle Name"]),
+            column(&["Provider First Line Business Practice Location Address"]),
+            column(&["Provider Business Practice Location Address City Name"]),
+            column(&["Provider Business Practice Location Address State Name"]),
+            column(&["Provider Business Practice Location Address Postal Code"]),
+            column(&["Healthcare Provider Taxonomy Code_1"]),
+            column(&["Provider Enumeration Date"]),
+            column(&["NPI Deactivation Date"]),
+        ];
+
+        let tx = reference.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM nppes_providers", []).map_err(|e| e.to_string())?;
+        let mut rows = 0;
+        let mut row_number = 1;
+        while let Some(values) = read_record(reader, &mut line).map_err(|e| e.to_string())? {
+            row_number += 1;
+            let get = |col: Option<usize>| col.and_then(|i| values.get(i)).map(|v| v.trim()).unwrap_or_default();
+            let npi = get(Some(npi_col));
+            let [entity_type, org_name, last, first, middle, address, city, state, zip, taxonomy, enumerated, deactivated] =
+                cols.map(get);
+
+            let person = [first, middle, last].iter().filter(|p| !p.is_empty()).copied().collect::<Vec<_>>().join(" ");
+            let name = if org_name.is_empty() { person.as_str() } else { org_name };
+            let key = fuzzy_key(name);
+            // Deactivated NPIs carry no name but are kept, so using one is caught
+            if npi.is_empty() || (key.is_empty() && deactivated.is_empty()) {
+                continue;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO nppes_providers (npi, source_row, entity_type, name, name_key, name_block, address, city, state, zip, \
+                 address_key, taxonomy, enumeration_date, deactivation_date) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    npi,
+                    row_number,
+                    entity_type,
+                    name,
+                    key,
+                    name_block(if org_name.is_empty() { last } else { "" }, name),
+                    address,
+                    city,
+                    state,
+                    zip,
+                    address_key(address, zip),
+                    taxonomy,
+                    iso_date(enumerated),
+                    iso_date(deactivated)
+                ],
+            ).map_err(|e| e.to_string())?;
+            rows += 1;
+        }
+        screening::finish_import(reference, tx, NPPES, file_name, rows)
+    })
+}
+
+// A reference record that matched a subject
+struct Hit {
+    record_key: String,
+    source_row: i64,
+    name: String,
+    npi: Option<String>,
+    detail: serde_json::Value,
+    matched_on: Vec<&'static str>,
+    name_score: Option<f64>,
+}
+
+// Finds the records of `table` matching a subject. `table` must have
+// record_key (or npi), source_row, name, name_key, name_block, npi and
+// address_key; `detail` maps a row to the fields shown on the finding.
+fn find_hits(
+    reference: &ReferenceDb,
+    table: &str,
+    key_column: &str,
+    subject: &Subject,
+    detail: impl Fn(&rusqlite::Row) -> rusqlite::Result<serde_json::Value>,
+) -> Result<Vec<Hit>, String> {
+    let err = |e: rusqlite::Error| e.to_string();
+    let subject_key = fuzzy_key(&subject.name);
+    let blocks: Vec<String> = subject_key.split(' ').filter(|w| !w.is_empty()).map(soundex).collect();
+    let address = subject.address.as_deref().and_then(address_key_from_text);
+
+    let mut criteria: Vec<(&'static str, &str, String)> = blocks.into_iter().map(|b| ("name", "name_block", b)).collect();
+    criteria.extend(subject.npi.iter().map(|n| ("npi", "npi", n.clone())));
+    criteria.extend(address.into_iter().map(|a| ("address", "address_key", a)));
+
+    let mut hits: BTreeMap<String, Hit> = BTreeMap::new();
+    for (basis, column, value) in &criteria {
+        let mut stmt = reference.conn.prepare(&format!(
+            "SELECT {key}, source_row, name, name_key, npi, * FROM {table} WHERE {column} = ?1",
+            key = key_column,
+            table = table,
+            column = column
+        )).map_err(err)?;
+        let rows = stmt.query_map([value], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?, r.get::<_, String>(2)?, r.get::<_, String>(3)?, r.get::<_, Option<String>>(4)?, detail(r)?))
+        }).map_err(err)?;
+        for row in rows {
+            let (record_key, source_row, name, candidate_key, npi, detail) = row.map_err(err)?;
+            let matched = match *basis {
+                // A shared Soundex code only makes a candidate
+                "name" => {
+                    let score = name_similarity(&subject_key, &candidate_key);
+                    if score < NAME_THRESHOLD {
+                        continue;
+                    }
+                    Some(score)
+                }
+                _ => None,
+            };
+            let hit = hits.entry(record_key.clone()).or_insert_with(|| Hit {
+                record_key,
+                source_row,
+                name,
+                npi,
+                detail,
+                matched_on: Vec::new(),
+                name_score: None,
+            });
+            if !hit.matched_on.contains(basis) {
+                hit.matched_on.push(basis);
+            }
+            if matched.is_some() {
+                hit.name_score = matched;
+            }
+        }
+    }
+    Ok(hits.into_values().collect())
+}
+
+fn new_finding(check: &str, subject: &Subject, source: &str, hit: &Hit, file: &Option<ReferenceDataset>) -> Finding {
+    let mut detail = hit.detail.clone();
+    detail["name_score"] = json!(hit.name_score.map(|s| (s * 1000.0).round() / 1000.0));
+    Finding {
+        id: 0,
+        check: check.to_string(),
+        severity: "info".to_string(),
+        subject_type: subject.subject_type.clone(),
+        subject_id: subject.subject_id.clone(),
+        subject_name: subject.name.clone(),
+        source: source.to_string(),
+        record_key: hit.record_key.clone(),
+        source_ref: format!("{}:{}", file.as_ref().map(|d| d.file_name.as_str()).unwrap_or("?"), hit.source_row),
+        matched_on: hit.matched_on.iter().map(|m| m.to_string()).collect(),
+        summary: String::new(),
+        detail,
+        status: "open".to_string(),
+        found_at: crate::db::now_timestamp(),
+    }
+}
+
+// Screens the hunt's target and parties against the imported LEIE and NPPES
+// files, records the hits as findings and returns them
+pub fn screen_hunt(reference: &ReferenceDb, db: &HuntDatabase) -> Result<Vec<Finding>, String> {
+    let err = |e: rusqlite::Error| e.to_string();
+    let leie_set = reference.dataset(LEIE).map_err(err)?;
+    let nppes_set = reference.dataset(NPPES).map_err(err)?;
+    if leie_set.is_none() && nppes_set.is_none() {
+        return Ok(Vec::new());
+    }
+    let mut found = Vec::new();
+
+    for subject in screening::subjects(db).map_err(err)? {
+        if leie_set.is_some() {
+            let hits = find_hits(reference, "leie_exclusions", "record_key", &subject, |r| {
+                Ok(json!({
+                    "name": r.get::<_, String>("name")?,
+                    "general": r.get::<_, Option<String>>("general")?,
+                    "specialty": r.get::<_, Option<String>>("specialty")?,
+                    "npi": r.get::<_, Option<String>>("npi")?,
+                    "dob": r.get::<_, Option<String>>("dob")?,
+                    "address": r.get::<_, Option<String>>("address")?,
+                    "city": r.get::<_, Option<String>>("city")?,
+                    "state": r.get::<_, Option<String>>("state")?,
+                    "zip": r.get::<_, Option<String>>("zip")?,
+                    "exclusion_type": r.get::<_, Option<String>>("exclusion_type")?,
+                    "exclusion_date": r.get::<_, Option<String>>("exclusion_date")?,
+                    "reinstatement_date": r.get::<_, Option<String>>("reinstatement_date")?,
+                    "waiver_date": r.get::<_, Option<String>>("waiver_date")?,
+                }))
+            })?;
+            for hit in hits {
+                let mut finding = new_finding("leie_exclusion", &subject, LEIE, &hit, &leie_set);
+                let npi = hit.matched_on.contains(&"npi");
+                let name = hit.matched_on.contains(&"name");
+                let address = hit.matched_on.contains(&"address");
+                finding.severity = if npi || (name && address) {
+                    "high"
+                } else if name {
+                    "medium"
+                } else {
+                    "info"
+                }
+                .to_string();
+                finding.summary = format!(
+                    "{} is on the OIG exclusion list ({}, excluded {}); matched on {}",
+                    hit.name,
+                    finding.detail["exclusion_type"].as_str().unwrap_or("?"),
+                    finding.detail["exclusion_date"].as_str().unwrap_or("?"),
+                    finding.matched_on.join(", ")
+                );
+                found.push(finding);
+            }
+        }
+
+        if nppes_set.is_some() {
+            let hits = find_hits(reference, "nppes_providers", "npi", &subject, |r| {
+                Ok(json!({
+                    "npi": r.get::<_, String>("npi")?,
+                    "name": r.get::<_, String>("name")?,
+                    "entity_type": r.get::<_, Option<String>>("entity_type")?,
+                    "address": r.get::<_, Option<String>>("address")?,
+                    "city": r.get::<_, Option<String>>("city")?,
+                    "state": r.get::<_, Option<String>>("state")?,
+                    "zip": r.get::<_, Option<String>>("zip")?,
+                    "taxonomy": r.get::<_, Option<String>>("taxonomy")?,
+                    "enumeration_date": r.get::<_, Option<String>>("enumeration_date")?,
+                    "deactivation_date": r.get::<_, Option<String>>("deactivation_date")?,
+                }))
+            })?;
+            for hit in hits {
+                // An address alone matches every provider in a building
+                if hit.matched_on == ["address"] {
+                    continue;
+                }
+                let mut finding = new_finding("nppes_provider", &subject, NPPES, &hit, &nppes_set);
+                let npi_only = hit.matched_on.contains(&"npi") && !hit.matched_on.contains(&"name");
+                let deactivated = finding.detail["deactivation_date"].as_str().map(str::to_string);
+                if let Some(date) = deactivated {
+                    finding.severity = "medium".to_string();
+                    finding.summary = format!("NPI {} was deactivated on {}", hit.npi.as_deref().unwrap_or("?"), date);
+                } else if npi_only {
+                    // The party's NPI belongs to someone else
+                    finding.severity = "medium".to_string();
+                    finding.summary = format!("NPI {} is registered to {}, not {}", hit.npi.as_deref().unwrap_or("?"), hit.name, subject.name);
+                } else {
+                    finding.summary = format!(
+                        "NPPES lists {} under NPI {}; matched on {}",
+                        hit.name,
+                        hit.npi.as_deref().unwrap_or("?"),
+                        finding.matched_on.join(", ")
+                    );
+                }
+                found.push(finding);
+            }
+        }
+    }
+
+    let tx = db.conn.unchecked_transaction().map_err(err)?;
+    let mut ids = Vec::new();
+    for finding in &found {
+        ids.push(db.record_finding(finding).map_err(err)?);
+    }
+    tx.commit().map_err(err)?;
+    Ok(db.findings().map_err(err)?.into_iter().filter(|f| ids.contains(&f.id)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_helpers() {
+        assert_eq!(soundex("Robert"), "R163");
+        assert_eq!(soundex("Rupert"), "R163");
+        assert_eq!(soundex("Ashcraft"), "A261");
+        assert_eq!(soundex("Tymczak"), "T522");
+        assert_eq!(fuzzy_key("Dr. John Q. Smith, MD"), "JOHN SMITH");
+        assert!(name_similarity(&fuzzy_key("Jon Smith"), &fuzzy_key("JOHN SMITH")) >= NAME_THRESHOLD);
+        assert!(name_similarity(&fuzzy_key("John Smith"), &fuzzy_key("Joan Smythe-Brown")) < NAME_THRESHOLD);
+        assert_eq!(address_key_from_text("1 Main Street, Suite 2, St Paul, MN 55101-1234").as_deref(), Some("1 MAIN ST 55101"));
+    }
+
+    #[test]
+    fn test_screen_parties_against_leie_and_nppes() {
+        let base = std::env::temp_dir().join(format!("os-leie-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&base).unwrap();
+        let leie = base.join("UPDATED.csv");
+        std::fs::write(&leie, "LASTNAME,FIRSTNAME,MIDNAME,BUSNAME,GENERAL,SPECIALTY,UPIN,NPI,DOB,ADDRESS,CITY,STATE,ZIP,EXCLTYPE,EXCLDATE,REINDATE,WAIVERDATE,WVRSTATE\n\
+            SMITH,JOHN,Q,,IND- LIC HC SERV PRO,PHYSICIAN,,0000000000,19600101,1 MAIN ST,ST PAUL,MN,55101,1128a1,20200115,00000000,00000000,\n\
+            ,,,SUNRISE HOME HEALTH LLC,BUSINESS,HOME HEALTH,,1234567893,,9 ELM AVE,DULUTH,MN,55802,1128b7,20190301,00000000,00000000,\n").unwrap();
+
+        // NPPES ships as a zip holding the data file and its header-only twin
+        let nppes = base.join("NPPES_Data_Dissemination.zip");
+        let header = "\"NPI\",\"Entity Type Code\",\"Provider Organization Name (Legal Business Name)\",\"Provider Last Name (Legal Name)\",\"Provider First Name\",\"Provider Middle Name\",\"Provider First Line Business Practice Location Address\",\"Provider Business Practice Location Address City Name\",\"Provider Business Practice Location Address State Name\",\"Provider Business Practice Location Address Postal Code\",\"Healthcare Provider Taxonomy Code_1\",\"Provider Enumeration Date\",\"NPI Deactivation Date\"\n";
+        let data = format!("{}\
+            \"1111111112\",\"1\",\"\",\"SMITH\",\"JON\",\"\",\"1 MAIN ST\",\"ST PAUL\",\"MN\",\"551011234\",\"207Q00000X\",\"05/23/2005\",\"\"\n\
+            \"2222222220\",\"\",\"\",\"\",\"\",\"\",\"\",\"\",\"\",\"\",\"\",\"\",\"06/01/2018\"\n", header);
+        {
+            use std::io::Write;
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&nppes).unwrap());
+            for (name, body) in [("npidata_pfile_20050523-20240107_fileheader.csv", header.to_string()), ("npidata_pfile_20050523-20240107.csv", data)] {
+                zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+                zip.write_all(body.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let reference = ReferenceDb::open(":memory:").unwrap();
+        assert_eq!(import_leie(&reference, &leie).unwrap().rows, 2);
+        assert_eq!(import_nppes(&reference, &nppes).unwrap().rows, 2);
+
+        let db = HuntDatabase::open(":memory:").unwrap();
+        db.init_case("Test").unwrap();
+        db.set_case_details(Some("Sunrise Home Health, Inc."), None, None).unwrap();
+        db.conn.execute(
+            "INSERT INTO parties (name, role, npi, address) VALUES ('Dr. Jon Smith', 'Other', NULL, '1 Main Street, St Paul, MN 55101'), \
+             ('Mary Jones', 'Other', '2222222220', NULL)",
+            [],
+        ).unwrap();
+
+        let findings = screen_hunt(&reference, &db).unwrap();
+        let summary: Vec<(&str, &str, Option<&str>, &str)> = findings
+            .iter()
+            .map(|f| (f.check.as_str(), f.subject_type.as_str(), f.subject_id.as_deref(), f.severity.as_str()))
+            .collect();
+        assert_eq!(summary, [
+            ("leie_exclusion", "party", Some("1"), "high"),
+            ("leie_exclusion", "target", None, "medium"),
+            ("nppes_provider", "party", Some("2"), "medium"),
+            ("nppes_provider", "party", Some("1"), "info"),
+        ]);
+        let smith = &findings[0];
+        assert_eq!(smith.matched_on, ["name", "address"]);
+        assert_eq!(smith.source_ref, "UPDATED.csv:2");
+        assert_eq!(findings[2].source_ref, "npidata_pfile_20050523-20240107.csv:3");
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_parties_screened_when_hunt_has_no_target() {
+        let base = std::env::temp_dir().join(format!("os-leie-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&base).unwrap();
+        let exclusions = base.join("SAM_Exclusions_Public_Extract_V2.CSV");
+        std::fs::write(&exclusions, "\"Classification\",\"Name\",\"Unique Entity ID\",\"Excluding Agency\",\"Active Date\",\"Termination Date\",\"SAM Number\"\n\
+            \"Firm\",\"ACME CORPORATION\",\"\",\"HHS\",\"03/01/2021\",\"Indefinite\",\"S1\"\n").unwrap();
+        let leie = base.join("UPDATED.csv");
+        std::fs::write(&leie, "LASTNAME,FIRSTNAME,MIDNAME,BUSNAME,GENERAL,SPECIALTY,UPIN,NPI,DOB,ADDRESS,CITY,STATE,ZIP,EXCLTYPE,EXCLDATE,REINDATE,WAIVERDATE,WVRSTATE\n\
+            SMITH,JOHN,Q,,IND- LIC HC SERV PRO,PHYSICIAN,,0000000000,19600101,1 MAIN ST,ST PAUL,MN,55101,1128a1,20200115,00000000,00000000,\n").unwrap();
+
+        let reference = ReferenceDb::open(":memory:").unwrap();
+        crate::sam::import_exclusions(&reference, &exclusions).unwrap();
+        import_leie(&reference, &leie).unwrap();
+
+        let db = HuntDatabase::open(":memory:").unwrap();
+        db.init_case("Test").unwrap();
+        db.conn.execute("INSERT INTO parties (name, role) VALUES ('John Smith', 'Other')", []).unwrap();
+
+        // SAM only screens the target, so it finds nothing rather than failing
+        assert!(crate::sam::screen_target(&reference, &db).unwrap().is_empty());
+        let findings = screen_hunt(&reference, &db).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!((findings[0].check.as_str(), findings[0].subject_id.as_deref()), ("leie_exclusion", Some("1")));
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
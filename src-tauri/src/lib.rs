@@ -4,8 +4,29 @@ pub mod db;
 pub mod bundle;
 pub mod usaspending;
 pub mod pdf;
+pub mod metadata;
+pub mod evidence;
+pub mod email;
+pub mod extract;
+pub mod search;
+pub mod ocr;
+pub mod preview;
+pub mod shred;
+pub mod trash;
+pub mod snapshot;
+pub mod case;
+pub mod template;
+pub mod cache;
+pub mod bulk;
+pub mod sources;
+#[cfg(feature = "source-awards-file")]
+pub mod awards_file;
+pub mod screening;
+pub mod sam;
+pub mod leie;
 
 use crypto::AppState;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -13,6 +34,27 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .manage(AppState::new())
+        .setup(|app| {
+            // Bundled Tesseract language data for the optional OCR stage
+            if let Ok(resources) = app.path().resource_dir() {
+                let tessdata = resources.join("tessdata");
+                if tessdata.exists() {
+                    ocr::set_tessdata_dir(tessdata);
+                }
+                // Bundled pdfium for PDF page previews
+                let pdfium = resources.join("pdfium");
+                if pdfium.exists() {
+                    preview::set_pdfium_dir(pdfium);
+                }
+            }
+
+            // Scheduled encrypted snapshots (only run while the vault is unlocked)
+            if let Ok(data_root) = app.path().app_local_data_dir() {
+                let key = app.state::<AppState>().key.clone();
+                snapshot::start_scheduler(data_root, key);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::get_salt,
             commands::unlock_vault,
@@ -38,7 +80,67 @@ pub fn run() {
             commands::add_hunt_evidence,
             commands::add_hunt_evidence_bytes,
             commands::delete_hunt_evidence,
-            commands::purge_vault_cache
+            commands::export_evidence_cmd,
+            commands::purge_vault_cache,
+            commands::inspect_file_metadata,
+            commands::search_hunt,
+            commands::ingest_mailbox,
+            commands::get_email_messages,
+            commands::ingest_directory,
+            commands::cancel_ingest,
+            commands::run_evidence_ocr,
+            commands::get_evidence_preview,
+            commands::set_evidence_category,
+            commands::set_evidence_tags,
+            commands::reorder_exhibits,
+            commands::link_event_evidence,
+            commands::unlink_event_evidence,
+            commands::link_event_party,
+            commands::unlink_event_party,
+            commands::get_event_evidence,
+            commands::get_event_parties,
+            commands::get_evidence_events,
+            commands::get_party_events,
+            commands::list_trash,
+            commands::restore_event,
+            commands::restore_party,
+            commands::restore_evidence,
+            commands::list_trashed_hunts,
+            commands::restore_hunt,
+            commands::empty_trash,
+            commands::create_snapshot,
+            commands::list_snapshots,
+            commands::restore_snapshot,
+            commands::get_snapshot_policy,
+            commands::set_snapshot_policy,
+            commands::update_hunt_case,
+            commands::set_hunt_status,
+            commands::get_hunt_status_history,
+            commands::attach_target_awards,
+            commands::import_bulk_awards,
+            commands::detach_target_award,
+            commands::get_target_awards,
+            commands::list_hunt_templates,
+            commands::import_hunt_template,
+            commands::get_hunt_template,
+            commands::get_hunt_checklist,
+            commands::set_checklist_item,
+            commands::search_recipients,
+            commands::get_recipient_profile,
+            commands::confirm_target_entity,
+            commands::get_target_entity,
+            commands::fetch_award_detail,
+            commands::get_award_detail,
+            commands::plot_award_transactions,
+            commands::list_target_sources,
+            commands::import_reference_extract,
+            commands::list_reference_datasets,
+            commands::screen_hunt,
+            commands::get_hunt_findings,
+            commands::set_finding_status,
+            commands::list_cached_searches,
+            commands::get_cached_search,
+            commands::clear_scout_cache
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,871 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+
+// Read-only inspection of the metadata that `crypto::strip_metadata` would remove.
+// Nothing in here modifies or persists the input bytes.
+
+#[derive(Serialize, Debug, Default)]
+pub struct MetadataReport {
+    pub format: String,
+    pub exif: BTreeMap<String, String>,
+    pub gps: Option<GpsInfo>,
+    pub xmp: Option<String>,
+    pub text_chunks: Vec<TextChunk>,
+    pub comments: Vec<String>,
+    pub document_properties: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct GpsInfo {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude: Option<f64>,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TextChunk {
+    pub chunk_type: String,
+    pub keyword: String,
+    pub text: String,
+}
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+const XMP_APP1_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+pub fn inspect_metadata(data: &[u8]) -> MetadataReport {
+    if data.len() >= 4 && data[0] == 0xFF && data[1] == 0xD8 {
+        inspect_jpeg(data)
+    } else if data.len() >= 8 && data[0..8] == PNG_SIGNATURE {
+        inspect_png(data)
+    } else if data.starts_with(b"%PDF") {
+        inspect_pdf(data)
+    } else if data.starts_with(b"PK\x03\x04") {
+        inspect_ooxml(data)
+    } else {
+        MetadataReport {
+            format: "unknown".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+fn inspect_jpeg(data: &[u8]) -> MetadataReport {
+    let mut report = MetadataReport {
+        format: "jpeg".to_string(),
+        ..Default::default()
+    };
+
+    let mut i = 2;
+    while i + 4 <= data.len() {
+        if data[i] != 0xFF {
+            break;
+        }
+        let marker = data[i + 1];
+        if marker == 0xD8 || marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            i += 2;
+            continue;
+        }
+        // Start of scan / end of image: no more metadata segments follow
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+
+        let len = ((data[i + 2] as usize) << 8) | (data[i + 3] as usize);
+        if len < 2 || i + 2 + len > data.len() {
+            break;
+        }
+        let payload = &data[i + 4..i + 2 + len];
+
+        if marker == 0xE1 && payload.starts_with(b"Exif\0\0") {
+            parse_exif(&payload[6..], &mut report);
+        } else if marker == 0xE1 && payload.starts_with(XMP_APP1_HEADER) {
+            let xmp = String::from_utf8_lossy(&payload[XMP_APP1_HEADER.len()..]).into_owned();
+            report.xmp = Some(xmp);
+        } else if marker == 0xFE {
+            report.comments.push(String::from_utf8_lossy(payload).into_owned());
+        }
+
+        i += 2 + len;
+    }
+
+    report
+}
+
+fn inspect_png(data: &[u8]) -> MetadataReport {
+    let mut report = MetadataReport {
+        format: "png".to_string(),
+        ..Default::default()
+    };
+
+    let mut i = 8;
+    while i + 8 <= data.len() {
+        let length = u32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]) as usize;
+        let chunk_type = &data[i + 4..i + 8];
+        if i + 12 + length > data.len() {
+            break;
+        }
+        let body = &data[i + 8..i + 8 + length];
+
+        match chunk_type {
+            b"eXIf" => parse_exif(body, &mut report),
+            b"tEXt" => {
+                if let Some((keyword, text)) = split_keyword(body) {
+                    report.text_chunks.push(TextChunk {
+                        chunk_type: "tEXt".to_string(),
+                        keyword,
+                        text: latin1_to_string(text),
+                    });
+                }
+            }
+            b"zTXt" => {
+                // keyword \0 compression-method compressed-text
+                if let Some((keyword, rest)) = split_keyword(body) {
+                    if rest.len() > 1 {
+                        if let Some(inflated) = inflate(&rest[1..]) {
+                            report.text_chunks.push(TextChunk {
+                                chunk_type: "zTXt".to_string(),
+                                keyword,
+                                text: latin1_to_string(&inflated),
+                            });
+                        }
+                    }
+                }
+            }
+            b"iTXt" => {
+                if let Some(chunk) = parse_itxt(body) {
+                    if chunk.keyword == "XML:com.adobe.xmp" {
+                        report.xmp = Some(chunk.text);
+                    } else {
+                        report.text_chunks.push(chunk);
+                    }
+                }
+            }
+            b"IEND" => break,
+            _ => {}
+        }
+
+        i += 12 + length;
+    }
+
+    report
+}
+
+fn parse_itxt(body: &[u8]) -> Option<TextChunk> {
+    // keyword \0 compression-flag compression-method language \0 translated-keyword \0 text
+    let (keyword, rest) = split_keyword(body)?;
+    if rest.len() < 2 {
+        return None;
+    }
+    let compressed = rest[0] == 1;
+    let rest = &rest[2..];
+    let lang_end = rest.iter().position(|&b| b == 0)?;
+    let rest = &rest[lang_end + 1..];
+    let translated_end = rest.iter().position(|&b| b == 0)?;
+    let text_bytes = &rest[translated_end + 1..];
+
+    let text = if compressed {
+        String::from_utf8_lossy(&inflate(text_bytes)?).into_owned()
+    } else {
+        String::from_utf8_lossy(text_bytes).into_owned()
+    };
+
+    Some(TextChunk {
+        chunk_type: "iTXt".to_string(),
+        keyword,
+        text,
+    })
+}
+
+fn split_keyword(body: &[u8]) -> Option<(String, &[u8])> {
+    let pos = body.iter().position(|&b| b == 0)?;
+    Some((latin1_to_string(&body[..pos]), &body[pos + 1..]))
+}
+
+pub(crate) fn latin1_to_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+pub(crate) fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    let mut decoder = flate2::read::ZlibDecoder::new(data);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out).ok()?;
+    Some(out)
+}
+
+// --- EXIF (TIFF structure) ---
+
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let b = self.data.get(offset..offset + 2)?;
+        Some(if self.little_endian {
+            u16::from_le_bytes([b[0], b[1]])
+        } else {
+            u16::from_be_bytes([b[0], b[1]])
+        })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        let b = self.data.get(offset..offset + 4)?;
+        Some(if self.little_endian {
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        } else {
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+        })
+    }
+}
+
+struct IfdEntry {
+    tag: u16,
+    field_type: u16,
+    count: u32,
+    // Offset of the value bytes within the TIFF block
+    value_offset: usize,
+}
+
+fn type_size(field_type: u16) -> usize {
+    match field_type {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 => 4,
+        5 | 10 | 12 => 8,
+        _ => 0,
+    }
+}
+
+fn read_ifd(tiff: &Tiff, offset: usize) -> Vec<IfdEntry> {
+    let mut entries = Vec::new();
+    let count = match tiff.u16_at(offset) {
+        Some(c) => c as usize,
+        None => return entries,
+    };
+
+    for n in 0..count {
+        let base = offset + 2 + n * 12;
+        let (tag, field_type, value_count) = match (tiff.u16_at(base), tiff.u16_at(base + 2), tiff.u32_at(base + 4)) {
+            (Some(t), Some(ft), Some(c)) => (t, ft, c),
+            _ => break,
+        };
+        let total = type_size(field_type).saturating_mul(value_count as usize);
+        let value_offset = if total <= 4 {
+            base + 8
+        } else {
+            match tiff.u32_at(base + 8) {
+                Some(o) => o as usize,
+                None => continue,
+            }
+        };
+        entries.push(IfdEntry {
+            tag,
+            field_type,
+            count: value_count,
+            value_offset,
+        });
+    }
+
+    entries
+}
+
+fn rationals(tiff: &Tiff, entry: &IfdEntry) -> Vec<f64> {
+    (0..(entry.count as usize).min(16))
+        .filter_map(|n| {
+            let off = entry.value_offset + n * 8;
+            let num = tiff.u32_at(off)?;
+            let den = tiff.u32_at(off + 4)?;
+            if entry.field_type == 10 {
+                let (num, den) = (num as i32, den as i32);
+                if den == 0 { None } else { Some(num as f64 / den as f64) }
+            } else if den == 0 {
+                None
+            } else {
+                Some(num as f64 / den as f64)
+            }
+        })
+        .collect()
+}
+
+fn format_value(tiff: &Tiff, entry: &IfdEntry) -> String {
+    let count = entry.count as usize;
+    match entry.field_type {
+        2 => {
+            let bytes = tiff.data.get(entry.value_offset..entry.value_offset + count).unwrap_or(&[]);
+            String::from_utf8_lossy(bytes).trim_end_matches('\0').trim().to_string()
+        }
+        1 | 7 => {
+            let bytes = tiff.data.get(entry.value_offset..entry.value_offset + count.min(64)).unwrap_or(&[]);
+            // Undefined blobs (maker notes etc.) are shown as text only when printable
+            if !bytes.is_empty() && bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ' || *b == 0) {
+                String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string()
+            } else {
+                format!("<{} bytes>", count)
+            }
+        }
+        3 => (0..count.min(16))
+            .filter_map(|n| tiff.u16_at(entry.value_offset + n * 2))
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        4 => (0..count.min(16))
+            .filter_map(|n| tiff.u32_at(entry.value_offset + n * 4))
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        9 => (0..count.min(16))
+            .filter_map(|n| tiff.u32_at(entry.value_offset + n * 4))
+            .map(|v| (v as i32).to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        5 | 10 => rationals(tiff, entry)
+            .iter()
+            .map(|v| format!("{}", v))
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => format!("<{} values>", count),
+    }
+}
+
+fn exif_tag_name(tag: u16) -> Option<&'static str> {
+    Some(match tag {
+        0x010E => "ImageDescription",
+        0x010F => "Make",
+        0x0110 => "Model",
+        0x0112 => "Orientation",
+        0x011A => "XResolution",
+        0x011B => "YResolution",
+        0x0131 => "Software",
+        0x0132 => "DateTime",
+        0x013B => "Artist",
+        0x8298 => "Copyright",
+        0x829A => "ExposureTime",
+        0x829D => "FNumber",
+        0x8827 => "ISOSpeedRatings",
+        0x9003 => "DateTimeOriginal",
+        0x9004 => "DateTimeDigitized",
+        0x9010 => "OffsetTime",
+        0x9011 => "OffsetTimeOriginal",
+        0x920A => "FocalLength",
+        0x9286 => "UserComment",
+        0xA002 => "PixelXDimension",
+        0xA003 => "PixelYDimension",
+        0xA420 => "ImageUniqueID",
+        0xA430 => "CameraOwnerName",
+        0xA431 => "BodySerialNumber",
+        0xA433 => "LensMake",
+        0xA434 => "LensModel",
+        0xA435 => "LensSerialNumber",
+        _ => return None,
+    })
+}
+
+fn parse_exif(tiff_data: &[u8], report: &mut MetadataReport) {
+    let little_endian = match tiff_data.get(0..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => return,
+    };
+    let tiff = Tiff { data: tiff_data, little_endian };
+    let ifd0 = match tiff.u32_at(4) {
+        Some(o) => o as usize,
+        None => return,
+    };
+
+    let mut sub_ifds = Vec::new();
+    let mut gps_ifd = None;
+
+    for entry in read_ifd(&tiff, ifd0) {
+        match entry.tag {
+            0x8769 => sub_ifds.push(tiff.u32_at(entry.value_offset).unwrap_or(0) as usize),
+            0x8825 => gps_ifd = tiff.u32_at(entry.value_offset).map(|o| o as usize),
+            _ => record_tag(&tiff, &entry, report),
+        }
+    }
+
+    for offset in sub_ifds.into_iter().filter(|o| *o != 0) {
+        for entry in read_ifd(&tiff, offset) {
+            record_tag(&tiff, &entry, report);
+        }
+    }
+
+    if let Some(offset) = gps_ifd {
+        report.gps = Some(parse_gps(&tiff, offset));
+    }
+}
+
+fn record_tag(tiff: &Tiff, entry: &IfdEntry, report: &mut MetadataReport) {
+    let name = match exif_tag_name(entry.tag) {
+        Some(n) => n.to_string(),
+        None => format!("Tag0x{:04X}", entry.tag),
+    };
+    report.exif.insert(name, format_value(tiff, entry));
+}
+
+fn parse_gps(tiff: &Tiff, offset: usize) -> GpsInfo {
+    let mut gps = GpsInfo::default();
+    let mut lat_ref = "N".to_string();
+    let mut lon_ref = "E".to_string();
+    let mut alt_below_sea = false;
+    let mut time = None;
+    let mut date = None;
+
+    for entry in read_ifd(tiff, offset) {
+        match entry.tag {
+            0x0001 => lat_ref = format_value(tiff, &entry),
+            0x0002 => gps.latitude = dms_to_decimal(&rationals(tiff, &entry)),
+            0x0003 => lon_ref = format_value(tiff, &entry),
+            0x0004 => gps.longitude = dms_to_decimal(&rationals(tiff, &entry)),
+            0x0005 => {
+                alt_below_sea = tiff.data.get(entry.value_offset).copied() == Some(1);
+            }
+            0x0006 => gps.altitude = rationals(tiff, &entry).first().copied(),
+            0x0007 => {
+                let parts = rationals(tiff, &entry);
+                if parts.len() == 3 {
+                    time = Some(format!("{:02}:{:02}:{:02}", parts[0] as u32, parts[1] as u32, parts[2] as u32));
+                }
+            }
+            0x001D => date = Some(format_value(tiff, &entry)),
+            _ => {}
+        }
+    }
+
+    if lat_ref.eq_ignore_ascii_case("S") {
+        gps.latitude = gps.latitude.map(|v| -v);
+    }
+    if lon_ref.eq_ignore_ascii_case("W") {
+        gps.longitude = gps.longitude.map(|v| -v);
+    }
+    if alt_below_sea {
+        gps.altitude = gps.altitude.map(|v| -v);
+    }
+    gps.timestamp = match (date, time) {
+        (Some(d), Some(t)) => Some(format!("{} {} UTC", d, t)),
+        (Some(d), None) => Some(d),
+        (None, Some(t)) => Some(format!("{} UTC", t)),
+        (None, None) => None,
+    };
+
+    gps
+}
+
+fn dms_to_decimal(parts: &[f64]) -> Option<f64> {
+    if parts.len() < 3 {
+        return None;
+    }
+    Some(parts[0] + parts[1] / 60.0 + parts[2] / 3600.0)
+}
+
+// --- PDF ---
+
+const PDF_INFO_KEYS: [&str; 8] = [
+    "Title", "Author", "Subject", "Keywords", "Creator", "Producer", "CreationDate", "ModDate",
+];
+
+fn inspect_pdf(data: &[u8]) -> MetadataReport {
+    let mut report = MetadataReport {
+        format: "pdf".to_string(),
+        ..Default::default()
+    };
+
+    // Only the Info dictionary counts; outline items and annotations have
+    // their own /Title entries
+    let info = pdf_info_object(data).unwrap_or(&[]);
+    for key in PDF_INFO_KEYS {
+        let needle = format!("/{}", key);
+        let mut search_from = 0;
+        while let Some(pos) = find_bytes(&info[search_from..], needle.as_bytes()) {
+            let start = search_from + pos + needle.len();
+            search_from = start;
+            // Skip things like /TitleFoo
+            if info.get(start).is_some_and(|b| b.is_ascii_alphanumeric()) {
+                continue;
+            }
+            if let Some(value) = read_pdf_string(&info[start..]) {
+                report.document_properties.insert(key.to_string(), value);
+                break;
+            }
+        }
+    }
+
+    if let Some(start) = find_bytes(data, b"<x:xmpmeta") {
+        if let Some(len) = find_bytes(&data[start..], b"</x:xmpmeta>") {
+            let end = start + len + b"</x:xmpmeta>".len();
+            report.xmp = Some(String::from_utf8_lossy(&data[start..end]).into_owned());
+        }
+    }
+
+    report
+}
+
+// Body of the Info object named by the last trailer (or cross-reference
+// stream). Later revisions are appended to the file, so the last trailer and
+// the last copy of the object win. Info dictionaries inside compressed object
+// streams are not read.
+fn pdf_info_object(data: &[u8]) -> Option<&[u8]> {
+    let mut reference = None;
+    let mut search_from = 0;
+    while let Some(pos) = find_bytes(&data[search_from..], b"/Info") {
+        let start = search_from + pos + b"/Info".len();
+        search_from = start;
+        if let Some(r) = read_pdf_reference(&data[start..]) {
+            reference = Some(r);
+        }
+    }
+    let (number, generation) = reference?;
+
+    let header = format!("{} {} obj", number, generation);
+    let mut body_start = None;
+    let mut search_from = 0;
+    while let Some(pos) = find_bytes(&data[search_from..], header.as_bytes()) {
+        let at = search_from + pos;
+        search_from = at + header.len();
+        // Skip "11 0 obj" when looking for "1 0 obj"
+        if at == 0 || !data[at - 1].is_ascii_digit() {
+            body_start = Some(search_from);
+        }
+    }
+    let start = body_start?;
+    let len = find_bytes(&data[start..], b"endobj").unwrap_or(data.len() - start);
+    Some(&data[start..start + len])
+}
+
+// An indirect reference such as " 12 0 R"
+fn read_pdf_reference(data: &[u8]) -> Option<(u32, u16)> {
+    let text = String::from_utf8_lossy(&data[..data.len().min(32)]);
+    let mut parts = text.split(|c: char| c.is_ascii_whitespace()).filter(|p| !p.is_empty());
+    let number = parts.next()?.parse().ok()?;
+    let generation = parts.next()?.parse().ok()?;
+    parts.next()?.starts_with('R').then_some((number, generation))
+}
+
+pub(crate) fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn read_pdf_string(data: &[u8]) -> Option<String> {
+    let start = data.iter().position(|b| !b.is_ascii_whitespace())?;
+    let (bytes, _) = lex_pdf_string(&data[start..])?;
+    Some(decode_pdf_text(&bytes))
+}
+
+// Lexes a literal `( ... )` or hex `< ... >` string starting at `data[0]`.
+// Returns the raw string bytes and the number of input bytes consumed.
+pub(crate) fn lex_pdf_string(data: &[u8]) -> Option<(Vec<u8>, usize)> {
+    match data.first()? {
+        b'(' => {
+            let mut depth = 1;
+            let mut out = Vec::new();
+            let mut i = 1;
+            while i < data.len() {
+                let b = data[i];
+                match b {
+                    b'\\' => {
+                        i += 1;
+                        match data.get(i)? {
+                            b'n' => out.push(b'\n'),
+                            b'r' => out.push(b'\r'),
+                            b't' => out.push(b'\t'),
+                            b'b' => out.push(0x08),
+                            b'f' => out.push(0x0C),
+                            b'\r' | b'\n' => {}
+                            d @ b'0'..=b'7' => {
+                                let mut value = (d - b'0') as u32;
+                                for _ in 0..2 {
+                                    match data.get(i + 1) {
+                                        Some(n @ b'0'..=b'7') => {
+                                            value = value * 8 + (n - b'0') as u32;
+                                            i += 1;
+                                        }
+                                        _ => break,
+                                    }
+                                }
+                                out.push(value as u8);
+                            }
+                            other => out.push(*other),
+                        }
+                    }
+                    b'(' => {
+                        depth += 1;
+                        out.push(b);
+                    }
+                    b')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some((out, i + 1));
+                        }
+                        out.push(b);
+                    }
+                    _ => out.push(b),
+                }
+                i += 1;
+            }
+            None
+        }
+        b'<' if data.get(1) != Some(&b'<') => {
+            let end = data.iter().position(|&b| b == b'>')?;
+            let hex: Vec<u8> = data[1..end].iter().copied().filter(|b| b.is_ascii_hexdigit()).collect();
+            let bytes = hex
+                .chunks(2)
+                .filter_map(|pair| {
+                    let s = std::str::from_utf8(pair).ok()?;
+                    // A trailing odd nibble is padded with zero per the PDF spec
+                    u8::from_str_radix(&format!("{:0<2}", s), 16).ok()
+                })
+                .collect::<Vec<_>>();
+            Some((bytes, end + 1))
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn decode_pdf_text(bytes: &[u8]) -> String {
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = bytes[2..]
+            .chunks(2)
+            .filter(|c| c.len() == 2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        latin1_to_string(bytes)
+    }
+}
+
+// --- Office Open XML (docx / xlsx / pptx) ---
+
+fn inspect_ooxml(data: &[u8]) -> MetadataReport {
+    let mut report = MetadataReport {
+        format: "zip".to_string(),
+        ..Default::default()
+    };
+
+    let mut archive = match zip::ZipArchive::new(Cursor::new(data)) {
+        Ok(a) => a,
+        Err(_) => return report,
+    };
+
+    let names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+    if names.iter().any(|n| n.starts_with("word/")) {
+        report.format = "docx".to_string();
+    } else if names.iter().any(|n| n.starts_with("xl/")) {
+        report.format = "xlsx".to_string();
+    } else if names.iter().any(|n| n.starts_with("ppt/")) {
+        report.format = "pptx".to_string();
+    }
+
+    for part in ["docProps/core.xml", "docProps/app.xml", "docProps/custom.xml"] {
+        let xml = match archive.by_name(part) {
+            Ok(mut f) => {
+                let mut s = String::new();
+                if f.read_to_string(&mut s).is_err() {
+                    continue;
+                }
+                s
+            }
+            Err(_) => continue,
+        };
+        if part.ends_with("custom.xml") {
+            collect_custom_properties(&xml, &mut report.document_properties);
+        } else {
+            collect_leaf_elements(&xml, &mut report.document_properties);
+        }
+    }
+
+    report
+}
+
+// Collects `<ns:Name ...>text</ns:Name>` leaf elements keyed by local name.
+fn collect_leaf_elements(xml: &str, out: &mut BTreeMap<String, String>) {
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        if rest.starts_with('/') || rest.starts_with('?') || rest.starts_with('!') {
+            continue;
+        }
+        let tag_end = match rest.find('>') {
+            Some(e) => e,
+            None => break,
+        };
+        let tag = &rest[..tag_end];
+        if tag.ends_with('/') {
+            continue;
+        }
+        let qname = tag.split_whitespace().next().unwrap_or("");
+        let after = &rest[tag_end + 1..];
+        let text_end = match after.find('<') {
+            Some(e) => e,
+            None => break,
+        };
+        let closing = format!("</{}>", qname);
+        if after[text_end..].starts_with(&closing) {
+            let text = unescape_xml(after[..text_end].trim());
+            if !text.is_empty() {
+                let local = qname.rsplit(':').next().unwrap_or(qname);
+                out.insert(local.to_string(), text);
+            }
+        }
+    }
+}
+
+// custom.xml holds `<property name="X"><vt:lpwstr>value</vt:lpwstr></property>`
+fn collect_custom_properties(xml: &str, out: &mut BTreeMap<String, String>) {
+    for chunk in xml.split("<property ").skip(1) {
+        let name = chunk
+            .split("name=\"")
+            .nth(1)
+            .and_then(|s| s.split('"').next())
+            .unwrap_or_default();
+        let mut values = BTreeMap::new();
+        collect_leaf_elements(chunk, &mut values);
+        if let Some(value) = values.into_values().next() {
+            if !name.is_empty() {
+                out.insert(format!("custom:{}", unescape_xml(name)), value);
+            }
+        }
+    }
+}
+
+pub(crate) fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiff_with_make_and_gps() -> Vec<u8> {
+        // Little-endian TIFF: IFD0 { Make="Acme", GPS pointer }, GPS IFD { N 10° 30' 0", W 5° 0' 0" }
+        let mut t = Vec::new();
+        t.extend_from_slice(b"II*\0");
+        t.extend_from_slice(&8u32.to_le_bytes());
+        // IFD0 at 8: 2 entries
+        t.extend_from_slice(&2u16.to_le_bytes());
+        t.extend_from_slice(&0x010Fu16.to_le_bytes());
+        t.extend_from_slice(&2u16.to_le_bytes());
+        t.extend_from_slice(&4u32.to_le_bytes());
+        t.extend_from_slice(b"Acm\0");
+        t.extend_from_slice(&0x8825u16.to_le_bytes());
+        t.extend_from_slice(&4u16.to_le_bytes());
+        t.extend_from_slice(&1u32.to_le_bytes());
+        t.extend_from_slice(&38u32.to_le_bytes());
+        t.extend_from_slice(&0u32.to_le_bytes());
+        // GPS IFD at 38: 4 entries, rationals start at 38 + 2 + 48 + 4 = 92
+        t.extend_from_slice(&4u16.to_le_bytes());
+        for (tag, ty, count, value) in [
+            (1u16, 2u16, 2u32, u32::from_le_bytes(*b"N\0\0\0")),
+            (2, 5, 3, 92),
+            (3, 2, 2, u32::from_le_bytes(*b"W\0\0\0")),
+            (4, 5, 3, 116),
+        ] {
+            t.extend_from_slice(&tag.to_le_bytes());
+            t.extend_from_slice(&ty.to_le_bytes());
+            t.extend_from_slice(&count.to_le_bytes());
+            t.extend_from_slice(&value.to_le_bytes());
+        }
+        t.extend_from_slice(&0u32.to_le_bytes());
+        for (num, den) in [(10u32, 1u32), (30, 1), (0, 1), (5, 1), (0, 1), (0, 1)] {
+            t.extend_from_slice(&num.to_le_bytes());
+            t.extend_from_slice(&den.to_le_bytes());
+        }
+        t
+    }
+
+    #[test]
+    fn test_jpeg_exif_and_gps() {
+        let tiff = tiff_with_make_and_gps();
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        let len = (2 + 6 + tiff.len()) as u16;
+        jpeg.extend_from_slice(&len.to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xFE, 0x00, 0x06]);
+        jpeg.extend_from_slice(b"hey!");
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+
+        let report = inspect_metadata(&jpeg);
+        assert_eq!(report.format, "jpeg");
+        assert_eq!(report.exif.get("Make").map(String::as_str), Some("Acm"));
+        assert_eq!(report.comments, vec!["hey!".to_string()]);
+        let gps = report.gps.expect("gps parsed");
+        assert_eq!(gps.latitude, Some(10.5));
+        assert_eq!(gps.longitude, Some(-5.0));
+    }
+
+    #[test]
+    fn test_png_text_chunk() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        let body = b"Author\0Jane Relator";
+        png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        png.extend_from_slice(b"tEXt");
+        png.extend_from_slice(body);
+        png.extend_from_slice(&[0, 0, 0, 0]);
+
+        let report = inspect_metadata(&png);
+        assert_eq!(report.format, "png");
+        assert_eq!(
+            report.text_chunks,
+            vec![TextChunk {
+                chunk_type: "tEXt".to_string(),
+                keyword: "Author".to_string(),
+                text: "Jane Relator".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_pdf_info_dictionary() {
+        let pdf = b"%PDF-1.4\n\
+1 0 obj << /Title (Q3 \\(draft\\)) /Author <FEFF004A006F> /CreationDate (D:20190101) >> endobj\n\
+11 0 obj << /Title (Chapter 1) /Parent 10 0 R >> endobj\n\
+trailer << /Size 12 /Info 1 0 R /Root 2 0 R >>\n%%EOF\n";
+        let report = inspect_metadata(pdf);
+        assert_eq!(report.format, "pdf");
+        assert_eq!(report.document_properties.get("Title").map(String::as_str), Some("Q3 (draft)"));
+        assert_eq!(report.document_properties.get("Author").map(String::as_str), Some("Jo"));
+        assert_eq!(report.document_properties.get("CreationDate").map(String::as_str), Some("D:20190101"));
+
+        // An incremental update appends a new Info object and trailer
+        let mut updated = pdf.to_vec();
+        updated.extend_from_slice(b"12 0 obj << /Title (Final) >> endobj\ntrailer << /Size 13 /Info 12 0 R /Prev 9 >>\n%%EOF\n");
+        let report = inspect_metadata(&updated);
+        assert_eq!(report.document_properties.get("Title").map(String::as_str), Some("Final"));
+        assert!(!report.document_properties.contains_key("Author"));
+
+        assert!(inspect_metadata(b"%PDF-1.4\n1 0 obj << /Title (Outline only) >> endobj").document_properties.is_empty());
+    }
+
+    #[test]
+    fn test_rationals_capped_for_huge_counts() {
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&1u16.to_le_bytes());
+        // XResolution claiming 4 billion rationals, pointing at one real one
+        tiff.extend_from_slice(&0x011Au16.to_le_bytes());
+        tiff.extend_from_slice(&5u16.to_le_bytes());
+        tiff.extend_from_slice(&u32::MAX.to_le_bytes());
+        tiff.extend_from_slice(&26u32.to_le_bytes());
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(&72u32.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((2 + 6 + tiff.len()) as u16).to_be_bytes());
+        jpeg.extend_from_slice(b"Exif\0\0");
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xD9]);
+
+        let report = inspect_metadata(&jpeg);
+        assert_eq!(report.exif.get("XResolution").map(String::as_str), Some("72"));
+    }
+}
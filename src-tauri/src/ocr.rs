@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use crate::extract;
+
+// Offline OCR for scanned evidence. The Tesseract binding is only compiled in
+// with `--features ocr`; without it every entry point reports "no text".
+//
+// Language data is looked up in the bundled `tessdata/` resource directory
+// (registered at startup) and otherwise in TESSDATA_PREFIX.
+
+static TESSDATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_tessdata_dir(dir: PathBuf) {
+    let _ = TESSDATA_DIR.set(dir);
+}
+
+pub const UNAVAILABLE: &str = "OCR support is not compiled into this build";
+
+pub fn is_available() -> bool {
+    cfg!(feature = "ocr")
+}
+
+// PDFs with less extracted text than this are treated as image-only scans
+const MIN_PDF_TEXT_CHARS: usize = 20;
+
+fn is_image(data: &[u8]) -> bool {
+    data.starts_with(&[0xFF, 0xD8])
+        || data.starts_with(&[0x89, 0x50, 0x4E, 0x47])
+        || data.starts_with(b"II*\0")
+        || data.starts_with(b"MM\0*")
+}
+
+// Whether a file is an image or an image-only PDF, i.e. something OCR would read
+pub fn wants_ocr(data: &[u8]) -> bool {
+    is_image(data) || is_scanned_pdf(data)
+}
+
+fn is_scanned_pdf(data: &[u8]) -> bool {
+    data.starts_with(b"%PDF")
+        && extract::extract_pdf(data).map(|t| t.trim().len()).unwrap_or(0) < MIN_PDF_TEXT_CHARS
+}
+
+// OCR text for images and image-only PDFs; None for anything else
+pub fn ocr_evidence(data: &[u8]) -> Result<Option<String>, String> {
+    if !is_available() {
+        return Ok(None);
+    }
+
+    let pages: Vec<Vec<u8>> = if is_image(data) {
+        vec![data.to_vec()]
+    } else if is_scanned_pdf(data) {
+        extract::pdf_jpeg_images(data)
+    } else {
+        return Ok(None);
+    };
+
+    let mut out = Vec::new();
+    for page in pages {
+        if let Some(text) = ocr_image(&page)? {
+            out.push(text);
+        }
+    }
+
+    let joined = out.join("\n\n");
+    if joined.trim().is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(joined.trim().to_string()))
+    }
+}
+
+#[cfg(feature = "ocr")]
+fn ocr_image(data: &[u8]) -> Result<Option<String>, String> {
+    let datapath = TESSDATA_DIR.get().and_then(|p| p.to_str());
+    let mut engine = leptess::LepTess::new(datapath, "eng")
+        .map_err(|e| format!("OCR engine failed to start: {}", e))?;
+    if engine.set_image_from_mem(data).is_err() {
+        // Undecodable image data is not an OCR failure worth aborting ingest for
+        return Ok(None);
+    }
+    engine.set_source_resolution(300);
+    let text = engine.get_utf8_text().map_err(|e| format!("OCR failed: {}", e))?;
+    Ok(Some(text))
+}
+
+#[cfg(not(feature = "ocr"))]
+fn ocr_image(_data: &[u8]) -> Result<Option<String>, String> {
+    Ok(None)
+}
@@ -120,6 +120,7 @@ impl World for MinimalWorld {
 pub fn compile_report(
     target_name: &str, 
     total_value: f64,
+    awards_markup: &str,
     timeline_markup: &str,
     evidence_markup: &str,
     complaint_markup: &str,
@@ -160,20 +161,31 @@ Relator submits this disclosure statement pursuant to 31 U.S.C. 3730(b)(2). The
 - **Target Entity Name:** {target}
 - **Estimated Contract Exposure Value:** \${value}
 
+The exposure value is the total obligation of the federal awards identified for this matter:
+
+#table(
+  columns: (1.6fr, 0.8fr, 1.6fr, 1fr),
+  inset: 6pt,
+  fill: (_, y) => if y == 0 {{ gray.lighten(80%) }} else {{ none }},
+  align: horizon,
+  [*Award ID*], [*Date Signed*], [*Awarding Agency*], [*Obligation*],
+  {awards}
+)
+
 = II. Fraud Timeline & Chronology
 The following events trace the billing anomalies, false certifications, and internal directives supporting the fraud allegations:
 
 {timeline}
 
 = III. Evidence Locker Log
-The evidence below has been securely archived, audited, and encrypted locally within the relator's vault:
+The evidence below has been securely archived, audited, and encrypted locally within the relator's vault. Items are grouped by category and cited by exhibit number. Where metadata was scrubbed from a file, the unmodified original may also be preserved; each row identifies which version its hash belongs to:
 
 #table(
-  columns: (1.2fr, 1.8fr, 2.5fr),
+  columns: (0.6fr, 1.2fr, 1.6fr, 1fr, 2.5fr),
   inset: 6pt,
   fill: (_, y) => if y == 0 {{ gray.lighten(80%) }} else {{ none }},
   align: horizon,
-  [*File Name*], [*Description of Proof*], [*SHA-256 Hash Checksum*],
+  [*Exhibit*], [*File Name*], [*Description of Proof*], [*Version*], [*SHA-256 Hash Checksum*],
   {evidence}
 )
 
@@ -189,9 +201,10 @@ The preliminary factual counts and allegations compiled for the Qui Tam filing a
 "#,
         target = target_name,
         date = chrono::Local::now().format("%Y-%m-%d"),
-        value = total_value,
+        value = format!("{:.2}", total_value),
+        awards = if awards_markup.is_empty() { "  [N/A], [N/A], [No awards attached], [N/A],\n" } else { awards_markup },
         timeline = if timeline_markup.is_empty() { "_No events logged in timeline._" } else { timeline_markup },
-        evidence = if evidence_markup.is_empty() { "  [No evidence files], [No evidence logged], [N/A]\n" } else { evidence_markup },
+        evidence = if evidence_markup.is_empty() { "  [N/A], [No evidence files], [No evidence logged], [N/A], [N/A]\n" } else { evidence_markup },
         complaint = if complaint_markup.is_empty() { "_No complaint narrative compiled._" } else { complaint_markup }
     );
 
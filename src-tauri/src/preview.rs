@@ -0,0 +1,197 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use image::ImageFormat;
+use crate::extract;
+
+// Small previews for the Case Vault list. Images are thumbnailed to PNG.
+// PDFs have their first page rendered to PNG through pdfium, which is only
+// compiled in with `--features pdf-render`; without it, or when pdfium can't
+// be loaded or can't open the file, they use their first embedded page scan,
+// otherwise the opening lines of their text. Other text-bearing files show
+// their first lines.
+//
+// The pdfium library is looked up in the bundled `pdfium/` resource directory
+// (registered at startup) and otherwise on the system library path.
+//
+// Previews are generated from the scrubbed bytes and stored encrypted as
+// `previews/<evidence id>.enc`. Each item has its own file because its nonce
+// is stored on its own row, even when two items share a hash.
+
+const THUMBNAIL_SIZE: u32 = 256;
+const TEXT_PREVIEW_LINES: usize = 20;
+const TEXT_PREVIEW_CHARS: usize = 2000;
+
+static PDFIUM_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_pdfium_dir(dir: PathBuf) {
+    let _ = PDFIUM_DIR.set(dir);
+}
+
+pub struct PreviewData {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+pub fn preview_path(hunt_dir: &Path, evidence_id: i64) -> PathBuf {
+    hunt_dir.join("previews").join(format!("{}.enc", evidence_id))
+}
+
+// Previews used to be named after the evidence hash; such files are never
+// read again and only matter when shredding
+pub fn legacy_preview_path(hunt_dir: &Path, sha256_hash: &str) -> PathBuf {
+    hunt_dir.join("previews").join(format!("{}.enc", sha256_hash))
+}
+
+pub fn generate(filename: &str, data: &[u8]) -> Option<PreviewData> {
+    if let Some(thumb) = thumbnail(data) {
+        return Some(thumb);
+    }
+
+    if data.starts_with(b"%PDF") {
+        if let Some(page) = render_first_page(data) {
+            return Some(page);
+        }
+        if let Some(thumb) = extract::pdf_jpeg_images(data).iter().find_map(|img| thumbnail(img)) {
+            return Some(thumb);
+        }
+    }
+
+    extract::extract_text(filename, data).and_then(|text| text_preview(&text))
+}
+
+fn thumbnail(data: &[u8]) -> Option<PreviewData> {
+    let format = image::guess_format(data).ok()?;
+    if !matches!(format, ImageFormat::Jpeg | ImageFormat::Png) {
+        return None;
+    }
+    let img = image::load_from_memory_with_format(data, format).ok()?;
+    let thumb = img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+
+    let mut out = Vec::new();
+    thumb.write_to(&mut Cursor::new(&mut out), ImageFormat::Png).ok()?;
+    Some(PreviewData { mime_type: "image/png".to_string(), data: out })
+}
+
+#[cfg(feature = "pdf-render")]
+fn render_first_page(data: &[u8]) -> Option<PreviewData> {
+    use pdfium_render::prelude::*;
+    use std::sync::Mutex;
+
+    // pdfium is not re-entrant, and ingest prepares files on several threads
+    static RENDER: Mutex<()> = Mutex::new(());
+    let _guard = RENDER.lock().ok()?;
+
+    let bindings = PDFIUM_DIR
+        .get()
+        .map(|dir| Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(dir)))
+        .filter(|b| b.is_ok())
+        .unwrap_or_else(Pdfium::bind_to_system_library);
+    let bindings = match bindings {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("WARNING: pdfium could not be loaded; PDF previews fall back to scans or text: {}", e);
+            return None;
+        }
+    };
+    let pdfium = Pdfium::new(bindings);
+    let document = pdfium.load_pdf_from_byte_slice(data, None).ok()?;
+    let page = document.pages().first().ok()?;
+    let config = PdfRenderConfig::new()
+        .set_target_width(THUMBNAIL_SIZE as Pixels)
+        .set_maximum_height(THUMBNAIL_SIZE as Pixels);
+    let bitmap = page.render_with_config(&config).ok()?;
+
+    let rgba = image::RgbaImage::from_raw(bitmap.width() as u32, bitmap.height() as u32, bitmap.as_rgba_bytes())?;
+    let mut out = Vec::new();
+    image::DynamicImage::ImageRgba8(rgba).write_to(&mut Cursor::new(&mut out), ImageFormat::Png).ok()?;
+    Some(PreviewData { mime_type: "image/png".to_string(), data: out })
+}
+
+#[cfg(not(feature = "pdf-render"))]
+fn render_first_page(_data: &[u8]) -> Option<PreviewData> {
+    None
+}
+
+fn text_preview(text: &str) -> Option<PreviewData> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|l| l.trim_end())
+        .filter(|l| !l.is_empty())
+        .take(TEXT_PREVIEW_LINES)
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let joined = lines.join("\n");
+    let preview: String = joined.chars().take(TEXT_PREVIEW_CHARS).collect();
+    Some(PreviewData { mime_type: "text/plain".to_string(), data: preview.into_bytes() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+
+    #[test]
+    fn test_image_thumbnail_and_text_preview() {
+        let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_pixel(1024, 512, Rgb([200, 10, 10]));
+        let mut png = Vec::new();
+        img.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+
+        let thumb = generate("scan.png", &png).unwrap();
+        assert_eq!(thumb.mime_type, "image/png");
+        let decoded = image::load_from_memory(&thumb.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (256, 128));
+
+        let text: String = (1..=50).map(|i| format!("line {}\n", i)).collect();
+        let preview = generate("notes.txt", text.as_bytes()).unwrap();
+        assert_eq!(preview.mime_type, "text/plain");
+        let body = String::from_utf8(preview.data).unwrap();
+        assert!(body.starts_with("line 1\n"));
+        assert!(body.ends_with("line 20"));
+    }
+
+    // One page, 200x100pt, with a line of text
+    fn one_page_pdf() -> Vec<u8> {
+        let content = "BT /F1 12 Tf 20 50 Td (Invoice INV-0042) Tj ET";
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 200 100] /Contents 4 0 R \
+             /Resources << /Font << /F1 << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> >> >> >>".to_string(),
+            format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content),
+        ];
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, body).as_bytes());
+        }
+        let xref = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes());
+        pdf
+    }
+
+    #[cfg(feature = "pdf-render")]
+    #[test]
+    fn test_pdf_first_page_rendered() {
+        let preview = generate("invoice.pdf", &one_page_pdf()).unwrap();
+        assert_eq!(preview.mime_type, "image/png");
+        let decoded = image::load_from_memory(&preview.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (256, 128));
+    }
+
+    #[cfg(not(feature = "pdf-render"))]
+    #[test]
+    fn test_pdf_without_renderer_falls_back_to_text() {
+        let preview = generate("invoice.pdf", &one_page_pdf()).unwrap();
+        assert_eq!(preview.mime_type, "text/plain");
+        assert_eq!(preview.data, b"Invoice INV-0042");
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use rusqlite::params;
+use serde_json::json;
+use crate::bulk::read_record;
+use crate::db::HuntDatabase;
+use crate::screening::{self, address_key, iso_date, name_key, Finding, ReferenceDataset, ReferenceDb};
+
+// SAM.gov checks. Two public extracts are imported:
+// - the exclusions extract, a CSV with a header row (also inside its zip);
+// - the entity registration extract, a pipe-delimited `.dat` file with BOF/EOF
+//   marker lines and fixed field positions (also inside its zip).
+// A target is matched by UEI, by name, and by the address its SAM registration
+// gives. Exclusions whose active period covers award action dates are flagged.
+
+pub const EXCLUSIONS: &str = "sam_exclusions";
+pub const ENTITIES: &str = "sam_entities";
+
+// Field positions in the public entity extract (V2)
+const ENTITY_UEI: usize = 0;
+const ENTITY_CAGE: usize = 3;
+const ENTITY_REGISTRATION_DATE: usize = 7;
+const ENTITY_EXPIRATION_DATE: usize = 8;
+const ENTITY_ACTIVATION_DATE: usize = 10;
+const ENTITY_LEGAL_NAME: usize = 11;
+const ENTITY_DBA_NAME: usize = 12;
+const ENTITY_ADDRESS: usize = 15;
+const ENTITY_CITY: usize = 17;
+const ENTITY_STATE: usize = 18;
+const ENTITY_ZIP: usize = 19;
+const ENTITY_COUNTRY: usize = 21;
+
+// Replaces the stored exclusions with the extract's
+pub fn import_exclusions(reference: &ReferenceDb, path: &Path) -> Result<ReferenceDataset, String> {
+    screening::read_extract(path, |n| n.ends_with(".csv"), |reader, file_name| {
+        let mut line = String::new();
+        let header = read_record(reader, &mut line).map_err(|e| e.to_string())?.ok_or("The extract is empty")?;
+        let column = |names: &[&str]| {
+            header.iter().position(|h| {
+                let h = h.trim().trim_start_matches('\u{feff}');
+                names.iter().any(|n| h.eq_ignore_ascii_case(n))
+            })
+        };
+        let name_col = column(&["Name"]).ok_or("Not a SAM exclusions extract: no Name column")?;
+        let cols = [
+            column(&["Classification"]),
+            column(&["First"]),
+            column(&["Middle"]),
+            column(&["Last"]),
+            column(&["Unique Entity ID", "UEI"]),
+            column(&["CAGE"]),
+            column(&["NPI"]),
+            column(&["Address 1"]),
+            column(&["City"]),
+            column(&["State / Province", "State"]),
+            column(&["Zip Code", "Zip"]),
+            column(&["Exclusion Type"]),
+            column(&["Exclusion Program"]),
+            column(&["Excluding Agency"]),
+            column(&["Active Date"]),
+            column(&["Termination Date"]),
+            column(&["SAM Number"]),
+        ];
+
+        let tx = reference.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM sam_exclusions", []).map_err(|e| e.to_string())?;
+        let mut rows = 0;
+        // Row 1 is the header
+        let mut row_number = 1;
+        while let Some(values) = read_record(reader, &mut line).map_err(|e| e.to_string())? {
+            row_number += 1;
+            let get = |col: Option<usize>| col.and_then(|i| values.get(i)).map(|v| v.trim()).unwrap_or_default();
+            let [classification, first, middle, last, uei, cage, npi, address, city, state, zip, exclusion_type, program, agency, active, termination, sam_number] =
+                cols.map(get);
+
+            // Individuals have an empty Name and their parts in First/Middle/Last
+            let full_name = [first, middle, last].iter().filter(|p| !p.is_empty()).copied().collect::<Vec<_>>().join(" ");
+            let name = if get(Some(name_col)).is_empty() { full_name.as_str() } else { get(Some(name_col)) };
+            let key = if get(Some(name_col)).is_empty() { name_key(&format!("{} {}", first, last)) } else { name_key(name) };
+            if key.is_empty() {
+                continue;
+            }
+            let record_key = if sam_number.is_empty() { format!("row {}", row_number) } else { sam_number.to_string() };
+            tx.execute(
+                "INSERT INTO sam_exclusions (record_key, source_row, classification, name, name_key, uei, cage, npi, address, city, state, zip, \
+                 address_key, exclusion_type, exclusion_program, excluding_agency, active_date, termination_date) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+                params![
+                    record_key,
+                    row_number,
+                    classification,
+                    name,
+                    key,
+                    (!uei.is_empty()).then(|| uei.to_uppercase()),
+                    cage,
+                    npi,
+                    address,
+                    city,
+                    state,
+                    zip,
+                    address_key(address, zip),
+                    exclusion_type,
+                    program,
+                    agency,
+                    iso_date(active),
+                    iso_date(termination)
+                ],
+            ).map_err(|e| e.to_string())?;
+            rows += 1;
+        }
+        screening::finish_import(reference, tx, EXCLUSIONS, file_name, rows)
+    })
+}
+
+// Replaces the stored registrations with the extract's
+pub fn import_entities(reference: &ReferenceDb, path: &Path) -> Result<ReferenceDataset, String> {
+    screening::read_extract(path, |n| n.ends_with(".dat"), |reader, file_name| {
+        let tx = reference.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM sam_entities", []).map_err(|e| e.to_string())?;
+        let mut rows = 0;
+        let mut line = String::new();
+        let mut row_number = 0;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+                break;
+            }
+            row_number += 1;
+            let record = line.trim_end_matches(['\r', '\n']);
+            if record.starts_with("BOF ") || record.starts_with("EOF ") || record.is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = record.trim_end_matches("!end").split('|').map(str::trim).collect();
+            if fields.len() <= ENTITY_COUNTRY {
+                return Err(format!("{}:{}: not a SAM entity extract record", file_name, row_number));
+            }
+            let legal_name = fields[ENTITY_LEGAL_NAME];
+            let dba_name = fields[ENTITY_DBA_NAME];
+            tx.execute(
+                "INSERT OR REPLACE INTO sam_entities (uei, source_row, cage, legal_name, dba_name, name_key, dba_key, address, city, state, zip, \
+                 country, address_key, registration_date, expiration_date, activation_date) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+                params![
+                    fields[ENTITY_UEI].to_uppercase(),
+                    row_number,
+                    fields[ENTITY_CAGE],
+                    legal_name,
+                    dba_name,
+                    name_key(legal_name),
+                    Some(name_key(dba_name)).filter(|k| !k.is_empty()),
+                    fields[ENTITY_ADDRESS],
+                    fields[ENTITY_CITY],
+                    fields[ENTITY_STATE],
+                    fields[ENTITY_ZIP],
+                    fields[ENTITY_COUNTRY],
+                    address_key(fields[ENTITY_ADDRESS], fields[ENTITY_ZIP]),
+                    iso_date(fields[ENTITY_REGISTRATION_DATE]),
+                    iso_date(fields[ENTITY_EXPIRATION_DATE]),
+                    iso_date(fields[ENTITY_ACTIVATION_DATE])
+                ],
+            ).map_err(|e| e.to_string())?;
+            rows += 1;
+        }
+        screening::finish_import(reference, tx, ENTITIES, file_name, rows)
+    })
+}
+
+struct Registration {
+    uei: String,
+    source_row: i64,
+    legal_name: String,
+    address_key: Option<String>,
+    registration_date: Option<String>,
+    expiration_date: Option<String>,
+    detail: serde_json::Value,
+}
+
+struct Exclusion {
+    record_key: String,
+    source_row: i64,
+    name: String,
+    excluding_agency: String,
+    exclusion_type: String,
+    active_date: Option<String>,
+    termination_date: Option<String>,
+    detail: serde_json::Value,
+}
+
+fn new_finding(check: &str, subject: &str, source: &str, record_key: String, source_ref: String) -> Finding {
+    Finding {
+        id: 0,
+        check: check.to_string(),
+        severity: "info".to_string(),
+        subject_type: "target".to_string(),
+        subject_id: None,
+        subject_name: subject.to_string(),
+        source: source.to_string(),
+        record_key,
+        source_ref,
+        matched_on: Vec::new(),
+        summary: String::new(),
+        detail: serde_json::Value::Null,
+        status: "open".to_string(),
+        found_at: crate::db::now_timestamp(),
+    }
+}
+
+fn source_ref(dataset: &Option<ReferenceDataset>, row: i64) -> String {
+    format!("{}:{}", dataset.as_ref().map(|d| d.file_name.as_str()).unwrap_or("?"), row)
+}
+
+// Screens the hunt's target against the imported SAM extracts, records the
+// hits as findings and returns them
+pub fn screen_target(reference: &ReferenceDb, db: &HuntDatabase) -> Result<Vec<Finding>, String> {
+    let err = |e: rusqlite::Error| e.to_string();
+    let exclusions_set = reference.dataset(EXCLUSIONS).map_err(err)?;
+    let entities_set = reference.dataset(ENTITIES).map_err(err)?;
+    if exclusions_set.is_none() && entities_set.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let record = db.case_record().map_err(err)?;
+    let profile = db.target_profile().map_err(err)?;
+    let subject = record
+        .as_ref()
+        .and_then(|r| r.target_entity.clone())
+        .or_else(|| profile.as_ref().map(|p| p.name.clone()))
+        .unwrap_or_default();
+    let uei = profile.as_ref().and_then(|p| p.uei.clone()).map(|u| u.to_uppercase());
+    let mut names: Vec<String> = std::iter::once(subject.as_str())
+        .chain(profile.iter().flat_map(|p| std::iter::once(p.name.as_str()).chain(p.alternate_names.iter().map(String::as_str))))
+        .map(name_key)
+        .filter(|k| !k.is_empty())
+        .collect();
+    names.sort();
+    names.dedup();
+    // Parties may still be screened against the other lists
+    if names.is_empty() && uei.is_none() {
+        return Ok(Vec::new());
+    }
+    let dates = screening::award_dates(db).map_err(err)?;
+    let mut found = Vec::new();
+
+    // Registrations: by UEI, or by legal or DBA name when the UEI is unknown
+    let mut registrations: BTreeMap<String, (Registration, Vec<&str>)> = BTreeMap::new();
+    let criteria: Vec<(&str, &str, String)> = match &uei {
+        Some(uei) => vec![("uei", "uei", uei.clone())],
+        None => names
+            .iter()
+            .flat_map(|n| [("name", "name_key", n.clone()), ("name", "dba_key", n.clone())])
+            .collect(),
+    };
+    for (basis, column, value) in &criteria {
+        let mut stmt = reference.conn.prepare(&format!(
+            "SELECT uei, source_row, legal_name, dba_name, cage, address, city, state, zip, address_key, \
+             registration_date, expiration_date, activation_date FROM sam_entities WHERE {} = ?1",
+            column
+        )).map_err(err)?;
+        let rows = stmt.query_map([value], |r| {
+            Ok(Registration {
+                uei: r.get(0)?,
+                source_row: r.get(1)?,
+                legal_name: r.get(2)?,
+                address_key: r.get(9)?,
+                registration_date: r.get(10)?,
+                expiration_date: r.get(11)?,
+                detail: json!({
+                    "uei": r.get::<_, String>(0)?,
+                    "legal_name": r.get::<_, String>(2)?,
+                    "dba_name": r.get::<_, Option<String>>(3)?,
+                    "cage": r.get::<_, Option<String>>(4)?,
+                    "address": r.get::<_, Option<String>>(5)?,
+                    "city": r.get::<_, Option<String>>(6)?,
+                    "state": r.get::<_, Option<String>>(7)?,
+                    "zip": r.get::<_, Option<String>>(8)?,
+                    "registration_date": r.get::<_, Option<String>>(10)?,
+                    "expiration_date": r.get::<_, Option<String>>(11)?,
+                    "activation_date": r.get::<_, Option<String>>(12)?,
+                }),
+            })
+        }).map_err(err)?;
+        for registration in rows {
+            let registration = registration.map_err(err)?;
+            let entry = registrations.entry(registration.uei.clone()).or_insert_with(|| (registration, Vec::new()));
+            if !entry.1.contains(basis) {
+                entry.1.push(basis);
+            }
+        }
+    }
+
+    for (registration, matched_on) in registrations.values() {
+        let mut finding = new_finding(
+            "sam_registration",
+            &subject,
+            ENTITIES,
+            registration.uei.clone(),
+            source_ref(&entities_set, registration.source_row),
+        );
+        finding.matched_on = matched_on.iter().map(|m| m.to_string()).collect();
+        let after_expiry: Vec<_> = match &registration.expiration_date {
+            Some(expired) => dates.iter().filter(|(_, d)| d.as_str() > expired.as_str()).collect(),
+            None => Vec::new(),
+        };
+        if after_expiry.is_empty() {
+            finding.summary = format!(
+                "Registered in SAM.gov as {} (UEI {}), {} to {}",
+                registration.legal_name,
+                registration.uei,
+                registration.registration_date.as_deref().unwrap_or("?"),
+                registration.expiration_date.as_deref().unwrap_or("?")
+            );
+        } else {
+            finding.severity = "medium".to_string();
+            finding.summary = format!(
+                "{} award action(s) dated after the SAM.gov registration of {} expired on {}",
+                after_expiry.len(),
+                registration.legal_name,
+                registration.expiration_date.as_deref().unwrap_or("?")
+            );
+        }
+        let mut detail = registration.detail.clone();
+        detail["awards_after_expiration"] = json!(after_expiry.iter().map(|(id, d)| json!({ "award_id": id, "date": d })).collect::<Vec<_>>());
+        finding.detail = detail;
+        found.push(finding);
+    }
+
+    // Exclusions: by UEI, name, or the address of a matched registration
+    let addresses: Vec<String> = registrations.values().filter_map(|(r, _)| r.address_key.clone()).collect();
+    let mut criteria: Vec<(&str, &str, String)> = names.iter().map(|n| ("name", "name_key", n.clone())).collect();
+    criteria.extend(uei.iter().map(|u| ("uei", "uei", u.clone())));
+    criteria.extend(addresses.into_iter().map(|a| ("address", "address_key", a)));
+
+    let mut exclusions: BTreeMap<String, (Exclusion, Vec<&str>)> = BTreeMap::new();
+    for (basis, column, value) in &criteria {
+        let mut stmt = reference.conn.prepare(&format!(
+            "SELECT record_key, source_row, name, excluding_agency, exclusion_type, active_date, termination_date, \
+             classification, uei, cage, npi, address, city, state, zip, exclusion_program FROM sam_exclusions WHERE {} = ?1",
+            column
+        )).map_err(err)?;
+        let rows = stmt.query_map([value], |r| {
+            Ok(Exclusion {
+                record_key: r.get(0)?,
+                source_row: r.get(1)?,
+                name: r.get(2)?,
+                excluding_agency: r.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                exclusion_type: r.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                active_date: r.get(5)?,
+                termination_date: r.get(6)?,
+                detail: json!({
+                    "name": r.get::<_, String>(2)?,
+                    "classification": r.get::<_, Option<String>>(7)?,
+                    "uei": r.get::<_, Option<String>>(8)?,
+                    "cage": r.get::<_, Option<String>>(9)?,
+                    "npi": r.get::<_, Option<String>>(10)?,
+                    "address": r.get::<_, Option<String>>(11)?,
+                    "city": r.get::<_, Option<String>>(12)?,
+                    "state": r.get::<_, Option<String>>(13)?,
+                    "zip": r.get::<_, Option<String>>(14)?,
+                    "exclusion_type": r.get::<_, Option<String>>(4)?,
+                    "exclusion_program": r.get::<_, Option<String>>(15)?,
+                    "excluding_agency": r.get::<_, Option<String>>(3)?,
+                    "active_date": r.get::<_, Option<String>>(5)?,
+                    "termination_date": r.get::<_, Option<String>>(6)?,
+                }),
+            })
+        }).map_err(err)?;
+        for exclusion in rows {
+            let exclusion = exclusion.map_err(err)?;
+            let entry = exclusions.entry(exclusion.record_key.clone()).or_insert_with(|| (exclusion, Vec::new()));
+            if !entry.1.contains(basis) {
+                entry.1.push(basis);
+            }
+        }
+    }
+
+    for (exclusion, matched_on) in exclusions.values() {
+        let mut finding = new_finding(
+            "sam_exclusion",
+            &subject,
+            EXCLUSIONS,
+            exclusion.record_key.clone(),
+            source_ref(&exclusions_set, exclusion.source_row),
+        );
+        finding.matched_on = matched_on.iter().map(|m| m.to_string()).collect();
+        let overlapping = screening::dates_within(&dates, exclusion.active_date.as_deref(), exclusion.termination_date.as_deref());
+        // An address alone is weak evidence: many entities share an office
+        let strong = matched_on.iter().any(|m| *m != "address");
+        finding.severity = match (strong, overlapping.is_empty()) {
+            (true, false) => "high",
+            (true, true) => "medium",
+            (false, _) => "info",
+        }
+        .to_string();
+        finding.summary = format!(
+            "{} excluded by {} ({}) from {} {}; {} award action(s) in that period",
+            exclusion.name,
+            exclusion.excluding_agency,
+            exclusion.exclusion_type,
+            exclusion.active_date.as_deref().unwrap_or("?"),
+            exclusion.termination_date.as_deref().map_or("indefinitely".to_string(), |d| format!("to {}", d)),
+            overlapping.len()
+        );
+        let mut detail = exclusion.detail.clone();
+        detail["overlapping_awards"] = json!(overlapping.iter().map(|(id, d)| json!({ "award_id": id, "date": d })).collect::<Vec<_>>());
+        finding.detail = detail;
+        found.push(finding);
+    }
+
+    let tx = db.conn.unchecked_transaction().map_err(err)?;
+    let mut ids = Vec::new();
+    for finding in &found {
+        ids.push(db.record_finding(finding).map_err(err)?);
+    }
+    tx.commit().map_err(err)?;
+    Ok(db.findings().map_err(err)?.into_iter().filter(|f| ids.contains(&f.id)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usaspending::AwardSummary;
+
+    #[test]
+    fn test_screen_target_flags_exclusion_overlapping_awards() {
+        let base = std::env::temp_dir().join(format!("os-sam-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&base).unwrap();
+        let exclusions = base.join("SAM_Exclusions_Public_Extract_V2.CSV");
+        std::fs::write(&exclusions, "\u{feff}\"Classification\",\"Name\",\"First\",\"Middle\",\"Last\",\"Address 1\",\"City\",\"State / Province\",\"Zip Code\",\"Unique Entity ID\",\"Excluding Agency\",\"Exclusion Type\",\"Active Date\",\"Termination Date\",\"SAM Number\"\n\
+            \"Firm\",\"ACME CORPORATION\",\"\",\"\",\"\",\"1 Main Street\",\"St Paul\",\"MN\",\"55101\",\"\",\"HHS\",\"Prohibition/Restriction\",\"03/01/2021\",\"Indefinite\",\"S1\"\n\
+            \"Firm\",\"Shell Holdings, Inc.\",\"\",\"\",\"\",\"1 MAIN ST\",\"St Paul\",\"MN\",\"55101-1234\",\"\",\"GSA\",\"Ineligible\",\"01/01/2019\",\"12/31/2019\",\"S2\"\n\
+            \"Individual\",\"\",\"John\",\"Q\",\"Smith\",\"\",\"\",\"\",\"\",\"\",\"OPM\",\"Ineligible\",\"01/01/2020\",\"\",\"S3\"\n").unwrap();
+        let entities = base.join("SAM_PUBLIC_MONTHLY_V2.dat");
+        let mut fields = vec![""; 30];
+        fields[ENTITY_UEI] = "abc123def456";
+        fields[ENTITY_EXPIRATION_DATE] = "20210601";
+        fields[ENTITY_REGISTRATION_DATE] = "20150101";
+        fields[ENTITY_LEGAL_NAME] = "ACME CORP";
+        fields[ENTITY_ADDRESS] = "1 Main St";
+        fields[ENTITY_ZIP] = "55101";
+        std::fs::write(&entities, format!("BOF PUBLIC V2 20240101 20240101 0000001 0000001\n{}!end\nEOF PUBLIC V2 20240101 20240101 0000001 0000001\n", fields.join("|"))).unwrap();
+
+        let reference = ReferenceDb::open(":memory:").unwrap();
+        assert_eq!(import_exclusions(&reference, &exclusions).unwrap().rows, 3);
+        assert_eq!(import_entities(&reference, &entities).unwrap().rows, 1);
+
+        let db = HuntDatabase::open(":memory:").unwrap();
+        db.init_case("Test").unwrap();
+        db.set_case_details(Some("Acme Corp, LLC"), None, None).unwrap();
+        db.attach_award(&AwardSummary {
+            generated_internal_id: "CONT_AWD_1".into(),
+            date_signed: "2021-05-01".into(),
+            description: None,
+            total_obligation: 10.0,
+            awarding_agency: "HHS".into(),
+            recipient_name: "ACME CORP".into(),
+            raw: None,
+            sources: Vec::new(),
+        }).unwrap();
+        db.attach_award(&AwardSummary { generated_internal_id: "CONT_AWD_2".into(), date_signed: "2021-08-01".into(), ..db.target_awards().unwrap()[0].clone() }).unwrap();
+
+        let findings = screen_target(&reference, &db).unwrap();
+        let kinds: Vec<(&str, &str, &str)> = findings.iter().map(|f| (f.check.as_str(), f.record_key.as_str(), f.severity.as_str())).collect();
+        assert_eq!(kinds, [
+            ("sam_exclusion", "S1", "high"),
+            ("sam_registration", "ABC123DEF456", "medium"),
+            ("sam_exclusion", "S2", "info"),
+        ]);
+        assert_eq!(findings[0].matched_on, ["name", "address"]);
+        assert_eq!(findings[0].detail["overlapping_awards"].as_array().unwrap().len(), 2);
+        assert_eq!(findings[0].source_ref, "SAM_Exclusions_Public_Extract_V2.CSV:2");
+
+        // Re-screening refreshes hits without resetting the user's review
+        assert!(db.set_finding_status(findings[0].id, "dismissed").unwrap());
+        let again = screen_target(&reference, &db).unwrap();
+        assert_eq!(again.len(), 3);
+        assert_eq!(again[0].status, "dismissed");
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
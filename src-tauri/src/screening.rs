@@ -0,0 +1,468 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+// Screening of hunt targets and parties against public reference lists
+// (exclusions, registrations, provider registries). The lists are imported
+// from local extract files into one shared SQLite database,
+// `<app data>/reference/reference.db`. It holds only public data and is not
+// encrypted. Hits are written to the hunt's `findings` table, which keeps the
+// extract file and row each hit came from.
+
+// A hit against a reference list, as stored on a hunt
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Finding {
+    pub id: i64,
+    // The check that raised it, e.g. "sam_exclusion"
+    pub check: String,
+    // "high", "medium" or "info"
+    pub severity: String,
+    // "target", or "party" with the party id in `subject_id`
+    pub subject_type: String,
+    pub subject_id: Option<String>,
+    pub subject_name: String,
+    // Reference dataset and the record within it
+    pub source: String,
+    pub record_key: String,
+    // `<file name>:<row>` of the record in the imported extract
+    pub source_ref: String,
+    // Which identifiers matched: "uei", "name", "address", ...
+    pub matched_on: Vec<String>,
+    pub summary: String,
+    pub detail: serde_json::Value,
+    // "open", "confirmed" or "dismissed"
+    pub status: String,
+    pub found_at: String,
+}
+
+pub const FINDING_STATUSES: &[&str] = &["open", "confirmed", "dismissed"];
+
+// A reference list as last imported
+#[derive(Serialize, Clone, Debug)]
+pub struct ReferenceDataset {
+    pub dataset: String,
+    pub file_name: String,
+    pub rows: usize,
+    pub imported_at: String,
+}
+
+pub fn reference_db_path(data_root: &Path) -> PathBuf {
+    data_root.join("reference").join("reference.db")
+}
+
+pub struct ReferenceDb {
+    pub conn: Connection,
+}
+
+impl ReferenceDb {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        let _ = conn.pragma_update(None, "journal_mode", "WAL");
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS datasets (
+                dataset TEXT PRIMARY KEY,
+                file_name TEXT NOT NULL,
+                rows INTEGER NOT NULL,
+                imported_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // SAM.gov exclusions public extract
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sam_exclusions (
+                record_key TEXT NOT NULL,
+                source_row INTEGER NOT NULL,
+                classification TEXT,
+                name TEXT NOT NULL,
+                name_key TEXT NOT NULL,
+                uei TEXT,
+                cage TEXT,
+                npi TEXT,
+                address TEXT,
+                city TEXT,
+                state TEXT,
+                zip TEXT,
+                address_key TEXT,
+                exclusion_type TEXT,
+                exclusion_program TEXT,
+                excluding_agency TEXT,
+                active_date TEXT,
+                termination_date TEXT
+            )",
+            [],
+        )?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS sam_exclusions_name ON sam_exclusions(name_key);
+             CREATE INDEX IF NOT EXISTS sam_exclusions_uei ON sam_exclusions(uei);
+             CREATE INDEX IF NOT EXISTS sam_exclusions_address ON sam_exclusions(address_key);",
+        )?;
+
+        // SAM.gov entity registration public extract
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sam_entities (
+                uei TEXT PRIMARY KEY,
+                source_row INTEGER NOT NULL,
+                cage TEXT,
+                legal_name TEXT NOT NULL,
+                dba_name TEXT,
+                name_key TEXT NOT NULL,
+                dba_key TEXT,
+                address TEXT,
+                city TEXT,
+                state TEXT,
+                zip TEXT,
+                country TEXT,
+                address_key TEXT,
+                registration_date TEXT,
+                expiration_date TEXT,
+                activation_date TEXT
+            )",
+            [],
+        )?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS sam_entities_name ON sam_entities(name_key);
+             CREATE INDEX IF NOT EXISTS sam_entities_dba ON sam_entities(dba_key);",
+        )?;
+
+        // HHS-OIG List of Excluded Individuals/Entities; name_block is the
+        // Soundex code used to find fuzzy name candidates
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS leie_exclusions (
+                record_key TEXT NOT NULL,
+                source_row INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                name_key TEXT NOT NULL,
+                name_block TEXT NOT NULL,
+                business_name TEXT,
+                general TEXT,
+                specialty TEXT,
+                npi TEXT,
+                dob TEXT,
+                address TEXT,
+                city TEXT,
+                state TEXT,
+                zip TEXT,
+                address_key TEXT,
+                exclusion_type TEXT,
+                exclusion_date TEXT,
+                reinstatement_date TEXT,
+                waiver_date TEXT
+            )",
+            [],
+        )?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS leie_block ON leie_exclusions(name_block);
+             CREATE INDEX IF NOT EXISTS leie_npi ON leie_exclusions(npi);
+             CREATE INDEX IF NOT EXISTS leie_address ON leie_exclusions(address_key);",
+        )?;
+
+        // CMS NPPES NPI registry
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS nppes_providers (
+                npi TEXT PRIMARY KEY,
+                source_row INTEGER NOT NULL,
+                entity_type TEXT,
+                name TEXT NOT NULL,
+                name_key TEXT NOT NULL,
+                name_block TEXT NOT NULL,
+                address TEXT,
+                city TEXT,
+                state TEXT,
+                zip TEXT,
+                address_key TEXT,
+                taxonomy TEXT,
+                enumeration_date TEXT,
+                deactivation_date TEXT
+            )",
+            [],
+        )?;
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS nppes_block ON nppes_providers(name_block);
+             CREATE INDEX IF NOT EXISTS nppes_address ON nppes_providers(address_key);",
+        )?;
+
+        Ok(Self { conn })
+    }
+
+    pub fn open_at(data_root: &Path) -> std::result::Result<Self, String> {
+        let path = reference_db_path(data_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        Self::open(path).map_err(|e| e.to_string())
+    }
+
+    pub fn record_import(&self, dataset: &str, file_name: &str, rows: usize) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO datasets (dataset, file_name, rows, imported_at) VALUES (?1, ?2, ?3, ?4)",
+            params![dataset, file_name, rows as i64, crate::db::now_timestamp()],
+        )?;
+        Ok(())
+    }
+
+    pub fn dataset(&self, dataset: &str) -> Result<Option<ReferenceDataset>> {
+        self.conn
+            .query_row(
+                "SELECT dataset, file_name, rows, imported_at FROM datasets WHERE dataset = ?1",
+                [dataset],
+                |r| {
+                    Ok(ReferenceDataset {
+                        dataset: r.get(0)?,
+                        file_name: r.get(1)?,
+                        rows: r.get::<_, i64>(2)? as usize,
+                        imported_at: r.get(3)?,
+                    })
+                },
+            )
+            .optional()
+    }
+
+    pub fn datasets(&self) -> Result<Vec<ReferenceDataset>> {
+        let mut stmt = self.conn.prepare("SELECT dataset, file_name, rows, imported_at FROM datasets ORDER BY dataset")?;
+        let rows = stmt.query_map([], |r| {
+            Ok(ReferenceDataset {
+                dataset: r.get(0)?,
+                file_name: r.get(1)?,
+                rows: r.get::<_, i64>(2)? as usize,
+                imported_at: r.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+// Legal-form words dropped from names before comparing, so "Acme Corp" and
+// "ACME CORPORATION" match
+const NAME_NOISE: &[&str] = &[
+    "THE", "INC", "INCORPORATED", "LLC", "L", "C", "LLP", "LP", "LTD", "LIMITED", "CO", "CORP", "CORPORATION",
+    "COMPANY", "PC", "PLLC", "PA",
+];
+
+pub fn name_key(name: &str) -> String {
+    crate::bulk::normalize_name(name)
+        .split(' ')
+        .filter(|w| !w.is_empty() && !NAME_NOISE.contains(w))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const ADDRESS_WORDS: &[(&str, &str)] = &[
+    ("STREET", "ST"), ("AVENUE", "AVE"), ("ROAD", "RD"), ("BOULEVARD", "BLVD"), ("DRIVE", "DR"),
+    ("SUITE", "STE"), ("LANE", "LN"), ("COURT", "CT"), ("HIGHWAY", "HWY"), ("PARKWAY", "PKWY"),
+    ("NORTH", "N"), ("SOUTH", "S"), ("EAST", "E"), ("WEST", "W"),
+];
+
+// First address line in USPS abbreviations plus the 5-digit ZIP; None when
+// either is missing
+pub fn address_key(line1: &str, zip: &str) -> Option<String> {
+    let zip: String = zip.chars().filter(|c| c.is_ascii_digit()).take(5).collect();
+    let street: Vec<String> = crate::bulk::normalize_name(line1)
+        .split(' ')
+        .filter(|w| !w.is_empty())
+        .map(|w| ADDRESS_WORDS.iter().find(|(long, _)| *long == w).map_or(w, |(_, short)| short).to_string())
+        .collect();
+    (zip.len() == 5 && !street.is_empty()).then(|| format!("{} {}", street.join(" "), zip))
+}
+
+// A free-form address ("1 Main St, Suite 2, St Paul, MN 55101") as an
+// `address_key`: the first comma-separated part and the last ZIP code in it
+pub fn address_key_from_text(address: &str) -> Option<String> {
+    let line1 = address.split(',').next().unwrap_or_default();
+    let zip = address
+        .split(|c: char| !c.is_ascii_digit() && c != '-')
+        .rfind(|w| w.len() >= 5 && w[..5].chars().all(|c| c.is_ascii_digit()))?;
+    address_key(line1, zip)
+}
+
+// Titles and credentials ignored when comparing people's names
+const NAME_TITLES: &[&str] = &["DR", "MR", "MRS", "MS", "MD", "DO", "RN", "NP", "DDS", "DMD", "PHD", "JR", "SR", "II", "III"];
+
+// Name tokens for fuzzy comparison: `name_key` without initials and titles, sorted
+pub fn fuzzy_key(name: &str) -> String {
+    let mut tokens: Vec<String> = name_key(name)
+        .split(' ')
+        .filter(|w| w.len() > 1 && !NAME_TITLES.contains(w))
+        .map(str::to_string)
+        .collect();
+    tokens.sort();
+    tokens.join(" ")
+}
+
+// American Soundex of one word, e.g. "ROBERT" -> "R163"
+pub fn soundex(word: &str) -> String {
+    let code = |c: char| match c {
+        'B' | 'F' | 'P' | 'V' => '1',
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => '2',
+        'D' | 'T' => '3',
+        'L' => '4',
+        'M' | 'N' => '5',
+        'R' => '6',
+        'H' | 'W' => '-',
+        _ => '0',
+    };
+    let letters: Vec<char> = word.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_uppercase()).collect();
+    let Some(&first) = letters.first() else { return String::new() };
+    let mut out = first.to_string();
+    let mut last = code(first);
+    for &c in &letters[1..] {
+        let d = code(c);
+        // H and W don't separate letters with the same code; vowels do
+        if d == '-' {
+            continue;
+        }
+        if d != '0' && d != last {
+            out.push(d);
+            if out.len() == 4 {
+                break;
+            }
+        }
+        last = d;
+    }
+    format!("{:0<4}", out)
+}
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() { 1.0 } else { 0.0 };
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_used = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, &c) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        if let Some(j) = (lo..hi).find(|&j| !b_used[j] && b[j] == c) {
+            b_used[j] = true;
+            a_matches.push(c);
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+    let b_matches: Vec<char> = b.iter().zip(&b_used).filter(|(_, &u)| u).map(|(&c, _)| c).collect();
+    let transpositions = a_matches.iter().zip(&b_matches).filter(|(x, y)| x != y).count() as f64 / 2.0;
+    let m = a_matches.len() as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions) / m) / 3.0
+}
+
+// Jaro-Winkler similarity of two fuzzy keys, 0.0 to 1.0
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let j = jaro(&a, &b);
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count() as f64;
+    j + prefix * 0.1 * (1.0 - j)
+}
+
+// Someone or something in a hunt that can be screened
+#[derive(Clone, Debug)]
+pub struct Subject {
+    // "target" or "party"
+    pub subject_type: String,
+    pub subject_id: Option<String>,
+    pub name: String,
+    pub npi: Option<String>,
+    pub address: Option<String>,
+}
+
+// The case's target entity and every party
+pub fn subjects(db: &crate::db::HuntDatabase) -> Result<Vec<Subject>> {
+    let mut subjects = Vec::new();
+    let target = db.case_record()?.and_then(|r| r.target_entity).filter(|t| !t.trim().is_empty());
+    if let Some(name) = target {
+        subjects.push(Subject { subject_type: "target".into(), subject_id: None, name, npi: None, address: None });
+    }
+    let mut stmt = db.conn.prepare("SELECT id, name, npi, address FROM parties ORDER BY id")?;
+    let parties = stmt.query_map([], |r| {
+        Ok(Subject {
+            subject_type: "party".into(),
+            subject_id: Some(r.get::<_, i64>(0)?.to_string()),
+            name: r.get(1)?,
+            npi: r.get::<_, Option<String>>(2)?.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            address: r.get::<_, Option<String>>(3)?.filter(|a| !a.trim().is_empty()),
+        })
+    })?;
+    for party in parties {
+        subjects.push(party?);
+    }
+    Ok(subjects)
+}
+
+// Extract dates come as MM/DD/YYYY, YYYYMMDD or YYYY-MM-DD; stored as YYYY-MM-DD.
+// Blank and "Indefinite" give None.
+pub fn iso_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    ["%m/%d/%Y", "%Y%m%d", "%Y-%m-%d"]
+        .iter()
+        .find_map(|f| chrono::NaiveDate::parse_from_str(value, f).ok())
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+// Runs `read` over an extract file, or over the first entry whose lowercased
+// name is `wanted` if the file is a zip, as downloaded
+pub fn read_extract<T>(
+    path: &Path,
+    wanted: impl Fn(&str) -> bool,
+    read: impl FnOnce(&mut dyn BufRead, &str) -> std::result::Result<T, String>,
+) -> std::result::Result<T, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let is_zip = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        return read(&mut BufReader::new(file), &name);
+    }
+
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Not a zip archive: {}", e))?;
+    let index = (0..archive.len())
+        .find(|&i| archive.name_for_index(i).is_some_and(|n| wanted(&n.to_lowercase())))
+        .ok_or_else(|| "The archive does not contain the expected extract file".to_string())?;
+    let entry = archive.by_index(index).map_err(|e| e.to_string())?;
+    let name = entry.name().rsplit('/').next().unwrap_or_default().to_string();
+    let mut reader = BufReader::new(entry);
+    read(&mut reader, &name)
+}
+
+// Records a finished import and commits the transaction that loaded its rows
+pub fn finish_import(
+    reference: &ReferenceDb,
+    tx: rusqlite::Transaction,
+    dataset: &str,
+    file_name: &str,
+    rows: usize,
+) -> std::result::Result<ReferenceDataset, String> {
+    if rows == 0 {
+        return Err("No records found in the extract".to_string());
+    }
+    reference.record_import(dataset, file_name, rows).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    reference
+        .dataset(dataset)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Import was not recorded".to_string())
+}
+
+// Award action dates for a hunt: each attached award's signing date and every
+// stored modification, as (award id, date)
+pub fn award_dates(db: &crate::db::HuntDatabase) -> Result<Vec<(String, String)>> {
+    let mut stmt = db.conn.prepare(
+        "SELECT generated_internal_id, date_signed FROM target_awards WHERE date_signed IS NOT NULL AND date_signed != '' \
+         UNION SELECT generated_internal_id, action_date FROM award_transactions WHERE action_date IS NOT NULL \
+         ORDER BY 2",
+    )?;
+    let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+    rows.collect()
+}
+
+// Dates falling in [start, end]; an open end means still in effect
+pub fn dates_within<'a>(dates: &'a [(String, String)], start: Option<&str>, end: Option<&str>) -> Vec<&'a (String, String)> {
+    dates
+        .iter()
+        .filter(|(_, d)| {
+            let d = &d[..d.len().min(10)];
+            start.is_some_and(|s| d >= s) && end.is_none_or(|e| d <= e)
+        })
+        .collect()
+}
@@ -0,0 +1,404 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use crate::crypto::{self, SessionKey};
+
+// Hunt-wide full-text search.
+//
+// The FTS5 table never sees plaintext: every token is replaced by a keyed hash
+// derived from the session key (a "blind index"), and the table is contentless.
+// Readable text lives encrypted in `evidence_text` (or in the plaintext record
+// tables for events, parties and complaint sections) and is only decrypted to
+// build snippets for the rows that matched.
+
+pub const KIND_EVIDENCE: &str = "evidence";
+pub const KIND_EVENT: &str = "event";
+pub const KIND_PARTY: &str = "party";
+pub const KIND_SECTION: &str = "section";
+
+#[derive(Serialize, Debug)]
+pub struct SearchHit {
+    pub kind: String,
+    pub ref_id: String,
+    pub title: String,
+    pub snippet: String,
+    pub score: f64,
+}
+
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn blind(token: &str, key: &SessionKey) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"open-season-fts\0");
+    hasher.update(key.0);
+    hasher.update(token.as_bytes());
+    let digest = hasher.finalize();
+    // 80 bits is plenty to keep distinct terms distinct inside one hunt
+    digest[..10].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn blind_tokens(text: &str, key: &SessionKey) -> String {
+    tokenize(text)
+        .iter()
+        .map(|t| blind(t, key))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn index_document(conn: &Connection, kind: &str, ref_id: &str, blind_body: &str) -> Result<(), String> {
+    remove_document(conn, kind, ref_id)?;
+    conn.execute(
+        "INSERT INTO search_docs (kind, ref_id) VALUES (?1, ?2)",
+        params![kind, ref_id],
+    ).map_err(|e| e.to_string())?;
+    let rowid = conn.last_insert_rowid();
+    conn.execute(
+        "INSERT INTO search_index (rowid, body) VALUES (?1, ?2)",
+        params![rowid, blind_body],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn remove_document(conn: &Connection, kind: &str, ref_id: &str) -> Result<(), String> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM search_docs WHERE kind = ?1 AND ref_id = ?2",
+            params![kind, ref_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(rowid) = existing {
+        conn.execute("DELETE FROM search_index WHERE rowid = ?1", params![rowid])
+            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM search_docs WHERE id = ?1", params![rowid])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn remove_kind(conn: &Connection, kind: &str) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM search_index WHERE rowid IN (SELECT id FROM search_docs WHERE kind = ?1)",
+        params![kind],
+    ).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM search_docs WHERE kind = ?1", params![kind])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn store_evidence_text(
+    conn: &Connection,
+    evidence_id: i64,
+    source: &str,
+    ciphertext: &[u8],
+    nonce: &[u8],
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO evidence_text (evidence_id, source, ciphertext, nonce) VALUES (?1, ?2, ?3, ?4) \
+         ON CONFLICT(evidence_id, source) DO UPDATE SET ciphertext = excluded.ciphertext, nonce = excluded.nonce",
+        params![evidence_id, source, ciphertext, nonce],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// All decrypted text stored for one evidence item, in a stable source order
+pub fn evidence_text(conn: &Connection, evidence_id: i64, key: &SessionKey) -> Result<String, String> {
+    let mut stmt = conn
+        .prepare("SELECT ciphertext, nonce FROM evidence_text WHERE evidence_id = ?1 ORDER BY source ASC")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![evidence_id], |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, Vec<u8>>(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut parts = Vec::new();
+    for r in rows {
+        let (ciphertext, nonce) = r.map_err(|e| e.to_string())?;
+        let plaintext = crypto::decrypt_data(&ciphertext, &nonce, key)?;
+        parts.push(String::from_utf8_lossy(&plaintext).into_owned());
+    }
+    Ok(parts.join("\n"))
+}
+
+fn evidence_label(conn: &Connection, evidence_id: i64) -> Result<(String, String), String> {
+    conn.query_row(
+        "SELECT file_path, description FROM evidence WHERE id = ?1",
+        params![evidence_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|e| e.to_string())
+}
+
+// Rebuilds the index entry of one evidence item from its label and stored text
+pub fn reindex_evidence(conn: &Connection, evidence_id: i64, key: &SessionKey) -> Result<(), String> {
+    let (filename, description) = evidence_label(conn, evidence_id)?;
+    let text = evidence_text(conn, evidence_id, key)?;
+    let body = format!("{}\n{}\n{}", filename, description, text);
+    index_document(conn, KIND_EVIDENCE, &evidence_id.to_string(), &blind_tokens(&body, key))
+}
+
+// Events, parties and complaint sections are indexed by whatever writes them.
+// Refreshes one record's entry, or drops it if the record is gone.
+pub fn index_record(conn: &Connection, key: &SessionKey, kind: &str, ref_id: &str) -> Result<(), String> {
+    match record_rows(conn, Some((kind, ref_id)))?.into_iter().next() {
+        Some((kind, ref_id, _title, body)) => index_document(conn, kind, &ref_id, &blind_tokens(&body, key)),
+        None => remove_document(conn, kind, ref_id),
+    }
+}
+
+// Bumped when record entries must be rebuilt, e.g. for hunts from before
+// records were indexed on write
+const RECORD_INDEX_VERSION: i64 = 1;
+
+fn ensure_record_index(conn: &Connection, key: &SessionKey) -> Result<(), String> {
+    let version: Option<i64> = conn
+        .query_row("SELECT value FROM counters WHERE name = 'record_index_version'", [], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if version.unwrap_or(0) >= RECORD_INDEX_VERSION {
+        return Ok(());
+    }
+
+    for kind in [KIND_EVENT, KIND_PARTY, KIND_SECTION] {
+        remove_kind(conn, kind)?;
+    }
+    for (kind, ref_id, _title, body) in record_rows(conn, None)? {
+        index_document(conn, kind, &ref_id, &blind_tokens(&body, key))?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO counters (name, value) VALUES ('record_index_version', ?1)",
+        params![RECORD_INDEX_VERSION],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+type RecordRow = (&'static str, String, String, String);
+
+// (kind, ref_id, title, searchable body) for the plaintext record tables,
+// optionally restricted to a single (kind, ref_id)
+fn record_rows(conn: &Connection, only: Option<(&str, &str)>) -> Result<Vec<RecordRow>, String> {
+    let queries: [(&'static str, &str); 3] = [
+        (KIND_EVENT, "SELECT CAST(id AS TEXT), title, COALESCE(description, '') || ' ' || COALESCE(event_type, '') FROM events"),
+        (KIND_PARTY, "SELECT CAST(id AS TEXT), name, COALESCE(role, '') || ' ' || COALESCE(notes, '') FROM parties"),
+        (KIND_SECTION, "SELECT section_id, section_id, COALESCE(content, '') FROM complaint_sections"),
+    ];
+
+    let mut out = Vec::new();
+    for (kind, sql) in queries {
+        if let Some((only_kind, _)) = only {
+            if only_kind != kind {
+                continue;
+            }
+        }
+        let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+            .map_err(|e| e.to_string())?;
+        for r in rows {
+            let (ref_id, title, body) = r.map_err(|e| e.to_string())?;
+            if let Some((_, only_id)) = only {
+                if only_id != ref_id {
+                    continue;
+                }
+            }
+            let full = format!("{}\n{}", title, body);
+            out.push((kind, ref_id, title, full));
+        }
+    }
+    Ok(out)
+}
+
+fn match_expression(query: &str, key: &SessionKey) -> Option<(String, Vec<String>)> {
+    let mut clauses = Vec::new();
+    let mut terms = Vec::new();
+    for word in query.split_whitespace() {
+        let tokens = tokenize(word);
+        if tokens.is_empty() {
+            continue;
+        }
+        // A word like "INV-0042" becomes a phrase over its parts
+        let phrase = tokens.iter().map(|t| blind(t, key)).collect::<Vec<_>>().join(" ");
+        clauses.push(format!("\"{}\"", phrase));
+        terms.extend(tokens);
+    }
+    if clauses.is_empty() {
+        None
+    } else {
+        Some((clauses.join(" AND "), terms))
+    }
+}
+
+pub fn search(conn: &Connection, key: &SessionKey, query: &str, limit: usize) -> Result<Vec<SearchHit>, String> {
+    ensure_record_index(conn, key)?;
+
+    let (expression, terms) = match match_expression(query, key) {
+        Some(m) => m,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.kind, d.ref_id, bm25(search_index) AS score \
+             FROM search_index JOIN search_docs d ON d.id = search_index.rowid \
+             WHERE search_index MATCH ?1 ORDER BY score LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![expression, limit as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, f64>(2)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut hits = Vec::new();
+    for r in rows {
+        let (kind, ref_id, score) = r.map_err(|e| e.to_string())?;
+        let (title, body) = if kind == KIND_EVIDENCE {
+            let evidence_id: i64 = ref_id.parse().map_err(|_| "Corrupt search index entry".to_string())?;
+            let (filename, description) = evidence_label(conn, evidence_id)?;
+            let text = evidence_text(conn, evidence_id, key)?;
+            (filename, format!("{}\n{}", description, text))
+        } else {
+            match record_rows(conn, Some((&kind, &ref_id)))?.into_iter().next() {
+                Some((_, _, title, body)) => (title, body),
+                None => continue,
+            }
+        };
+
+        hits.push(SearchHit {
+            kind,
+            ref_id,
+            title,
+            snippet: make_snippet(&body, &terms),
+            // bm25() is lower-is-better; flip it so callers can sort descending
+            score: -score,
+        });
+    }
+    Ok(hits)
+}
+
+const SNIPPET_BEFORE: usize = 60;
+const SNIPPET_AFTER: usize = 140;
+
+// A window of text around the first matched term, with matches wrapped in **
+pub fn make_snippet(text: &str, terms: &[String]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text.to_lowercase().chars().collect();
+    // Lowercasing can change length for a few scripts; fall back to no highlighting
+    let same_len = lower.len() == chars.len();
+
+    let find_term = |from: usize| -> Option<(usize, usize)> {
+        if !same_len {
+            return None;
+        }
+        let mut best: Option<(usize, usize)> = None;
+        for term in terms {
+            let t: Vec<char> = term.chars().collect();
+            if t.is_empty() || t.len() > lower.len() {
+                continue;
+            }
+            for start in from..=lower.len() - t.len() {
+                let boundary_before = start == 0 || !lower[start - 1].is_alphanumeric();
+                let boundary_after = start + t.len() == lower.len() || !lower[start + t.len()].is_alphanumeric();
+                if boundary_before && boundary_after && lower[start..start + t.len()] == t[..] {
+                    match best {
+                        Some((s, _)) if s <= start => {}
+                        _ => best = Some((start, start + t.len())),
+                    }
+                    break;
+                }
+            }
+        }
+        best
+    };
+
+    let anchor = find_term(0).map(|(s, _)| s).unwrap_or(0);
+    let start = anchor.saturating_sub(SNIPPET_BEFORE);
+    let end = (anchor + SNIPPET_AFTER).min(chars.len());
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    let mut pos = start;
+    while pos < end {
+        match find_term(pos) {
+            Some((s, e)) if e <= end => {
+                out.extend(&chars[pos..s]);
+                out.push_str("**");
+                out.extend(&chars[s..e]);
+                out.push_str("**");
+                pos = e;
+            }
+            _ => {
+                out.extend(&chars[pos..end]);
+                pos = end;
+            }
+        }
+    }
+    if end < chars.len() {
+        out.push('…');
+    }
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::HuntDatabase;
+
+    #[test]
+    fn test_blind_index_search() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([7u8; 32]);
+        db.conn.execute(
+            "INSERT INTO events (title, description, event_date) VALUES ('Invoice submitted', 'Billed INV-0042 twice', '2023-01-05')",
+            [],
+        ).unwrap();
+        db.conn.execute(
+            "INSERT INTO parties (name, role, notes) VALUES ('Pat Doe', 'Witness', 'Saw the shredding')",
+            [],
+        ).unwrap();
+
+        let hits = search(&db.conn, &key, "inv-0042", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].kind, KIND_EVENT);
+        assert!(hits[0].snippet.contains("**INV**-**0042**"));
+
+        // The index only ever holds keyed hashes
+        let body_count: i64 = db.conn
+            .query_row("SELECT count(*) FROM search_index WHERE search_index MATCH 'shredding'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(body_count, 0);
+    }
+
+    #[test]
+    fn test_records_indexed_on_write_and_search_only_reads() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([7u8; 32]);
+        // A hunt from before write-time indexing is rebuilt on its first search
+        db.conn.execute("INSERT INTO parties (name, role) VALUES ('Pat Doe', 'Witness')", []).unwrap();
+        assert_eq!(search(&db.conn, &key, "pat", 10).unwrap().len(), 1);
+
+        db.conn.execute("INSERT INTO complaint_sections (section_id, content) VALUES ('facts', 'Kickbacks paid monthly')", []).unwrap();
+        index_record(&db.conn, &key, KIND_SECTION, "facts").unwrap();
+        db.conn.execute("UPDATE complaint_sections SET content = 'Upcoded every claim' WHERE section_id = 'facts'", []).unwrap();
+        index_record(&db.conn, &key, KIND_SECTION, "facts").unwrap();
+
+        let before = db.conn.total_changes();
+        assert!(search(&db.conn, &key, "kickbacks", 10).unwrap().is_empty());
+        assert_eq!(search(&db.conn, &key, "upcoded", 10).unwrap()[0].ref_id, "facts");
+        assert_eq!(db.conn.total_changes(), before);
+
+        // A deleted record's entry goes with it
+        db.conn.execute("DELETE FROM parties", []).unwrap();
+        let party_id = db.conn.query_row("SELECT ref_id FROM search_docs WHERE kind = 'party'", [], |r| r.get::<_, String>(0)).unwrap();
+        index_record(&db.conn, &key, KIND_PARTY, &party_id).unwrap();
+        assert!(search(&db.conn, &key, "pat", 10).unwrap().is_empty());
+    }
+}
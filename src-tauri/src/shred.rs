@@ -0,0 +1,100 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+// Secure deletion helpers. Files are overwritten with random bytes and synced
+// before being unlinked; SQLite deletes rely on `PRAGMA secure_delete` (set in
+// HuntDatabase::open) plus a WAL checkpoint and VACUUM afterwards.
+//
+// Overwriting is best effort: SSD wear levelling and copy-on-write filesystems
+// can keep old blocks around. Every blob is also encrypted, so what survives is
+// ciphertext.
+
+const OVERWRITE_CHUNK: usize = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct DeletionReport {
+    pub files_overwritten: usize,
+    pub bytes_overwritten: u64,
+    pub failures: Vec<String>,
+    pub database_compacted: bool,
+    pub notes: Vec<String>,
+}
+
+impl DeletionReport {
+    pub fn note(&mut self, note: &str) {
+        if !self.notes.iter().any(|n| n == note) {
+            self.notes.push(note.to_string());
+        }
+    }
+}
+
+// Overwrites then removes one file; a missing file is not an error
+pub fn shred_file(path: &Path, report: &mut DeletionReport) {
+    if !path.is_file() {
+        return;
+    }
+    match overwrite(path) {
+        Ok(len) => {
+            report.files_overwritten += 1;
+            report.bytes_overwritten += len;
+        }
+        Err(e) => report.failures.push(format!("{}: overwrite failed: {}", path.display(), e)),
+    }
+    if let Err(e) = fs::remove_file(path) {
+        report.failures.push(format!("{}: {}", path.display(), e));
+    }
+}
+
+// Shreds every file below `dir`, then removes the directory tree
+pub fn shred_dir(dir: &Path, report: &mut DeletionReport) -> Result<(), String> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            shred_file(entry.path(), report);
+        }
+    }
+    fs::remove_dir_all(dir).map_err(|e| e.to_string())
+}
+
+fn overwrite(path: &Path) -> std::io::Result<u64> {
+    let len = fs::metadata(path)?.len();
+    let mut file = OpenOptions::new().write(true).open(path)?;
+
+    let mut buf = vec![0u8; OVERWRITE_CHUNK];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(OVERWRITE_CHUNK as u64) as usize;
+        rand::thread_rng().fill_bytes(&mut buf[..n]);
+        file.write_all(&buf[..n])?;
+        remaining -= n as u64;
+    }
+    file.sync_all()?;
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shred_dir_overwrites_and_removes() {
+        let dir = std::env::temp_dir().join(format!("os-shred-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("evidence")).unwrap();
+        fs::write(dir.join("metadata.db"), vec![7u8; 100_000]).unwrap();
+        fs::write(dir.join("evidence").join("a.enc"), b"ciphertext").unwrap();
+
+        let mut report = DeletionReport::default();
+        shred_dir(&dir, &mut report).unwrap();
+
+        assert!(!dir.exists());
+        assert_eq!(report.files_overwritten, 2);
+        assert_eq!(report.bytes_overwritten, 100_010);
+        assert!(report.failures.is_empty());
+    }
+}
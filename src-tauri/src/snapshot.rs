@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use crate::bundle;
+use crate::crypto::{self, SessionKey};
+use crate::shred::{self, DeletionReport};
+
+// Point-in-time snapshots of a hunt: a consistent copy of metadata.db taken
+// with SQLite's online backup API plus the hunt's evidence/preview files,
+// zipped and encrypted under the session key as
+// `<app data>/snapshots/<hunt_id>/<snapshot_id>.enc`.
+//
+// A background thread snapshots changed hunts while the vault is unlocked;
+// old snapshots are rotated out by the retention policy.
+
+const SNAPSHOT_ID_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SnapshotPolicy {
+    // 0 disables scheduled snapshots
+    pub interval_minutes: u64,
+    // Always keep this many of the newest snapshots
+    pub keep_last: usize,
+    // Additionally keep the newest snapshot of each of this many recent days
+    pub keep_daily: usize,
+}
+
+impl Default for SnapshotPolicy {
+    fn default() -> Self {
+        Self { interval_minutes: 60, keep_last: 24, keep_daily: 14 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+pub fn snapshots_root(data_root: &Path) -> PathBuf {
+    data_root.join("snapshots")
+}
+
+fn policy_path(data_root: &Path) -> PathBuf {
+    snapshots_root(data_root).join("policy.json")
+}
+
+pub fn load_policy(data_root: &Path) -> SnapshotPolicy {
+    fs::read(policy_path(data_root))
+        .ok()
+        .and_then(|b| serde_json::from_slice(&b).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_policy(data_root: &Path, policy: &SnapshotPolicy) -> Result<(), String> {
+    fs::create_dir_all(snapshots_root(data_root)).map_err(|e| e.to_string())?;
+    let json = serde_json::to_vec_pretty(policy).map_err(|e| e.to_string())?;
+    fs::write(policy_path(data_root), json).map_err(|e| e.to_string())
+}
+
+pub fn create_snapshot(data_root: &Path, hunt_dir: &Path, hunt_id: &str, key: &SessionKey) -> Result<SnapshotInfo, String> {
+    let dir = snapshots_root(data_root).join(hunt_id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    // Online backup into a scratch file, so the copy is consistent even while
+    // the app holds the database open; the plaintext copy is shredded after
+    let scratch = dir.join(format!(".{}.db.tmp", uuid::Uuid::new_v4()));
+    let db_copy = {
+        let conn = Connection::open(hunt_dir.join("metadata.db")).map_err(|e| e.to_string())?;
+        let result = conn
+            .backup(DatabaseName::Main, &scratch, None)
+            .map_err(|e| e.to_string())
+            .and_then(|_| fs::read(&scratch).map_err(|e| e.to_string()));
+        let mut report = DeletionReport::default();
+        shred::shred_file(&scratch, &mut report);
+        result?
+    };
+
+    let archive = bundle::pack_snapshot(hunt_dir, &db_copy)?;
+    let now = chrono::Utc::now();
+    let id = now.format(SNAPSHOT_ID_FORMAT).to_string();
+    let path = dir.join(format!("{}.enc", id));
+    crypto::write_sealed_file(&path, &archive, key)?;
+
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(SnapshotInfo { id, created_at: now.to_rfc3339(), size_bytes })
+}
+
+// Newest first
+pub fn list_snapshots(data_root: &Path, hunt_id: &str) -> Result<Vec<SnapshotInfo>, String> {
+    let dir = snapshots_root(data_root).join(hunt_id);
+    let mut snapshots = Vec::new();
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(snapshots);
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(id) = name.strip_suffix(".enc") else { continue };
+        let Ok(created) = chrono::NaiveDateTime::parse_from_str(id, SNAPSHOT_ID_FORMAT) else { continue };
+        snapshots.push(SnapshotInfo {
+            id: id.to_string(),
+            created_at: created.and_utc().to_rfc3339(),
+            size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+    }
+    snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(snapshots)
+}
+
+// Replaces the hunt folder with the snapshot's contents. The current state is
+// snapshotted first, so a restore can itself be undone.
+pub fn restore_snapshot(
+    data_root: &Path,
+    hunt_dir: &Path,
+    hunt_id: &str,
+    snapshot_id: &str,
+    key: &SessionKey,
+) -> Result<(), String> {
+    let path = snapshots_root(data_root).join(hunt_id).join(format!("{}.enc", snapshot_id));
+    if snapshot_id.contains(['/', '\\', '.']) || !path.exists() {
+        return Err("Snapshot not found".to_string());
+    }
+    let archive = crypto::read_sealed_file(&path, key)?;
+
+    if hunt_dir.join("metadata.db").exists() {
+        create_snapshot(data_root, hunt_dir, hunt_id, key)?;
+    }
+
+    let staging = hunt_dir.with_extension("restoring");
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    bundle::unpack_dir(&archive, &staging)?;
+
+    let mut report = DeletionReport::default();
+    shred::shred_dir(hunt_dir, &mut report)?;
+    fs::rename(&staging, hunt_dir).map_err(|e| e.to_string())
+}
+
+// Deletes snapshots not covered by the policy; returns how many were removed
+pub fn apply_retention(data_root: &Path, hunt_id: &str, policy: &SnapshotPolicy) -> Result<usize, String> {
+    let snapshots = list_snapshots(data_root, hunt_id)?;
+    let mut keep: HashSet<&str> = snapshots.iter().take(policy.keep_last).map(|s| s.id.as_str()).collect();
+
+    // Newest per day, for the most recent `keep_daily` days that have snapshots
+    let mut days_seen = Vec::new();
+    for s in &snapshots {
+        let day = &s.id[..8];
+        if !days_seen.contains(&day) {
+            if days_seen.len() >= policy.keep_daily {
+                break;
+            }
+            days_seen.push(day);
+            keep.insert(s.id.as_str());
+        }
+    }
+
+    let dir = snapshots_root(data_root).join(hunt_id);
+    let mut report = DeletionReport::default();
+    for s in &snapshots {
+        if !keep.contains(s.id.as_str()) {
+            shred::shred_file(&dir.join(format!("{}.enc", s.id)), &mut report);
+        }
+    }
+    Ok(report.files_overwritten)
+}
+
+// True if the hunt database changed after the newest snapshot was taken
+fn needs_snapshot(data_root: &Path, hunt_dir: &Path, hunt_id: &str, interval: Duration) -> bool {
+    let newest = list_snapshots(data_root, hunt_id).ok().and_then(|s| s.into_iter().next());
+    let Some(newest) = newest else { return true };
+    let Ok(taken) = chrono::DateTime::parse_from_rfc3339(&newest.created_at) else { return true };
+    let taken: SystemTime = taken.into();
+
+    if SystemTime::now().duration_since(taken).unwrap_or_default() < interval {
+        return false;
+    }
+    ["metadata.db", "metadata.db-wal"].iter().any(|f| {
+        fs::metadata(hunt_dir.join(f))
+            .and_then(|m| m.modified())
+            .map(|modified| modified > taken)
+            .unwrap_or(false)
+    })
+}
+
+// Runs one scheduler pass over every hunt in `vaults_root`
+pub fn run_scheduled(data_root: &Path, vaults_root: &Path, key: &SessionKey) {
+    let policy = load_policy(data_root);
+    if policy.interval_minutes == 0 {
+        return;
+    }
+    let interval = Duration::from_secs(policy.interval_minutes * 60);
+
+    let Ok(entries) = fs::read_dir(vaults_root) else { return };
+    for entry in entries.flatten() {
+        let hunt_dir = entry.path();
+        let hunt_id = entry.file_name().to_string_lossy().into_owned();
+        if !hunt_dir.join("metadata.db").exists() || !needs_snapshot(data_root, &hunt_dir, &hunt_id, interval) {
+            continue;
+        }
+        if let Err(e) = create_snapshot(data_root, &hunt_dir, &hunt_id, key) {
+            eprintln!("WARNING: scheduled snapshot of {} failed: {}", hunt_id, e);
+            continue;
+        }
+        if let Err(e) = apply_retention(data_root, &hunt_id, &policy) {
+            eprintln!("WARNING: snapshot retention for {} failed: {}", hunt_id, e);
+        }
+    }
+}
+
+// Background thread; does nothing while the vault is locked
+pub fn start_scheduler(data_root: PathBuf, key: Arc<Mutex<Option<SessionKey>>>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SCHEDULER_TICK);
+        let current = key.lock().map(|k| k.clone()).unwrap_or(None);
+        if let Some(k) = current {
+            run_scheduled(&data_root, &data_root.join("vaults"), &k);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::HuntDatabase;
+
+    #[test]
+    fn test_snapshot_restore_and_retention() {
+        let base = std::env::temp_dir().join(format!("os-snap-{}", uuid::Uuid::new_v4()));
+        let hunt_dir = base.join("vaults").join("h1");
+        fs::create_dir_all(hunt_dir.join("evidence")).unwrap();
+        fs::write(hunt_dir.join("evidence").join("abc.enc"), b"blob").unwrap();
+        let key = SessionKey([5u8; 32]);
+
+        {
+            let db = HuntDatabase::open(hunt_dir.join("metadata.db")).unwrap();
+            db.conn.execute("INSERT INTO parties (name) VALUES ('Before')", []).unwrap();
+        }
+        let snap = create_snapshot(&base, &hunt_dir, "h1", &key).unwrap();
+        {
+            let db = HuntDatabase::open(hunt_dir.join("metadata.db")).unwrap();
+            db.conn.execute("UPDATE parties SET name = 'After'", []).unwrap();
+        }
+        fs::remove_file(hunt_dir.join("evidence").join("abc.enc")).unwrap();
+
+        restore_snapshot(&base, &hunt_dir, "h1", &snap.id, &key).unwrap();
+        let db = HuntDatabase::open(hunt_dir.join("metadata.db")).unwrap();
+        let name: String = db.conn.query_row("SELECT name FROM parties", [], |r| r.get(0)).unwrap();
+        assert_eq!(name, "Before");
+        assert!(hunt_dir.join("evidence").join("abc.enc").exists());
+
+        // The original plus the pre-restore safety snapshot
+        assert_eq!(list_snapshots(&base, "h1").unwrap().len(), 2);
+        let policy = SnapshotPolicy { interval_minutes: 60, keep_last: 1, keep_daily: 1 };
+        assert_eq!(apply_retention(&base, "h1", &policy).unwrap(), 1);
+        assert_eq!(list_snapshots(&base, "h1").unwrap().len(), 1);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
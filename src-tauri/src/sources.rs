@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::cache::ResponseCache;
+use crate::usaspending::{
+    self, ApiConfig, AwardPage, AwardRecord, GroupFailure, RecipientCandidate, SearchFilters, TargetSearch,
+};
+
+// Target intelligence sources. Each source can search for a target's awards,
+// resolve a name to candidate entities, and fetch one award in full.
+// USAspending is always available; further sources are compiled in behind
+// cargo features and get an entry in `SOURCES`.
+
+pub const DEFAULT_SOURCE: &str = usaspending::SOURCE_ID;
+
+struct Registration {
+    id: &'static str,
+    label: &'static str,
+    open: fn(&SourceOptions) -> Box<dyn TargetSource>,
+}
+
+// Every source in this build
+const SOURCES: &[Registration] = &[
+    Registration {
+        id: usaspending::SOURCE_ID,
+        label: "USAspending.gov",
+        open: |options| Box::new(UsaSpending::new(ApiConfig::default(), options.clone())),
+    },
+    #[cfg(feature = "source-awards-file")]
+    Registration {
+        id: crate::awards_file::SOURCE_ID,
+        label: "Awards file",
+        open: |_| Box::new(crate::awards_file::AwardsFile::from_env()),
+    },
+];
+
+pub trait TargetSource: Send + Sync {
+    fn id(&self) -> &'static str;
+
+    fn search(
+        &self,
+        target_name: &str,
+        filters: &SearchFilters,
+        max_results: usize,
+        on_page: &mut dyn FnMut(&AwardPage),
+    ) -> Result<TargetSearch, String>;
+
+    fn resolve_entity(&self, query: &str, limit: usize) -> Result<Vec<RecipientCandidate>, String>;
+
+    fn fetch_award(&self, award_id: &str) -> Result<AwardRecord, String>;
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SourceInfo {
+    pub id: String,
+    pub label: String,
+}
+
+// Settings shared by every source opened for one request
+#[derive(Default, Clone)]
+pub struct SourceOptions {
+    // Encrypted response cache, when the vault is unlocked
+    pub cache: Option<ResponseCache>,
+    pub offline: bool,
+}
+
+pub struct UsaSpending {
+    config: ApiConfig,
+    options: SourceOptions,
+}
+
+impl UsaSpending {
+    pub fn new(config: ApiConfig, options: SourceOptions) -> Self {
+        Self { config, options }
+    }
+}
+
+impl TargetSource for UsaSpending {
+    fn id(&self) -> &'static str {
+        usaspending::SOURCE_ID
+    }
+
+    fn search(
+        &self,
+        target_name: &str,
+        filters: &SearchFilters,
+        max_results: usize,
+        on_page: &mut dyn FnMut(&AwardPage),
+    ) -> Result<TargetSearch, String> {
+        match &self.options.cache {
+            Some(cache) => usaspending::check_target_cached(
+                &self.config, cache, target_name, filters, max_results, self.options.offline, on_page,
+            ),
+            None if self.options.offline => Err("Offline results need an unlocked vault".to_string()),
+            None => usaspending::check_target_at(&self.config, target_name, filters, max_results, on_page),
+        }
+    }
+
+    fn resolve_entity(&self, query: &str, limit: usize) -> Result<Vec<RecipientCandidate>, String> {
+        usaspending::autocomplete_recipients(&self.config, query, limit).map_err(|e| e.to_string())
+    }
+
+    fn fetch_award(&self, award_id: &str) -> Result<AwardRecord, String> {
+        usaspending::fetch_award_record(&self.config, award_id).map_err(|e| e.to_string())
+    }
+}
+
+pub fn available() -> Vec<SourceInfo> {
+    SOURCES
+        .iter()
+        .map(|source| SourceInfo { id: source.id.to_string(), label: source.label.to_string() })
+        .collect()
+}
+
+pub fn open(id: &str, options: &SourceOptions) -> Result<Box<dyn TargetSource>, String> {
+    SOURCES
+        .iter()
+        .find(|source| source.id == id)
+        .map(|source| (source.open)(options))
+        .ok_or_else(|| format!("Unknown data source '{}'", id))
+}
+
+// How one source fared in a merged search
+#[derive(Serialize, Clone, Debug)]
+pub struct SourceReport {
+    pub source: String,
+    pub award_count: usize,
+    pub fetched_at: Option<String>,
+    pub from_cache: bool,
+    pub failures: Vec<GroupFailure>,
+    // Set when the source returned nothing at all
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MergedSearch {
+    #[serde(flatten)]
+    pub search: TargetSearch,
+    pub sources: Vec<SourceReport>,
+}
+
+// A page from one source, as emitted to the frontend
+#[derive(Serialize, Clone)]
+pub struct SourcePage<'a> {
+    pub source: &'a str,
+    #[serde(flatten)]
+    pub page: &'a AwardPage,
+}
+
+// Queries every source at once and merges the results
+pub fn search_all(
+    sources: &[Box<dyn TargetSource>],
+    target_name: &str,
+    filters: &SearchFilters,
+    max_results: usize,
+    on_page: impl Fn(SourcePage) + Sync,
+) -> Result<MergedSearch, String> {
+    let on_page = &on_page;
+    let results: Vec<(&str, Result<TargetSearch, String>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = sources
+            .iter()
+            .map(|source| {
+                let id = source.id();
+                let handle = scope.spawn(move || {
+                    source.search(target_name, filters, max_results, &mut |page| on_page(SourcePage { source: id, page }))
+                });
+                (id, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(id, handle)| (id, handle.join().unwrap_or_else(|_| Err("Source panicked".to_string()))))
+            .collect()
+    });
+    merge(results)
+}
+
+// Awards reported by several sources are kept once, with the fields of the
+// first source to report them and every reporting source in `sources`. Fails
+// only if every source failed.
+pub fn merge(results: Vec<(&str, Result<TargetSearch, String>)>) -> Result<MergedSearch, String> {
+    if results.iter().all(|(_, r)| r.is_err()) {
+        let errors: Vec<String> = results
+            .into_iter()
+            .filter_map(|(id, r)| r.err().map(|e| format!("{}: {}", id, e)))
+            .collect();
+        return Err(if errors.is_empty() { "No data sources selected".to_string() } else { errors.join("; ") });
+    }
+
+    let mut merged = TargetSearch::default();
+    let mut reports = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (id, result) in results {
+        let search = match result {
+            Ok(search) => search,
+            Err(e) => {
+                reports.push(SourceReport {
+                    source: id.to_string(),
+                    award_count: 0,
+                    fetched_at: None,
+                    from_cache: false,
+                    failures: Vec::new(),
+                    error: Some(e),
+                });
+                continue;
+            }
+        };
+
+        for award in &search.awards {
+            match positions.get(&award.generated_internal_id) {
+                Some(&i) => {
+                    let sources = &mut merged.awards[i].sources;
+                    if !sources.iter().any(|s| s == id) {
+                        sources.push(id.to_string());
+                    }
+                }
+                None => {
+                    positions.insert(award.generated_internal_id.clone(), merged.awards.len());
+                    let mut award = award.clone();
+                    award.sources = vec![id.to_string()];
+                    merged.awards.push(award);
+                }
+            }
+        }
+        for new_id in &search.new_award_ids {
+            if !merged.new_award_ids.contains(new_id) {
+                merged.new_award_ids.push(new_id.clone());
+            }
+        }
+        merged.failures.extend(search.failures.iter().cloned());
+        merged.from_cache |= search.from_cache;
+        merged.fetched_at = merged.fetched_at.max(search.fetched_at.clone());
+        merged.previous_fetched_at = match (merged.previous_fetched_at.take(), search.previous_fetched_at.clone()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        reports.push(SourceReport {
+            source: id.to_string(),
+            award_count: search.awards.len(),
+            fetched_at: search.fetched_at,
+            from_cache: search.from_cache,
+            failures: search.failures,
+            error: None,
+        });
+    }
+    Ok(MergedSearch { search: merged, sources: reports })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usaspending::AwardSummary;
+
+    struct Fixed {
+        id: &'static str,
+        awards: Option<Vec<(&'static str, f64)>>,
+    }
+
+    impl TargetSource for Fixed {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+
+        fn search(&self, _: &str, _: &SearchFilters, _: usize, on_page: &mut dyn FnMut(&AwardPage)) -> Result<TargetSearch, String> {
+            let awards: Vec<AwardSummary> = self
+                .awards
+                .as_ref()
+                .ok_or("unreachable")?
+                .iter()
+                .map(|(id, value)| AwardSummary {
+                    generated_internal_id: id.to_string(),
+                    date_signed: "2021-01-01".into(),
+                    description: None,
+                    total_obligation: *value,
+                    awarding_agency: self.id.into(),
+                    recipient_name: "ACME".into(),
+                    raw: None,
+                    sources: Vec::new(),
+                })
+                .collect();
+            on_page(&AwardPage { group: "contracts".into(), page: 1, awards: awards.clone(), fetched: awards.len(), has_next: false });
+            Ok(TargetSearch { awards, ..Default::default() })
+        }
+
+        fn resolve_entity(&self, _: &str, _: usize) -> Result<Vec<RecipientCandidate>, String> {
+            Ok(Vec::new())
+        }
+
+        fn fetch_award(&self, _: &str) -> Result<AwardRecord, String> {
+            Err("not supported".into())
+        }
+    }
+
+    #[test]
+    fn test_search_all_merges_with_provenance() {
+        let sources: Vec<Box<dyn TargetSource>> = vec![
+            Box::new(Fixed { id: "first", awards: Some(vec![("A", 10.0), ("B", 20.0)]) }),
+            Box::new(Fixed { id: "second", awards: Some(vec![("B", 25.0), ("C", 30.0)]) }),
+            Box::new(Fixed { id: "down", awards: None }),
+        ];
+        let pages = std::sync::Mutex::new(Vec::new());
+        let merged = search_all(&sources, "Acme", &SearchFilters::default(), 100, |p| {
+            pages.lock().unwrap().push(p.source.to_string());
+        })
+        .unwrap();
+
+        let awards: Vec<(&str, f64, Vec<String>)> = merged.search.awards
+            .iter()
+            .map(|a| (a.generated_internal_id.as_str(), a.total_obligation, a.sources.clone()))
+            .collect();
+        assert_eq!(awards, [
+            ("A", 10.0, vec!["first".to_string()]),
+            ("B", 20.0, vec!["first".to_string(), "second".to_string()]),
+            ("C", 30.0, vec!["second".to_string()]),
+        ]);
+        assert_eq!(merged.sources.len(), 3);
+        assert_eq!(merged.sources[2].error.as_deref(), Some("unreachable"));
+        assert_eq!(pages.lock().unwrap().len(), 2);
+
+        assert!(search_all(&sources[2..], "Acme", &SearchFilters::default(), 100, |_| {}).is_err());
+        assert!(open("nowhere", &SourceOptions::default()).is_err());
+        for source in available() {
+            assert_eq!(open(&source.id, &SourceOptions::default()).unwrap().id(), source.id);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+// Hunt templates: JSON files that seed a new hunt with complaint section
+// drafts, checklist items, and the event types and party roles suggested for
+// a fraud scheme. Built-ins ship in `src-tauri/templates/`; user templates are
+// imported into `<app data>/templates/<id>.json`.
+//
+// `format_version` is the file format; `version` is the template's own
+// revision, which hunts record so they can tell which edition they started from.
+
+pub const FORMAT_VERSION: u32 = 1;
+
+const BUILTIN: &[&str] = &[
+    include_str!("../templates/healthcare-upcoding.json"),
+    include_str!("../templates/ppp-loan-fraud.json"),
+    include_str!("../templates/dod-defective-pricing.json"),
+    include_str!("../templates/grant-misuse.json"),
+];
+
+// Section ids the complaint editor and report know about
+const SECTION_IDS: &[&str] = &["introduction", "jurisdiction", "parties", "facts", "violations"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Choice {
+    pub value: String,
+    pub label: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HuntTemplate {
+    pub format_version: u32,
+    pub id: String,
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // section_id -> starting text
+    #[serde(default)]
+    pub complaint_sections: BTreeMap<String, String>,
+    #[serde(default)]
+    pub event_types: Vec<Choice>,
+    #[serde(default)]
+    pub party_roles: Vec<Choice>,
+    #[serde(default)]
+    pub checklist: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateSummary {
+    pub id: String,
+    pub version: u32,
+    pub name: String,
+    pub description: String,
+    pub builtin: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChecklistItem {
+    pub id: i64,
+    pub text: String,
+    pub done: bool,
+    pub done_at: Option<String>,
+}
+
+impl HuntTemplate {
+    fn summary(&self, builtin: bool) -> TemplateSummary {
+        TemplateSummary {
+            id: self.id.clone(),
+            version: self.version,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            builtin,
+        }
+    }
+}
+
+pub fn parse(json: &str) -> Result<HuntTemplate, String> {
+    let template: HuntTemplate = serde_json::from_str(json).map_err(|e| format!("Invalid template: {}", e))?;
+
+    if template.format_version == 0 || template.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "Template format version {} is not supported (expected {})",
+            template.format_version, FORMAT_VERSION
+        ));
+    }
+    // The id becomes a file name
+    let valid_id = !template.id.is_empty()
+        && template.id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid_id {
+        return Err("Template id must be lowercase letters, digits and dashes".to_string());
+    }
+    if template.name.trim().is_empty() {
+        return Err("Template name is required".to_string());
+    }
+    if let Some(unknown) = template.complaint_sections.keys().find(|k| !SECTION_IDS.contains(&k.as_str())) {
+        return Err(format!("Unknown complaint section '{}' (expected one of {})", unknown, SECTION_IDS.join(", ")));
+    }
+    Ok(template)
+}
+
+pub fn builtin_templates() -> Vec<HuntTemplate> {
+    BUILTIN.iter().map(|json| parse(json).expect("built-in template is valid")).collect()
+}
+
+pub fn templates_dir(data_root: &Path) -> PathBuf {
+    data_root.join("templates")
+}
+
+// Unreadable or invalid files are skipped so one bad import can't hide the rest
+fn user_templates(data_root: &Path) -> Vec<HuntTemplate> {
+    let Ok(entries) = fs::read_dir(templates_dir(data_root)) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<HuntTemplate> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|json| parse(&json).ok())
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
+
+pub fn list(data_root: &Path) -> Vec<TemplateSummary> {
+    builtin_templates()
+        .iter()
+        .map(|t| t.summary(true))
+        .chain(user_templates(data_root).iter().map(|t| t.summary(false)))
+        .collect()
+}
+
+pub fn find(data_root: &Path, id: &str) -> Result<HuntTemplate, String> {
+    builtin_templates()
+        .into_iter()
+        .chain(user_templates(data_root))
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Template '{}' not found", id))
+}
+
+// Copies a template file into the user template folder. A template already
+// imported under the same id is replaced only by the same or a newer version.
+pub fn import(data_root: &Path, source: &Path) -> Result<TemplateSummary, String> {
+    let json = fs::read_to_string(source).map_err(|e| e.to_string())?;
+    let template = parse(&json)?;
+
+    if builtin_templates().iter().any(|t| t.id == template.id) {
+        return Err(format!("'{}' is a built-in template id; choose another id", template.id));
+    }
+    if let Some(existing) = user_templates(data_root).into_iter().find(|t| t.id == template.id) {
+        if existing.version > template.version {
+            return Err(format!(
+                "Template '{}' version {} is already installed; refusing to replace it with version {}",
+                template.id, existing.version, template.version
+            ));
+        }
+    }
+
+    let dir = templates_dir(data_root);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let pretty = serde_json::to_vec_pretty(&template).map_err(|e| e.to_string())?;
+    fs::write(dir.join(format!("{}.json", template.id)), pretty).map_err(|e| e.to_string())?;
+    Ok(template.summary(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::HuntDatabase;
+
+    #[test]
+    fn test_import_and_apply_template() {
+        assert_eq!(builtin_templates().len(), BUILTIN.len());
+
+        let base = std::env::temp_dir().join(format!("os-tmpl-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&base).unwrap();
+        let source = base.join("mine.json");
+        let write = |version: u32| {
+            let json = serde_json::json!({
+                "format_version": 1, "id": "custom-scheme", "version": version, "name": "Custom",
+                "complaint_sections": { "facts": "Start here" },
+                "checklist": ["First", "Second"]
+            });
+            fs::write(&source, json.to_string()).unwrap();
+        };
+
+        write(2);
+        assert!(!import(&base, &source).unwrap().builtin);
+        write(1);
+        assert!(import(&base, &source).is_err());
+        assert_eq!(list(&base).len(), BUILTIN.len() + 1);
+
+        let db = HuntDatabase::open(":memory:").unwrap();
+        db.init_case("Test").unwrap();
+        db.apply_template(&find(&base, "custom-scheme").unwrap()).unwrap();
+        let content: String = db.conn
+            .query_row("SELECT content FROM complaint_sections WHERE section_id = 'facts'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(content, "Start here");
+        assert_eq!(db.applied_template().unwrap().unwrap().version, 2);
+
+        let items = db.checklist().unwrap();
+        assert_eq!(items.iter().map(|i| i.text.as_str()).collect::<Vec<_>>(), ["First", "Second"]);
+        assert!(db.set_checklist_done(items[1].id, true).unwrap());
+        assert!(db.checklist().unwrap()[1].done);
+
+        assert!(parse(r#"{"format_version": 2, "id": "x", "version": 1, "name": "X"}"#).is_err());
+        assert!(parse(r#"{"format_version": 1, "id": "../x", "version": 1, "name": "X"}"#).is_err());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use base64::Engine;
+use rusqlite::params;
+use rusqlite::types::{Value, ValueRef};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as Json};
+use crate::crypto::{self, SessionKey};
+use crate::db::{self, HuntDatabase};
+use crate::shred::{self, DeletionReport};
+use crate::{bundle, evidence, preview, search};
+
+// Soft delete. Deleted events, parties and evidence have their rows (and link
+// rows) moved into the hunt's `trash` table as one encrypted JSON payload.
+// Evidence blobs, text and previews stay where they are (already encrypted)
+// until the trash is emptied. Deleted hunts are zipped, encrypted and moved to
+// `<app data>/trash/`, and the plaintext hunt folder is shredded.
+
+pub const KIND_EVENT: &str = "event";
+pub const KIND_PARTY: &str = "party";
+pub const KIND_EVIDENCE: &str = "evidence";
+
+// (table, id column) moved to the trash for each kind; the first is the record itself
+fn tables(kind: &str) -> Result<&'static [(&'static str, &'static str)], String> {
+    match kind {
+        KIND_EVENT => Ok(&[("events", "id"), ("event_evidence", "event_id"), ("event_parties", "event_id")]),
+        KIND_PARTY => Ok(&[("parties", "id"), ("event_parties", "party_id")]),
+        KIND_EVIDENCE => Ok(&[
+            ("evidence", "id"),
+            ("evidence_tags", "evidence_id"),
+            ("event_evidence", "evidence_id"),
+            ("email_messages", "evidence_id"),
+        ]),
+        _ => Err(format!("Unknown record type: {}", kind)),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrashedRows {
+    label: String,
+    rows: Vec<(String, Map<String, Json>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub kind: String,
+    pub ref_id: i64,
+    pub label: String,
+    pub deleted_at: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TrashedHunt {
+    pub hunt_id: String,
+    pub name: String,
+    pub deleted_at: String,
+}
+
+// --- Records inside a hunt ---
+
+pub fn trash_record(db: &HuntDatabase, key: &SessionKey, kind: &str, id: i64) -> Result<(), String> {
+    let tables = tables(kind)?;
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    let mut rows = Vec::new();
+    for (table, column) in tables {
+        for row in take_rows(&tx, table, column, id)? {
+            rows.push((table.to_string(), row));
+        }
+    }
+    let record = match rows.first() {
+        Some((table, row)) if table == tables[0].0 => row,
+        _ => return Err("Record not found".to_string()),
+    };
+
+    let payload = TrashedRows { label: label_for(kind, record), rows };
+    let json = serde_json::to_vec(&payload).map_err(|e| e.to_string())?;
+    let (ciphertext, nonce) = crypto::encrypt_data(&json, key)?;
+    tx.execute(
+        "INSERT OR REPLACE INTO trash (kind, ref_id, ciphertext, nonce, deleted_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![kind, id, ciphertext, nonce, chrono::Utc::now().to_rfc3339()],
+    ).map_err(|e| e.to_string())?;
+
+    search::remove_document(&tx, search_kind(kind), &id.to_string())?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn search_kind(kind: &str) -> &'static str {
+    match kind {
+        KIND_EVENT => search::KIND_EVENT,
+        KIND_PARTY => search::KIND_PARTY,
+        _ => search::KIND_EVIDENCE,
+    }
+}
+
+pub fn list_records(db: &HuntDatabase, key: &SessionKey) -> Result<Vec<TrashEntry>, String> {
+    let mut stmt = db.conn
+        .prepare("SELECT kind, ref_id, deleted_at FROM trash ORDER BY deleted_at DESC")
+        .map_err(|e| e.to_string())?;
+    let keys: Vec<(String, i64, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut entries = Vec::new();
+    for (kind, ref_id, deleted_at) in keys {
+        let payload = load_payload(db, key, &kind, ref_id)?;
+        entries.push(TrashEntry { kind, ref_id, label: payload.label, deleted_at });
+    }
+    Ok(entries)
+}
+
+pub fn restore_record(db: &HuntDatabase, key: &SessionKey, kind: &str, id: i64) -> Result<(), String> {
+    let payload = load_payload(db, key, kind, id)?;
+    let tx = db.conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    for (i, (table, row)) in payload.rows.iter().enumerate() {
+        // Only tables this kind owns may be written, whatever the payload says
+        if !tables(kind)?.iter().any(|(t, _)| t == table) {
+            return Err(format!("Unexpected table in trash payload: {}", table));
+        }
+        let mut row = row.clone();
+        if i == 0 && kind == KIND_EVIDENCE {
+            renumber_if_taken(&tx, &mut row)?;
+        }
+        let columns: Vec<&String> = row.keys().collect();
+        let placeholders: Vec<String> = (1..=columns.len()).map(|n| format!("?{}", n)).collect();
+        let values: Vec<Value> = row.values().map(json_to_value).collect::<Result<_, _>>()?;
+        let sql = format!(
+            "INSERT OR IGNORE INTO {} ({}) VALUES ({})",
+            table,
+            columns.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "),
+            placeholders.join(", ")
+        );
+        let inserted = tx.execute(&sql, rusqlite::params_from_iter(values)).map_err(|e| e.to_string())?;
+        if i == 0 && inserted == 0 {
+            return Err("A record with this id already exists".to_string());
+        }
+    }
+
+    tx.execute("DELETE FROM trash WHERE kind = ?1 AND ref_id = ?2", params![kind, id])
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    if kind == KIND_EVIDENCE {
+        search::reindex_evidence(&db.conn, id, key)?;
+    } else {
+        search::index_record(&db.conn, key, search_kind(kind), &id.to_string())?;
+    }
+    Ok(())
+}
+
+// Permanently deletes every trashed record in the hunt, shredding evidence files.
+// Identical files share one content-addressed blob, so a blob is only shredded
+// once no live row and no other trashed row still uses it.
+pub fn empty_records(hunt_dir: &Path, db: &HuntDatabase, key: &SessionKey, report: &mut DeletionReport) -> Result<(), String> {
+    let mut in_trash: HashMap<(&str, String), usize> = HashMap::new();
+    for row in trashed_evidence(db, key)? {
+        for column in BLOB_HASH_COLUMNS {
+            if let Some(hash) = row.get(column).and_then(|v| v.as_str()) {
+                *in_trash.entry((column, hash.to_string())).or_default() += 1;
+            }
+        }
+    }
+
+    for entry in list_records(db, key)? {
+        if entry.kind == KIND_EVIDENCE {
+            let payload = load_payload(db, key, &entry.kind, entry.ref_id)?;
+            if let Some((_, row)) = payload.rows.first() {
+                for column in BLOB_HASH_COLUMNS {
+                    let Some(hash) = row.get(column).and_then(|v| v.as_str()) else { continue };
+                    let users = in_trash.entry((column, hash.to_string())).or_default();
+                    *users = users.saturating_sub(1);
+                    if *users > 0 || used_by_live_evidence(&db.conn, column, hash)? {
+                        continue;
+                    }
+                    if column == "sha256_hash" {
+                        shred::shred_file(&evidence::scrubbed_path(hunt_dir, hash), report);
+                        shred::shred_file(&preview::legacy_preview_path(hunt_dir, hash), report);
+                    } else {
+                        shred::shred_file(&evidence::original_path(hunt_dir, hash), report);
+                    }
+                }
+                shred::shred_file(&preview::preview_path(hunt_dir, entry.ref_id), report);
+            }
+            for table in ["evidence_text", "evidence_previews"] {
+                db.conn.execute(&format!("DELETE FROM {} WHERE evidence_id = ?1", table), params![entry.ref_id])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        db.conn.execute("DELETE FROM trash WHERE kind = ?1 AND ref_id = ?2", params![entry.kind, entry.ref_id])
+            .map_err(|e| e.to_string())?;
+    }
+
+    db.compact().map_err(|e| e.to_string())?;
+    report.database_compacted = true;
+    Ok(())
+}
+
+// Evidence rows currently in the trash
+fn trashed_evidence(db: &HuntDatabase, key: &SessionKey) -> Result<Vec<Map<String, Json>>, String> {
+    let mut stmt = db.conn
+        .prepare("SELECT ref_id FROM trash WHERE kind = ?1")
+        .map_err(|e| e.to_string())?;
+    let ids: Vec<i64> = stmt
+        .query_map(params![KIND_EVIDENCE], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut rows = Vec::new();
+    for id in ids {
+        let payload = load_payload(db, key, KIND_EVIDENCE, id)?;
+        if let Some((_, row)) = payload.rows.into_iter().next() {
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+// Nonce a trashed evidence row recorded for the blob stored under `hash`
+pub fn trashed_evidence_nonce(
+    db: &HuntDatabase,
+    key: &SessionKey,
+    hash_column: &str,
+    nonce_column: &str,
+    hash: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    for row in trashed_evidence(db, key)? {
+        if row.get(hash_column).and_then(|v| v.as_str()) != Some(hash) {
+            continue;
+        }
+        if let Some(Value::Blob(nonce)) = row.get(nonce_column).map(json_to_value).transpose()? {
+            return Ok(Some(nonce));
+        }
+    }
+    Ok(None)
+}
+
+// Evidence columns naming a content-addressed blob: scrubbed copy, preserved original
+const BLOB_HASH_COLUMNS: [&str; 2] = ["sha256_hash", "original_sha256_hash"];
+
+fn used_by_live_evidence(conn: &rusqlite::Connection, column: &str, hash: &str) -> Result<bool, String> {
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM evidence WHERE {} = ?1)", column),
+        params![hash],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+// A restored exhibit keeps its number unless another item has taken it since
+fn renumber_if_taken(conn: &rusqlite::Connection, row: &mut Map<String, Json>) -> Result<(), String> {
+    let Some(number) = row.get("exhibit_number").and_then(|v| v.as_i64()) else {
+        return Ok(());
+    };
+    let taken: bool = conn
+        .query_row("SELECT EXISTS(SELECT 1 FROM evidence WHERE exhibit_number = ?1)", params![number], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if taken {
+        let next = db::next_exhibit_number(conn).map_err(|e| e.to_string())?;
+        row.insert("exhibit_number".to_string(), Json::from(next));
+    }
+    Ok(())
+}
+
+fn load_payload(db: &HuntDatabase, key: &SessionKey, kind: &str, id: i64) -> Result<TrashedRows, String> {
+    let (ciphertext, nonce): (Vec<u8>, Vec<u8>) = db.conn
+        .query_row(
+            "SELECT ciphertext, nonce FROM trash WHERE kind = ?1 AND ref_id = ?2",
+            params![kind, id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| "Item not found in trash".to_string())?;
+    let json = crypto::decrypt_data(&ciphertext, &nonce, key)?;
+    serde_json::from_slice(&json).map_err(|e| e.to_string())
+}
+
+// Reads and deletes all rows of `table` where `column` = id
+fn take_rows(conn: &rusqlite::Connection, table: &str, column: &str, id: i64) -> Result<Vec<Map<String, Json>>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {} WHERE {} = ?1", table, column))
+        .map_err(|e| e.to_string())?;
+    let names: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let mut rows = stmt.query(params![id]).map_err(|e| e.to_string())?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let mut map = Map::new();
+        for (i, name) in names.iter().enumerate() {
+            let value = row.get_ref(i).map_err(|e| e.to_string())?;
+            map.insert(name.clone(), value_to_json(value));
+        }
+        out.push(map);
+    }
+
+    conn.execute(&format!("DELETE FROM {} WHERE {} = ?1", table, column), params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+fn value_to_json(value: ValueRef<'_>) -> Json {
+    match value {
+        ValueRef::Null => Json::Null,
+        ValueRef::Integer(i) => Json::from(i),
+        ValueRef::Real(f) => Json::from(f),
+        ValueRef::Text(t) => Json::from(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => serde_json::json!({ "blob": base64::engine::general_purpose::STANDARD.encode(b) }),
+    }
+}
+
+fn json_to_value(json: &Json) -> Result<Value, String> {
+    Ok(match json {
+        Json::Null => Value::Null,
+        Json::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        Json::String(s) => Value::Text(s.clone()),
+        Json::Object(o) => {
+            let encoded = o.get("blob").and_then(|b| b.as_str()).ok_or("Malformed trash payload")?;
+            let bytes = base64::engine::general_purpose::STANDARD.decode(encoded).map_err(|e| e.to_string())?;
+            Value::Blob(bytes)
+        }
+        _ => return Err("Malformed trash payload".to_string()),
+    })
+}
+
+fn label_for(kind: &str, record: &Map<String, Json>) -> String {
+    let field = |name: &str| record.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    match kind {
+        KIND_EVENT => format!("{} - {}", field("event_date"), field("title")),
+        KIND_PARTY => field("name"),
+        _ => match record.get("exhibit_number").and_then(|v| v.as_i64()) {
+            Some(n) => format!("Ex. {} - {}", n, field("file_path")),
+            None => field("file_path"),
+        },
+    }
+}
+
+// --- Whole hunts ---
+
+pub fn hunt_trash_dir(vaults_root: &Path) -> PathBuf {
+    vaults_root.parent().unwrap_or(vaults_root).join("trash")
+}
+
+fn trashed_paths(trash_dir: &Path, hunt_id: &str) -> (PathBuf, PathBuf) {
+    (
+        trash_dir.join(format!("{}.enc", hunt_id)),
+        trash_dir.join(format!("{}.meta.enc", hunt_id)),
+    )
+}
+
+pub fn trash_hunt(
+    hunt_dir: &Path,
+    trash_dir: &Path,
+    hunt_id: &str,
+    name: &str,
+    key: &SessionKey,
+    report: &mut DeletionReport,
+) -> Result<(), String> {
+    fs::create_dir_all(trash_dir).map_err(|e| e.to_string())?;
+    let (archive_path, meta_path) = trashed_paths(trash_dir, hunt_id);
+
+    let archive = bundle::pack_dir(hunt_dir)?;
+    crypto::write_sealed_file(&archive_path, &archive, key)?;
+    let meta = TrashedHunt {
+        hunt_id: hunt_id.to_string(),
+        name: name.to_string(),
+        deleted_at: chrono::Utc::now().to_rfc3339(),
+    };
+    crypto::write_sealed_file(&meta_path, &serde_json::to_vec(&meta).map_err(|e| e.to_string())?, key)?;
+
+    // The hunt folder holds plaintext records, so it is shredded rather than just removed
+    shred::shred_dir(hunt_dir, report)
+}
+
+pub fn list_hunts(trash_dir: &Path, key: &SessionKey) -> Result<Vec<TrashedHunt>, String> {
+    let mut hunts = Vec::new();
+    let Ok(entries) = fs::read_dir(trash_dir) else {
+        return Ok(hunts);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.to_string_lossy().ends_with(".meta.enc") {
+            continue;
+        }
+        let meta = crypto::read_sealed_file(&path, key)?;
+        hunts.push(serde_json::from_slice(&meta).map_err(|e| e.to_string())?);
+    }
+    hunts.sort_by(|a: &TrashedHunt, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(hunts)
+}
+
+pub fn restore_hunt(trash_dir: &Path, vaults_root: &Path, hunt_id: &str, key: &SessionKey) -> Result<(), String> {
+    let (archive_path, meta_path) = trashed_paths(trash_dir, hunt_id);
+    if !archive_path.exists() {
+        return Err("Hunt not found in trash".to_string());
+    }
+    let target = vaults_root.join(hunt_id);
+    if target.exists() {
+        return Err(format!("Hunt '{}' already exists in vault.", hunt_id));
+    }
+
+    let archive = crypto::read_sealed_file(&archive_path, key)?;
+    bundle::unpack_dir(&archive, &target)?;
+    fs::remove_file(&archive_path).map_err(|e| e.to_string())?;
+    fs::remove_file(&meta_path).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn empty_hunts(trash_dir: &Path, report: &mut DeletionReport) -> Result<(), String> {
+    shred::shred_dir(trash_dir, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_and_restore_event_with_links() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([3u8; 32]);
+        db.conn.execute(
+            "INSERT INTO events (title, description, event_date, event_type) VALUES ('Invoice sent', 'x', '2024-01-02', 'billing')",
+            [],
+        ).unwrap();
+        let event_id = db.conn.last_insert_rowid();
+        db.conn.execute("INSERT INTO parties (id, name, role) VALUES (9, 'Jane Roe', 'Witness')", []).unwrap();
+        assert!(db.link_event_party(event_id, 9).unwrap());
+
+        trash_record(&db, &key, KIND_EVENT, event_id).unwrap();
+        let count = |sql: &str| -> i64 { db.conn.query_row(sql, [], |r| r.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM events"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM event_parties"), 0);
+
+        let listed = list_records(&db, &key).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].label, "2024-01-02 - Invoice sent");
+
+        restore_record(&db, &key, KIND_EVENT, event_id).unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM events WHERE title = 'Invoice sent'"), 1);
+        assert_eq!(count("SELECT COUNT(*) FROM event_parties WHERE party_id = 9"), 1);
+        assert_eq!(count("SELECT COUNT(*) FROM trash"), 0);
+    }
+
+    #[test]
+    fn test_trashing_evidence_or_party_takes_its_links() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([3u8; 32]);
+        db.conn.execute(
+            "INSERT INTO events (title, description, event_date, event_type) VALUES ('Invoice sent', 'x', '2024-01-02', 'billing')",
+            [],
+        ).unwrap();
+        let event = db.conn.last_insert_rowid();
+        let evidence = db.insert_evidence("a", "a.pdf", b"n", "h1").unwrap();
+        db.conn.execute("INSERT INTO parties (name, role) VALUES ('Jane Roe', 'Witness')", []).unwrap();
+        let party = db.conn.last_insert_rowid();
+        db.link_event_evidence(event, evidence).unwrap();
+        db.link_event_party(event, party).unwrap();
+
+        trash_record(&db, &key, KIND_EVIDENCE, evidence).unwrap();
+        trash_record(&db, &key, KIND_PARTY, party).unwrap();
+        assert!(db.event_evidence_links().unwrap().is_empty());
+        assert!(db.event_party_links().unwrap().is_empty());
+
+        restore_record(&db, &key, KIND_EVIDENCE, evidence).unwrap();
+        restore_record(&db, &key, KIND_PARTY, party).unwrap();
+        assert_eq!(db.event_evidence_links().unwrap()[&event], [evidence]);
+        assert_eq!(db.event_party_links().unwrap()[&event], [party]);
+
+        // Emptying the trash after a delete leaves no link behind
+        trash_record(&db, &key, KIND_EVIDENCE, evidence).unwrap();
+        let hunt_dir = std::env::temp_dir().join(format!("os-trash-{}", uuid::Uuid::new_v4()));
+        empty_records(&hunt_dir, &db, &key, &mut DeletionReport::default()).unwrap();
+        assert!(db.event_evidence_links().unwrap().is_empty());
+        assert_eq!(db.event_party_links().unwrap()[&event], [party]);
+    }
+
+    #[test]
+    fn test_restored_exhibit_gets_new_number_if_taken() {
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([3u8; 32]);
+        let number = |id: i64| -> i64 {
+            db.conn.query_row("SELECT exhibit_number FROM evidence WHERE id = ?1", params![id], |r| r.get(0)).unwrap()
+        };
+        let a = db.insert_evidence("a", "a.pdf", b"n", "h1").unwrap();
+        let b = db.insert_evidence("b", "b.pdf", b"n", "h2").unwrap();
+
+        trash_record(&db, &key, KIND_EVIDENCE, b).unwrap();
+        let c = db.insert_evidence("c", "c.pdf", b"n", "h3").unwrap();
+        restore_record(&db, &key, KIND_EVIDENCE, b).unwrap();
+        assert_eq!((number(a), number(b), number(c)), (1, 2, 3));
+
+        // Ex. 1 is reused by a reorder while `a` is in the trash
+        trash_record(&db, &key, KIND_EVIDENCE, a).unwrap();
+        db.reorder_exhibits(&[c]).unwrap();
+        restore_record(&db, &key, KIND_EVIDENCE, a).unwrap();
+        assert_eq!((number(c), number(b), number(a)), (1, 2, 4));
+    }
+
+    #[test]
+    fn test_emptying_trash_keeps_blobs_shared_with_other_rows() {
+        let hunt_dir = std::env::temp_dir().join(format!("os-trash-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(hunt_dir.join("evidence")).unwrap();
+        let db = HuntDatabase::open(":memory:").unwrap();
+        let key = SessionKey([3u8; 32]);
+        let add = || {
+            let id = db.insert_evidence("memo", "memo.txt", b"n", "h1").unwrap();
+            db.set_evidence_original(id, "o1", b"n").unwrap();
+            id
+        };
+        let blobs = [evidence::scrubbed_path(&hunt_dir, "h1"), evidence::original_path(&hunt_dir, "o1")];
+        for blob in &blobs {
+            fs::write(blob, b"ciphertext").unwrap();
+        }
+        let (live, trashed) = (add(), add());
+
+        trash_record(&db, &key, KIND_EVIDENCE, trashed).unwrap();
+        empty_records(&hunt_dir, &db, &key, &mut DeletionReport::default()).unwrap();
+        assert!(blobs.iter().all(|b| b.exists()));
+
+        // Once the last row using them is emptied too, the blobs go
+        let other = add();
+        trash_record(&db, &key, KIND_EVIDENCE, live).unwrap();
+        trash_record(&db, &key, KIND_EVIDENCE, other).unwrap();
+        empty_records(&hunt_dir, &db, &key, &mut DeletionReport::default()).unwrap();
+        assert!(blobs.iter().all(|b| !b.exists()));
+        fs::remove_dir_all(&hunt_dir).unwrap();
+    }
+}
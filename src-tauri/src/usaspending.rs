@@ -1,8 +1,11 @@
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::cache::{CacheEntry, ResponseCache};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AwardSummary {
     pub generated_internal_id: String,
     pub date_signed: String,
@@ -10,13 +13,45 @@ pub struct AwardSummary {
     pub total_obligation: f64,
     pub awarding_agency: String,
     pub recipient_name: String,
+    // The API's result object as returned, kept when an award is attached to a hunt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<serde_json::Value>,
+    // Data sources that reported the award, filled in when results are merged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
 }
 
 #[derive(Deserialize, Debug)]
 struct ApiResponse {
-    results: Vec<ApiResult>,
+    results: Vec<serde_json::Value>,
+    #[serde(default)]
+    page_metadata: PageMetadata,
 }
 
+#[derive(Deserialize, Debug, Default)]
+struct PageMetadata {
+    #[serde(rename = "hasNext", default)]
+    has_next: bool,
+}
+
+// One page of results, emitted to the frontend while a search is still running
+#[derive(Serialize, Clone, Debug)]
+pub struct AwardPage {
+    pub group: String, // AwardTypeGroup, e.g. "contracts" or "grants"
+    pub page: u32,
+    pub awards: Vec<AwardSummary>,
+    pub fetched: usize, // awards fetched so far across all groups
+    pub has_next: bool,
+}
+
+pub const API_BASE_URL: &str = "https://api.usaspending.gov";
+// Id of this source in `sources` and in cached search requests
+pub const SOURCE_ID: &str = "usaspending";
+// Per award group; contractors with thousands of awards can take minutes to page through
+pub const DEFAULT_MAX_RESULTS: usize = 500;
+// Largest page the search endpoint accepts
+const PAGE_SIZE: usize = 100;
+
 #[derive(Deserialize, Debug)]
 struct ApiResult {
     #[serde(rename = "generated_internal_id", default)]
@@ -47,104 +82,1258 @@ struct ApiResult {
     recipient_name: String,
 }
 
-pub fn check_target(target_name: &str) -> Result<Vec<AwardSummary>, String> {
-    let client = Client::new();
-    let base_url = "https://api.usaspending.gov/api/v2/search/spending_by_award/";
+// Award type groups as USAspending defines them; one search request may only
+// ask for codes from a single group
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AwardTypeGroup {
+    Contracts,
+    Idvs,
+    Grants,
+    DirectPayments,
+    Loans,
+    Other,
+}
 
-    // Group 1: Contracts
-    // Codes: A, B, C, D
-    let contracts_payload = make_payload(target_name, vec!["A", "B", "C", "D"], vec![
-        "Award ID", "Recipient Name", "Total Obligation", "Awarding Agency", "Description", "Action Date"
-    ]);
+impl AwardTypeGroup {
+    // Searched when the filters name no groups
+    pub const DEFAULT: [AwardTypeGroup; 5] = [
+        AwardTypeGroup::Contracts,
+        AwardTypeGroup::Grants,
+        AwardTypeGroup::DirectPayments,
+        AwardTypeGroup::Loans,
+        AwardTypeGroup::Other,
+    ];
 
-    // Group 2: Assistance (Loans, Grants, Direct Payments)
-    // Codes: 02, 03, 04, 05 (Grants), 06, 10 (Direct Payments), 07, 08 (Loans), 09, 11 (Insurance/Other)
-    let assistance_payload = make_payload(target_name, vec![
-        "02", "03", "04", "05", "06", "07", "08", "09", "10", "11"
-    ], vec![
-        "Award ID", "Recipient Name", "Total Obligation", "Face Value of Loan", "Original Loan Subsidy Cost", 
-        "Awarding Agency", "Description", "Action Date"
-    ]);
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AwardTypeGroup::Contracts => "contracts",
+            AwardTypeGroup::Idvs => "idvs",
+            AwardTypeGroup::Grants => "grants",
+            AwardTypeGroup::DirectPayments => "direct_payments",
+            AwardTypeGroup::Loans => "loans",
+            AwardTypeGroup::Other => "other",
+        }
+    }
 
-    let mut all_results = Vec::new();
+    fn codes(&self) -> &'static [&'static str] {
+        match self {
+            AwardTypeGroup::Contracts => &["A", "B", "C", "D"],
+            AwardTypeGroup::Idvs => &["IDV_A", "IDV_B", "IDV_B_A", "IDV_B_B", "IDV_B_C", "IDV_C", "IDV_D", "IDV_E"],
+            AwardTypeGroup::Grants => &["02", "03", "04", "05"],
+            AwardTypeGroup::DirectPayments => &["06", "10"],
+            AwardTypeGroup::Loans => &["07", "08"],
+            AwardTypeGroup::Other => &["09", "11"],
+        }
+    }
 
-    // Fetch Contracts
-    if let Ok(results) = fetch_group(&client, base_url, &contracts_payload) {
-        all_results.extend(results);
+    fn fields(&self) -> &'static [&'static str] {
+        match self {
+            AwardTypeGroup::Contracts | AwardTypeGroup::Idvs => &[
+                "Award ID", "Recipient Name", "Total Obligation", "Awarding Agency", "Description", "Action Date"
+            ],
+            _ => &[
+                "Award ID", "Recipient Name", "Total Obligation", "Face Value of Loan", "Original Loan Subsidy Cost",
+                "Awarding Agency", "Description", "Action Date"
+            ],
+        }
     }
+}
+
+// Narrows a target search; unset fields are left out of the request
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SearchFilters {
+    pub start_date: Option<String>, // YYYY-MM-DD, defaults to DEFAULT_START_DATE
+    pub end_date: Option<String>,   // YYYY-MM-DD, defaults to today
+    pub award_type_groups: Vec<AwardTypeGroup>,
+    pub awarding_agency: Option<String>, // top-tier agency name
+    pub funding_agency: Option<String>,
+    pub place_of_performance_state: Option<String>, // two-letter code
+    pub naics_codes: Vec<String>,
+    pub psc_codes: Vec<String>,
+    pub recipient_uei: Option<String>,
+    pub recipient_duns: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+}
+
+// Earliest date the award search endpoint accepts
+pub const DEFAULT_START_DATE: &str = "2007-10-01";
 
-    // Fetch Assistance
-    if let Ok(results) = fetch_group(&client, base_url, &assistance_payload) {
-        all_results.extend(results);
+impl SearchFilters {
+    fn groups(&self) -> Vec<AwardTypeGroup> {
+        if self.award_type_groups.is_empty() {
+            AwardTypeGroup::DEFAULT.to_vec()
+        } else {
+            self.award_type_groups.clone()
+        }
     }
-    
-    // Map to Summary
-    let summaries = all_results.into_iter().map(|r| {
-        // Determine value priority: Face Value -> Total Obligation -> Subsidy -> 0.0
-        let value = r.face_value_loan
-            .or(r.total_obligation)
-            .or(r.original_loan_subsidy_cost)
-            .unwrap_or(0.0);
 
-        AwardSummary {
-            generated_internal_id: r.generated_internal_id,
-            date_signed: r.date_signed,
-            description: Some(r.description),
-            total_obligation: value,
-            awarding_agency: r.awarding_agency,
-            recipient_name: r.recipient_name,
+    // Maps to the `filters` object of the USAspending search API, minus
+    // `award_type_codes`, which is set per group
+    pub fn to_api_filters(&self, keyword: &str) -> Result<serde_json::Value, String> {
+        let clean = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+        let parse_date = |d: &str| {
+            chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", d))
+        };
+
+        let start = clean(&self.start_date).unwrap_or_else(|| DEFAULT_START_DATE.to_string());
+        let end = clean(&self.end_date).unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string());
+        if parse_date(&start)? > parse_date(&end)? {
+            return Err("Start date is after end date".to_string());
+        }
+
+        let mut filters = json!({
+            "time_period": [{ "start_date": start, "end_date": end }]
+        });
+        let keyword = keyword.trim();
+        if !keyword.is_empty() {
+            filters["keywords"] = json!([keyword]);
+        }
+
+        let mut agencies = Vec::new();
+        if let Some(name) = clean(&self.awarding_agency) {
+            agencies.push(json!({ "type": "awarding", "tier": "toptier", "name": name }));
+        }
+        if let Some(name) = clean(&self.funding_agency) {
+            agencies.push(json!({ "type": "funding", "tier": "toptier", "name": name }));
+        }
+        if !agencies.is_empty() {
+            filters["agencies"] = json!(agencies);
+        }
+
+        if let Some(state) = clean(&self.place_of_performance_state) {
+            if state.len() != 2 || !state.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!("Invalid state code '{}'", state));
+            }
+            filters["place_of_performance_locations"] = json!([{ "country": "USA", "state": state.to_uppercase() }]);
+        }
+
+        let codes = |list: &[String]| -> Vec<String> {
+            list.iter().map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect()
+        };
+        let naics = codes(&self.naics_codes);
+        if !naics.is_empty() {
+            filters["naics_codes"] = json!({ "require": naics });
+        }
+        let psc = codes(&self.psc_codes);
+        if !psc.is_empty() {
+            filters["psc_codes"] = json!(psc);
+        }
+
+        // The recipient search matches names, UEIs and DUNS numbers alike
+        let recipients: Vec<String> = [clean(&self.recipient_uei), clean(&self.recipient_duns)].into_iter().flatten().collect();
+        if !recipients.is_empty() {
+            filters["recipient_search_text"] = json!(recipients);
+        }
+        if keyword.is_empty() && recipients.is_empty() {
+            return Err("A target name, UEI or DUNS is required".to_string());
+        }
+
+        if self.min_amount.is_some() || self.max_amount.is_some() {
+            if let (Some(min), Some(max)) = (self.min_amount, self.max_amount) {
+                if min > max {
+                    return Err("Minimum amount is greater than maximum amount".to_string());
+                }
+            }
+            let mut range = json!({});
+            if let Some(min) = self.min_amount {
+                range["lower_bound"] = json!(min);
+            }
+            if let Some(max) = self.max_amount {
+                range["upper_bound"] = json!(max);
+            }
+            filters["award_amounts"] = json!([range]);
+        }
+
+        Ok(filters)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UsaSpendingError {
+    // Connection refused, DNS, TLS, timeout, ...
+    Transport { message: String },
+    // Non-success response; `body` is the start of what the API sent back
+    Http { status: u16, body: String },
+    RateLimited { retry_after_secs: Option<u64> },
+    // The response was not in the shape we expect
+    Parse { message: String },
+    // A lookup matched nothing
+    NotFound { message: String },
+}
+
+impl UsaSpendingError {
+    fn is_transient(&self) -> bool {
+        match self {
+            UsaSpendingError::Transport { .. } | UsaSpendingError::RateLimited { .. } => true,
+            UsaSpendingError::Http { status, .. } => *status >= 500,
+            UsaSpendingError::Parse { .. } | UsaSpendingError::NotFound { .. } => false,
+        }
+    }
+}
+
+impl std::fmt::Display for UsaSpendingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UsaSpendingError::Transport { message } => write!(f, "Could not reach USAspending: {}", message),
+            UsaSpendingError::Http { status, body } => write!(f, "USAspending returned HTTP {}: {}", status, body),
+            UsaSpendingError::RateLimited { retry_after_secs: Some(secs) } => {
+                write!(f, "USAspending rate limit hit; retry after {}s", secs)
+            }
+            UsaSpendingError::RateLimited { retry_after_secs: None } => write!(f, "USAspending rate limit hit"),
+            UsaSpendingError::Parse { message } => write!(f, "Unexpected USAspending response: {}", message),
+            UsaSpendingError::NotFound { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for UsaSpendingError {}
+
+// An award group whose results are incomplete because a page failed
+#[derive(Serialize, Debug, Clone)]
+pub struct GroupFailure {
+    pub group: String,
+    pub page: u32,
+    pub error: UsaSpendingError,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TargetSearch {
+    pub awards: Vec<AwardSummary>,
+    // Non-empty means the award list is partial
+    pub failures: Vec<GroupFailure>,
+    // When these awards were fetched from the API
+    pub fetched_at: Option<String>,
+    // Served from the offline cache rather than the live API
+    pub from_cache: bool,
+    // The previous complete result for the same search, if any, and the
+    // awards that are new since then
+    pub previous_fetched_at: Option<String>,
+    pub new_award_ids: Vec<String>,
+}
+
+// What the offline cache keeps for a search
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CachedSearch {
+    pub target_name: String,
+    pub filters: SearchFilters,
+    pub max_results: usize,
+    pub awards: Vec<AwardSummary>,
+}
+
+// A cached search as listed for offline review
+#[derive(Serialize, Clone, Debug)]
+pub struct CachedSearchInfo {
+    pub cache_key: String,
+    pub source: String,
+    pub target_name: String,
+    pub filters: SearchFilters,
+    pub fetched_at: String,
+    pub award_count: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    // Doubled after each failed attempt
+    pub base_delay: Duration,
+    // Longest we will honor a Retry-After header
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 4, base_delay: Duration::from_millis(500), max_delay: Duration::from_secs(30) }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ApiConfig {
+    pub base_url: String,
+    pub retry: RetryPolicy,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self { base_url: API_BASE_URL.to_string(), retry: RetryPolicy::default() }
+    }
+}
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const ERROR_BODY_LIMIT: usize = 2000;
+
+pub fn check_target(
+    target_name: &str,
+    filters: &SearchFilters,
+    max_results: usize,
+    on_page: impl FnMut(&AwardPage),
+) -> Result<TargetSearch, String> {
+    check_target_at(&ApiConfig::default(), target_name, filters, max_results, on_page)
+}
+
+// Pages through each award group until `hasNext` is false or `max_results`
+// awards have been read from the group. A group that fails is reported in
+// `failures` and the others still run; if every group fails before returning
+// anything, the first error is returned instead.
+pub fn check_target_at(
+    config: &ApiConfig,
+    target_name: &str,
+    filters: &SearchFilters,
+    max_results: usize,
+    mut on_page: impl FnMut(&AwardPage),
+) -> Result<TargetSearch, String> {
+    let client = client().map_err(|e| e.to_string())?;
+    let url = api_url(config, "search/spending_by_award/");
+    let api_filters = filters.to_api_filters(target_name)?;
+
+    let groups = filters.groups();
+    let mut search = TargetSearch { fetched_at: Some(crate::db::now_timestamp()), ..Default::default() };
+    for group in &groups {
+        let mut group_count = 0;
+        let mut page = 1;
+        while group_count < max_results {
+            let payload = make_payload(&api_filters, *group, page);
+            let (results, has_next) = match fetch_page(&client, &url, &payload, &config.retry) {
+                Ok(r) => r,
+                Err(error) => {
+                    eprintln!("WARNING: USAspending {} page {} failed: {}", group.as_str(), page, error);
+                    search.failures.push(GroupFailure { group: group.as_str().to_string(), page, error });
+                    break;
+                }
+            };
+
+            let take = results.len().min(max_results - group_count);
+            let awards: Vec<AwardSummary> = results.into_iter().take(take).map(to_summary).collect();
+            group_count += awards.len();
+            let has_next = has_next && group_count < max_results && !awards.is_empty();
+
+            on_page(&AwardPage {
+                group: group.as_str().to_string(),
+                page,
+                fetched: search.awards.len() + awards.len(),
+                awards: awards.clone(),
+                has_next,
+            });
+            search.awards.extend(awards);
+
+            if !has_next {
+                break;
+            }
+            page += 1;
+        }
+    }
+
+    if search.awards.is_empty() && search.failures.len() == groups.len() {
+        return Err(search.failures[0].error.to_string());
+    }
+    println!(
+        "Fetched {} awards for '{}' ({} group(s) failed)",
+        search.awards.len(),
+        target_name,
+        search.failures.len()
+    );
+    Ok(search)
+}
+
+fn search_request(target_name: &str, filters: &SearchFilters, max_results: usize) -> serde_json::Value {
+    // Award search is case-insensitive, so the name is folded for the cache key.
+    // The source id keeps other sources' cached searches apart from these.
+    json!({
+        "kind": "award_search",
+        "source": SOURCE_ID,
+        "target_name": target_name.trim().to_lowercase(),
+        "filters": filters,
+        "max_results": max_results,
+    })
+}
+
+// `check_target` backed by the offline cache. Live results are compared with
+// the last complete result for the same search to fill `new_award_ids`, and
+// replace it when complete. With `offline`, or when the API can't be reached
+// at all, the cached result is returned instead.
+pub fn check_target_cached(
+    config: &ApiConfig,
+    cache: &ResponseCache,
+    target_name: &str,
+    filters: &SearchFilters,
+    max_results: usize,
+    offline: bool,
+    on_page: impl FnMut(&AwardPage),
+) -> Result<TargetSearch, String> {
+    let request = search_request(target_name, filters, max_results);
+    let previous: Option<CacheEntry<CachedSearch>> = cache.get(&request);
+    let from_cache = |entry: CacheEntry<CachedSearch>| TargetSearch {
+        awards: entry.response.awards,
+        fetched_at: Some(entry.fetched_at),
+        from_cache: true,
+        ..Default::default()
+    };
+
+    if offline {
+        return previous.map(from_cache).ok_or_else(|| "This search has not been run before; no offline copy".to_string());
+    }
+
+    let mut search = match check_target_at(config, target_name, filters, max_results, on_page) {
+        Ok(search) => search,
+        Err(e) => {
+            return match previous {
+                Some(entry) => {
+                    eprintln!("WARNING: USAspending unavailable ({}); using cached results", e);
+                    Ok(from_cache(entry))
+                }
+                None => Err(e),
+            };
         }
-    }).collect::<Vec<_>>(); // Collect to vec to allow printing
+    };
+
+    if let Some(previous) = &previous {
+        let known: std::collections::HashSet<&str> =
+            previous.response.awards.iter().map(|a| a.generated_internal_id.as_str()).collect();
+        search.new_award_ids = search.awards
+            .iter()
+            .map(|a| a.generated_internal_id.clone())
+            .filter(|id| !known.contains(id.as_str()))
+            .collect();
+        search.previous_fetched_at = Some(previous.fetched_at.clone());
+    }
 
-    println!("Final merged results for '{}': {:?}", target_name, summaries);
+    // A partial result would make the next diff report old awards as new
+    if search.failures.is_empty() {
+        let cached = CachedSearch {
+            target_name: target_name.trim().to_string(),
+            filters: filters.clone(),
+            max_results,
+            awards: search.awards.clone(),
+        };
+        let fetched_at = search.fetched_at.clone().unwrap_or_else(crate::db::now_timestamp);
+        if let Err(e) = cache.put(&request, &fetched_at, &cached) {
+            eprintln!("WARNING: could not cache search results: {}", e);
+        }
+    }
+    Ok(search)
+}
 
-    Ok(summaries)
+pub fn cached_searches(cache: &ResponseCache) -> Vec<CachedSearchInfo> {
+    cache
+        .entries::<CachedSearch>()
+        .into_iter()
+        .map(|(cache_key, entry)| CachedSearchInfo {
+            cache_key,
+            source: entry.request["source"].as_str().unwrap_or(SOURCE_ID).to_string(),
+            target_name: entry.response.target_name,
+            filters: entry.response.filters,
+            fetched_at: entry.fetched_at,
+            award_count: entry.response.awards.len(),
+        })
+        .collect()
+}
+
+pub fn cached_search(cache: &ResponseCache, cache_key: &str) -> Option<TargetSearch> {
+    let entry: CacheEntry<CachedSearch> = cache.get_by_key(cache_key)?;
+    Some(TargetSearch {
+        awards: entry.response.awards,
+        fetched_at: Some(entry.fetched_at),
+        from_cache: true,
+        ..Default::default()
+    })
+}
+
+fn to_summary((r, raw): (ApiResult, serde_json::Value)) -> AwardSummary {
+    // Determine value priority: Face Value -> Total Obligation -> Subsidy -> 0.0
+    let value = r.face_value_loan
+        .or(r.total_obligation)
+        .or(r.original_loan_subsidy_cost)
+        .unwrap_or(0.0);
+
+    AwardSummary {
+        generated_internal_id: r.generated_internal_id,
+        date_signed: r.date_signed,
+        description: Some(r.description),
+        total_obligation: value,
+        awarding_agency: r.awarding_agency,
+        recipient_name: r.recipient_name,
+        raw: Some(raw),
+        sources: Vec::new(),
+    }
 }
 
-fn make_payload(keyword: &str, codes: Vec<&str>, fields: Vec<&str>) -> serde_json::Value {
+fn make_payload(filters: &serde_json::Value, group: AwardTypeGroup, page: u32) -> serde_json::Value {
+    let mut filters = filters.clone();
+    filters["award_type_codes"] = json!(group.codes());
     json!({
-        "filters": {
-            "keywords": [keyword],
-            "time_period": [
-                {"start_date": "2010-01-01", "end_date": "2025-12-31"}
-            ],
-            "award_type_codes": codes
-        },
-        "fields": fields,
-        "limit": 10,
-        "page": 1
+        "filters": filters,
+        "fields": group.fields(),
+        "limit": PAGE_SIZE,
+        "page": page
     })
 }
 
-fn fetch_group(client: &Client, url: &str, payload: &serde_json::Value) -> Result<Vec<ApiResult>, String> {
-    let res = client.post(url)
-        .json(payload)
+// Sends the request built by `build`, retrying transient failures, and
+// returns the parsed JSON body
+fn request_json(
+    retry: &RetryPolicy,
+    build: impl Fn() -> reqwest::blocking::RequestBuilder,
+) -> Result<serde_json::Value, UsaSpendingError> {
+    let mut attempt = 1;
+    loop {
+        match send_once(build()) {
+            Err(e) if e.is_transient() && attempt < retry.max_attempts => {
+                let backoff = retry.base_delay * 2u32.pow(attempt - 1);
+                let delay = match &e {
+                    UsaSpendingError::RateLimited { retry_after_secs: Some(secs) } => Duration::from_secs(*secs),
+                    _ => backoff,
+                };
+                std::thread::sleep(delay.min(retry.max_delay));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn send_once(request: reqwest::blocking::RequestBuilder) -> Result<serde_json::Value, UsaSpendingError> {
+    let res = request
         .send()
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .map_err(|e| UsaSpendingError::Transport { message: e.to_string() })?;
 
-    if !res.status().is_success() {
-        return Err(format!("API Error: {}", res.status()));
+    let status = res.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after_secs = res.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok());
+        return Err(UsaSpendingError::RateLimited { retry_after_secs });
+    }
+    if !status.is_success() {
+        let body: String = res.text().unwrap_or_default().chars().take(ERROR_BODY_LIMIT).collect();
+        return Err(UsaSpendingError::Http { status: status.as_u16(), body });
     }
 
-    let text_response = res.text().map_err(|e| format!("Read error: {}", e))?;
-    
-    let api_response: ApiResponse = serde_json::from_str(&text_response)
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let text_response = res.text().map_err(|e| UsaSpendingError::Transport { message: e.to_string() })?;
+    serde_json::from_str(&text_response).map_err(|e| UsaSpendingError::Parse { message: e.to_string() })
+}
 
-    Ok(api_response.results)
+fn parse<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> Result<T, UsaSpendingError> {
+    serde_json::from_value(value).map_err(|e| UsaSpendingError::Parse { message: e.to_string() })
+}
+
+type PageResults = (Vec<(ApiResult, serde_json::Value)>, bool);
+
+fn fetch_page(
+    client: &Client,
+    url: &str,
+    payload: &serde_json::Value,
+    retry: &RetryPolicy,
+) -> Result<PageResults, UsaSpendingError> {
+    let api_response: ApiResponse = parse(request_json(retry, || client.post(url).json(payload))?)?;
+
+    let results = api_response.results
+        .into_iter()
+        .map(|raw| {
+            let result = ApiResult::deserialize(&raw).map_err(|e| UsaSpendingError::Parse { message: e.to_string() })?;
+            Ok((result, raw))
+        })
+        .collect::<Result<Vec<_>, UsaSpendingError>>()?;
+    Ok((results, api_response.page_metadata.has_next))
+}
+
+// --- Recipient resolution ---
+//
+// Keyword award search matches descriptions as well as recipients, so a name
+// can pull in unrelated entities. These calls resolve a name to a specific
+// recipient (UEI/DUNS, parent and children, alternate names) that the user
+// confirms before pulling awards by UEI.
+
+// "P" parent, "C" child, "R" recipient with no parent in USAspending's data
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecipientCandidate {
+    pub name: String,
+    pub uei: Option<String>,
+    pub duns: Option<String>,
+    pub level: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RelatedRecipient {
+    pub recipient_id: Option<String>,
+    pub name: Option<String>,
+    pub uei: Option<String>,
+    pub duns: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecipientProfile {
+    pub recipient_id: String,
+    pub name: String,
+    pub uei: Option<String>,
+    pub duns: Option<String>,
+    pub level: Option<String>,
+    pub alternate_names: Vec<String>,
+    pub parents: Vec<RelatedRecipient>,
+    pub children: Vec<RelatedRecipient>,
+    pub business_types: Vec<String>,
+    pub state: Option<String>,
+    pub total_transaction_amount: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct AutocompleteResponse {
+    #[serde(default)]
+    results: Vec<AutocompleteResult>,
+}
+
+#[derive(Deserialize)]
+struct AutocompleteResult {
+    #[serde(default)]
+    recipient_name: String,
+    uei: Option<String>,
+    duns: Option<String>,
+    recipient_level: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RecipientListResponse {
+    #[serde(default)]
+    results: Vec<RecipientListResult>,
+}
+
+#[derive(Deserialize)]
+struct RecipientListResult {
+    id: String,
+    uei: Option<String>,
+    duns: Option<String>,
+    recipient_level: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProfileResponse {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    alternate_names: Vec<String>,
+    uei: Option<String>,
+    duns: Option<String>,
+    recipient_id: Option<String>,
+    recipient_level: Option<String>,
+    #[serde(default)]
+    parents: Vec<ParentResult>,
+    #[serde(default)]
+    business_types: Vec<String>,
+    #[serde(default)]
+    location: Option<LocationResult>,
+    total_transaction_amount: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct ParentResult {
+    parent_id: Option<String>,
+    parent_name: Option<String>,
+    parent_uei: Option<String>,
+    parent_duns: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LocationResult {
+    state_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChildResult {
+    recipient_id: Option<String>,
+    name: Option<String>,
+    uei: Option<String>,
+    duns: Option<String>,
+}
+
+fn api_url(config: &ApiConfig, path: &str) -> String {
+    format!("{}/api/v2/{}", config.base_url.trim_end_matches('/'), path)
+}
+
+fn client() -> Result<Client, UsaSpendingError> {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| UsaSpendingError::Transport { message: e.to_string() })
+}
+
+pub fn autocomplete_recipients(config: &ApiConfig, text: &str, limit: usize) -> Result<Vec<RecipientCandidate>, UsaSpendingError> {
+    let client = client()?;
+    let url = api_url(config, "autocomplete/recipient/");
+    let payload = json!({ "search_text": text.trim(), "limit": limit });
+    let response: AutocompleteResponse = parse(request_json(&config.retry, || client.post(&url).json(&payload))?)?;
+
+    Ok(response.results
+        .into_iter()
+        .map(|r| RecipientCandidate {
+            name: r.recipient_name,
+            uei: r.uei.filter(|s| !s.is_empty()),
+            duns: r.duns.filter(|s| !s.is_empty()),
+            level: r.recipient_level,
+        })
+        .collect())
+}
+
+// Looks a recipient up by UEI or DUNS. When an identifier has both a parent
+// and a child record, `level` picks one; otherwise the first match is used.
+pub fn recipient_profile(config: &ApiConfig, identifier: &str, level: Option<&str>) -> Result<RecipientProfile, UsaSpendingError> {
+    let client = client()?;
+    let identifier = identifier.trim();
+
+    let list_url = api_url(config, "recipient/");
+    let payload = json!({ "keyword": identifier, "award_type": "all", "limit": 10, "page": 1 });
+    let list: RecipientListResponse = parse(request_json(&config.retry, || client.post(&list_url).json(&payload))?)?;
+    let matches_id = |r: &&RecipientListResult| {
+        r.uei.as_deref() == Some(identifier) || r.duns.as_deref() == Some(identifier)
+    };
+    let found = list.results.iter()
+        .filter(matches_id)
+        .find(|r| level.is_none() || r.recipient_level.as_deref() == level)
+        .or_else(|| list.results.iter().find(matches_id))
+        .ok_or_else(|| UsaSpendingError::NotFound { message: format!("No USAspending recipient with UEI/DUNS {}", identifier) })?;
+
+    let profile_url = api_url(config, &format!("recipient/{}/", found.id));
+    let profile: ProfileResponse = parse(request_json(&config.retry, || client.get(&profile_url))?)?;
+
+    // Only parents have children; a failed children lookup shouldn't lose the profile
+    let mut children = Vec::new();
+    if profile.recipient_level.as_deref() == Some("P") {
+        let children_url = api_url(config, &format!("recipient/children/{}/", identifier));
+        match request_json(&config.retry, || client.get(&children_url)).and_then(parse::<Vec<ChildResult>>) {
+            Ok(list) => {
+                children = list
+                    .into_iter()
+                    .map(|c| RelatedRecipient { recipient_id: c.recipient_id, name: c.name, uei: c.uei, duns: c.duns })
+                    .collect();
+            }
+            Err(e) => eprintln!("WARNING: could not load children of {}: {}", identifier, e),
+        }
+    }
+
+    Ok(RecipientProfile {
+        recipient_id: profile.recipient_id.unwrap_or_else(|| found.id.clone()),
+        name: profile.name.unwrap_or_default(),
+        uei: profile.uei,
+        duns: profile.duns,
+        level: profile.recipient_level,
+        alternate_names: profile.alternate_names,
+        parents: profile.parents
+            .into_iter()
+            .map(|p| RelatedRecipient { recipient_id: p.parent_id, name: p.parent_name, uei: p.parent_uei, duns: p.parent_duns })
+            .collect(),
+        children,
+        business_types: profile.business_types,
+        state: profile.location.and_then(|l| l.state_code),
+        total_transaction_amount: profile.total_transaction_amount,
+    })
+}
+
+// --- Award detail ---
+
+// Transaction and sub-award lists are paged like award search; this caps how
+// many pages are read for one award
+const MAX_HISTORY_PAGES: u32 = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PlaceOfPerformance {
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub zip: Option<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AwardDetail {
+    pub generated_internal_id: String,
+    // PIID for contracts, FAIN or URI for assistance
+    pub award_id: Option<String>,
+    pub category: Option<String>,
+    pub type_description: Option<String>,
+    pub description: Option<String>,
+    pub total_obligation: Option<f64>,
+    pub base_and_all_options_value: Option<f64>,
+    pub period_of_performance_start: Option<String>,
+    pub period_of_performance_end: Option<String>,
+    pub period_of_performance_potential_end: Option<String>,
+    pub place_of_performance: PlaceOfPerformance,
+    pub psc_code: Option<String>,
+    pub psc_description: Option<String>,
+    pub naics_code: Option<String>,
+    pub naics_description: Option<String>,
+    pub extent_competed: Option<String>,
+    pub awarding_agency: Option<String>,
+    pub awarding_office: Option<String>,
+    pub funding_office: Option<String>,
+    pub recipient_name: Option<String>,
+    pub recipient_uei: Option<String>,
+    pub subaward_count: Option<i64>,
+    pub total_subaward_amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AwardTransaction {
+    pub transaction_id: String,
+    pub modification_number: Option<String>,
+    pub action_date: Option<String>,
+    pub action_type: Option<String>,
+    pub action_type_description: Option<String>,
+    pub description: Option<String>,
+    pub federal_action_obligation: Option<f64>,
+    // Set once the modification has been added to the hunt's timeline
+    #[serde(default)]
+    pub event_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Subaward {
+    pub subaward_number: Option<String>,
+    pub action_date: Option<String>,
+    pub amount: Option<f64>,
+    pub recipient_name: Option<String>,
+    pub description: Option<String>,
+}
+
+// An award's detail with its modification history and sub-awards
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AwardRecord {
+    pub detail: AwardDetail,
+    pub transactions: Vec<AwardTransaction>,
+    pub subawards: Vec<Subaward>,
+    pub fetched_at: Option<String>,
+}
+
+fn text_at(v: &serde_json::Value, pointer: &str) -> Option<String> {
+    match v.pointer(pointer)? {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn number_at(v: &serde_json::Value, pointer: &str) -> Option<f64> {
+    v.pointer(pointer).and_then(|n| n.as_f64())
+}
+
+fn detail_from_raw(generated_internal_id: &str, raw: serde_json::Value) -> AwardDetail {
+    AwardDetail {
+        generated_internal_id: text_at(&raw, "/generated_unique_award_id").unwrap_or_else(|| generated_internal_id.to_string()),
+        award_id: text_at(&raw, "/piid").or_else(|| text_at(&raw, "/fain")).or_else(|| text_at(&raw, "/uri")),
+        category: text_at(&raw, "/category"),
+        type_description: text_at(&raw, "/type_description"),
+        description: text_at(&raw, "/description"),
+        total_obligation: number_at(&raw, "/total_obligation"),
+        base_and_all_options_value: number_at(&raw, "/base_and_all_options_value"),
+        period_of_performance_start: text_at(&raw, "/period_of_performance/start_date"),
+        period_of_performance_end: text_at(&raw, "/period_of_performance/end_date"),
+        period_of_performance_potential_end: text_at(&raw, "/period_of_performance/potential_end_date"),
+        place_of_performance: PlaceOfPerformance {
+            city: text_at(&raw, "/place_of_performance/city_name"),
+            state: text_at(&raw, "/place_of_performance/state_code"),
+            zip: text_at(&raw, "/place_of_performance/zip5"),
+            country: text_at(&raw, "/place_of_performance/country_name"),
+        },
+        psc_code: text_at(&raw, "/psc_hierarchy/base_code/code")
+            .or_else(|| text_at(&raw, "/latest_transaction_contract_data/product_or_service_code")),
+        psc_description: text_at(&raw, "/psc_hierarchy/base_code/description"),
+        naics_code: text_at(&raw, "/naics_hierarchy/base_code/code")
+            .or_else(|| text_at(&raw, "/latest_transaction_contract_data/naics")),
+        naics_description: text_at(&raw, "/naics_hierarchy/base_code/description")
+            .or_else(|| text_at(&raw, "/latest_transaction_contract_data/naics_description")),
+        extent_competed: text_at(&raw, "/latest_transaction_contract_data/extent_competed_description"),
+        awarding_agency: text_at(&raw, "/awarding_agency/toptier_agency/name"),
+        awarding_office: text_at(&raw, "/awarding_agency/office_agency_name"),
+        funding_office: text_at(&raw, "/funding_agency/office_agency_name"),
+        recipient_name: text_at(&raw, "/recipient/recipient_name"),
+        recipient_uei: text_at(&raw, "/recipient/recipient_uei"),
+        subaward_count: raw.pointer("/subaward_count").and_then(|n| n.as_i64()),
+        total_subaward_amount: number_at(&raw, "/total_subaward_amount"),
+        raw: Some(raw),
+    }
+}
+
+// Reads every page of a paged list endpoint for one award
+fn fetch_award_list(
+    client: &Client,
+    config: &ApiConfig,
+    path: &str,
+    award_id: &str,
+    sort: &str,
+) -> Result<Vec<serde_json::Value>, UsaSpendingError> {
+    let url = api_url(config, path);
+    let mut all = Vec::new();
+    for page in 1..=MAX_HISTORY_PAGES {
+        let payload = json!({ "award_id": award_id, "page": page, "limit": PAGE_SIZE, "sort": sort, "order": "asc" });
+        let response: ApiResponse = parse(request_json(&config.retry, || client.post(&url).json(&payload))?)?;
+        let done = !response.page_metadata.has_next || response.results.is_empty();
+        all.extend(response.results);
+        if done {
+            break;
+        }
+    }
+    Ok(all)
+}
+
+// When the API gives no transaction id, one is derived from the award id,
+// modification number and action date, so it stays the same when a refetch
+// returns new modifications. Repeats of the same key are numbered in order.
+fn transactions_from_raw(award_id: &str, raw: &[serde_json::Value]) -> Vec<AwardTransaction> {
+    let mut repeats: HashMap<String, usize> = HashMap::new();
+    raw.iter()
+        .map(|t| {
+            let modification_number = text_at(t, "/modification_number");
+            let action_date = text_at(t, "/action_date");
+            let transaction_id = text_at(t, "/id").unwrap_or_else(|| {
+                let key = format!(
+                    "{}#{}#{}",
+                    award_id,
+                    modification_number.as_deref().unwrap_or(""),
+                    action_date.as_deref().unwrap_or("")
+                );
+                let n = repeats.entry(key.clone()).or_insert(0);
+                *n += 1;
+                if *n == 1 { key } else { format!("{}#{}", key, n) }
+            });
+            AwardTransaction {
+                transaction_id,
+                modification_number,
+                action_date,
+                action_type: text_at(t, "/action_type"),
+                action_type_description: text_at(t, "/action_type_description"),
+                description: text_at(t, "/description"),
+                federal_action_obligation: number_at(t, "/federal_action_obligation"),
+                event_id: None,
+            }
+        })
+        .collect()
+}
+
+pub fn fetch_award_record(config: &ApiConfig, generated_internal_id: &str) -> Result<AwardRecord, UsaSpendingError> {
+    let client = client()?;
+    let id = generated_internal_id.trim();
+    let detail_url = api_url(config, &format!("awards/{}/", id));
+    let detail = detail_from_raw(id, request_json(&config.retry, || client.get(&detail_url))?);
+
+    let transactions = transactions_from_raw(id, &fetch_award_list(&client, config, "transactions/", id, "action_date")?);
+
+    let subawards = fetch_award_list(&client, config, "subawards/", id, "subaward_number")?
+        .into_iter()
+        .map(|s| Subaward {
+            subaward_number: text_at(&s, "/subaward_number"),
+            action_date: text_at(&s, "/action_date"),
+            amount: number_at(&s, "/amount"),
+            recipient_name: text_at(&s, "/recipient_name"),
+            description: text_at(&s, "/description"),
+        })
+        .collect();
+
+    Ok(AwardRecord { detail, transactions, subawards, fetched_at: None })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    // Minimal HTTP server standing in for the API. `handler` gets the request
+    // number (from 0), path and JSON body and returns the status and response body.
+    fn spawn_server(handler: impl Fn(usize, &str, &serde_json::Value) -> (u16, String) + Send + 'static) -> ApiConfig {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for (n, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = v.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+
+                let (status, response) = handler(n, &path, &request);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    response.len(),
+                    response
+                );
+            }
+        });
+        ApiConfig {
+            base_url: format!("http://{}", addr),
+            retry: RetryPolicy { max_attempts: 3, base_delay: Duration::from_millis(1), max_delay: Duration::from_millis(10) },
+        }
+    }
+
+    // `contracts` awards spread over pages of the requested size, no assistance awards
+    fn award_page(request: &serde_json::Value, contracts: usize) -> (u16, String) {
+        let page = request["page"].as_u64().unwrap() as usize;
+        let limit = request["limit"].as_u64().unwrap() as usize;
+        let total = if request["filters"]["award_type_codes"][0] == "A" { contracts } else { 0 };
+        let start = ((page - 1) * limit).min(total);
+        let end = (start + limit).min(total);
+        let results: Vec<_> = (start..end)
+            .map(|i| json!({ "generated_internal_id": format!("CONT_AWD_{}", i), "total_obligation": 10.0 }))
+            .collect();
+        (200, json!({ "results": results, "page_metadata": { "page": page, "hasNext": end < total } }).to_string())
+    }
+
+    fn spawn_mock_api(contracts: usize) -> ApiConfig {
+        spawn_server(move |_, _, request| award_page(request, contracts))
+    }
+
+    #[test]
+    fn test_pages_until_has_next_is_false() {
+        let base = spawn_mock_api(250);
+        let mut pages = Vec::new();
+        let awards = check_target_at(&base, "Acme", &SearchFilters::default(), 1000, |p| {
+            pages.push((p.group.clone(), p.page, p.awards.len(), p.has_next))
+        }).unwrap().awards;
+        assert_eq!(awards.len(), 250);
+        assert_eq!(awards[249].generated_internal_id, "CONT_AWD_249");
+        assert_eq!(pages[..3], [
+            ("contracts".to_string(), 1, 100, true),
+            ("contracts".to_string(), 2, 100, true),
+            ("contracts".to_string(), 3, 50, false),
+        ]);
+        // One empty page for each remaining default group
+        assert_eq!(pages.len(), 3 + AwardTypeGroup::DEFAULT.len() - 1);
+        assert_eq!(pages[3], ("grants".to_string(), 1, 0, false));
+    }
+
+    #[test]
+    fn test_stops_at_max_results() {
+        let base = spawn_mock_api(250);
+        let mut calls = 0;
+        let filters = SearchFilters {
+            award_type_groups: vec![AwardTypeGroup::Contracts, AwardTypeGroup::Loans],
+            ..Default::default()
+        };
+        let awards = check_target_at(&base, "Acme", &filters, 120, |_| calls += 1).unwrap().awards;
+        assert_eq!(awards.len(), 120);
+        // Two contract pages plus the (empty) loans page
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_retries_transient_errors_and_reports_failed_groups() {
+        // The first two requests hit a 503, then loans is rejected outright
+        let config = spawn_server(|n, _, request| {
+            if n < 2 {
+                (503, "upstream unavailable".to_string())
+            } else if request["filters"]["award_type_codes"][0] == "07" {
+                (422, r#"{"detail": "bad filter"}"#.to_string())
+            } else {
+                award_page(request, 5)
+            }
+        });
+        let search = check_target_at(&config, "Acme", &SearchFilters::default(), 100, |_| {}).unwrap();
+        assert_eq!(search.awards.len(), 5);
+        assert_eq!(search.failures.len(), 1);
+        assert_eq!(search.failures[0].group, "loans");
+        assert_eq!(
+            search.failures[0].error,
+            UsaSpendingError::Http { status: 422, body: r#"{"detail": "bad filter"}"#.to_string() }
+        );
+
+        // Nothing but errors is an error, not an empty result
+        let down = spawn_server(|_, _, _| (500, String::new()));
+        assert!(check_target_at(&down, "Acme", &SearchFilters::default(), 100, |_| {}).is_err());
+        let garbled = spawn_server(|_, _, _| (200, "<html>".to_string()));
+        assert!(check_target_at(&garbled, "Acme", &SearchFilters::default(), 100, |_| {})
+            .unwrap_err()
+            .starts_with("Unexpected USAspending response"));
+    }
+
+    #[test]
+    fn test_resolves_recipient_profile_with_children() {
+        let config = spawn_server(|_, path, request| {
+            let body = match path {
+                "/api/v2/autocomplete/recipient/" => {
+                    assert_eq!(request["search_text"], "Acme");
+                    json!({ "results": [
+                        { "recipient_name": "ACME HOLDINGS", "uei": "PARENT123456", "recipient_level": "P" },
+                        { "recipient_name": "ACME FOODS LLC", "uei": "CHILD1234567", "duns": "", "recipient_level": "C" }
+                    ]})
+                }
+                "/api/v2/recipient/" => json!({ "results": [
+                    { "id": "abc-C", "uei": "PARENT123456", "recipient_level": "C" },
+                    { "id": "abc-P", "uei": "PARENT123456", "recipient_level": "P" }
+                ]}),
+                "/api/v2/recipient/abc-P/" => json!({
+                    "name": "ACME HOLDINGS", "alternate_names": ["ACME HOLDING CO"], "uei": "PARENT123456",
+                    "recipient_id": "abc-P", "recipient_level": "P", "parents": [],
+                    "business_types": ["nonprofit"], "location": { "state_code": "MN" }, "total_transaction_amount": 1.5e6
+                }),
+                "/api/v2/recipient/children/PARENT123456/" => json!([
+                    { "recipient_id": "def-C", "name": "ACME FOODS LLC", "uei": "CHILD1234567" }
+                ]),
+                _ => return (404, String::new()),
+            };
+            (200, body.to_string())
+        });
+
+        let candidates = autocomplete_recipients(&config, "Acme", 10).unwrap();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[1].duns, None);
+
+        let profile = recipient_profile(&config, "PARENT123456", Some("P")).unwrap();
+        assert_eq!(profile.recipient_id, "abc-P");
+        assert_eq!(profile.alternate_names, ["ACME HOLDING CO"]);
+        assert_eq!(profile.state.as_deref(), Some("MN"));
+        assert_eq!(profile.children[0].uei.as_deref(), Some("CHILD1234567"));
+
+        let missing = recipient_profile(&config, "NOPE", None).unwrap_err();
+        assert!(matches!(missing, UsaSpendingError::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_fetches_award_detail_with_history() {
+        let config = spawn_server(|_, path, request| {
+            let body = match path {
+                "/api/v2/awards/CONT_AWD_1/" => json!({
+                    "generated_unique_award_id": "CONT_AWD_1", "piid": "W91", "category": "contract",
+                    "total_obligation": 300.0,
+                    "period_of_performance": { "start_date": "2020-01-01", "end_date": "2021-01-01" },
+                    "place_of_performance": { "state_code": "MN", "city_name": "ST PAUL" },
+                    "naics_hierarchy": { "base_code": { "code": "722310", "description": "Food Service Contractors" } },
+                    "latest_transaction_contract_data": { "extent_competed_description": "NOT COMPETED" },
+                    "awarding_agency": { "office_agency_name": "FNS", "toptier_agency": { "name": "USDA" } },
+                    "subaward_count": 1
+                }),
+                "/api/v2/transactions/" => {
+                    // Two pages of one modification each
+                    let page = request["page"].as_u64().unwrap();
+                    json!({
+                        "results": [{ "id": format!("T{}", page), "modification_number": (page - 1).to_string(),
+                                      "action_date": format!("2020-0{}-01", page), "federal_action_obligation": 150.0 }],
+                        "page_metadata": { "hasNext": page < 2 }
+                    })
+                }
+                "/api/v2/subawards/" => json!({
+                    "results": [{ "subaward_number": "S1", "amount": 50.0, "recipient_name": "SUB LLC" }],
+                    "page_metadata": { "hasNext": false }
+                }),
+                _ => return (404, String::new()),
+            };
+            (200, body.to_string())
+        });
+
+        let record = fetch_award_record(&config, "CONT_AWD_1").unwrap();
+        assert_eq!(record.detail.award_id.as_deref(), Some("W91"));
+        assert_eq!(record.detail.naics_code.as_deref(), Some("722310"));
+        assert_eq!(record.detail.extent_competed.as_deref(), Some("NOT COMPETED"));
+        assert_eq!(record.detail.place_of_performance.state.as_deref(), Some("MN"));
+        assert_eq!(record.transactions.len(), 2);
+        assert_eq!(record.transactions[1].modification_number.as_deref(), Some("1"));
+        assert_eq!(record.subawards[0].recipient_name.as_deref(), Some("SUB LLC"));
+    }
+
+    #[test]
+    fn test_fallback_transaction_ids_survive_new_modifications() {
+        let first = [
+            json!({ "modification_number": "0", "action_date": "2020-01-01" }),
+            json!({ "modification_number": "P00001", "action_date": "2020-03-01" }),
+        ];
+        let refetched = [
+            json!({ "modification_number": "0", "action_date": "2020-01-01" }),
+            json!({ "modification_number": "A1", "action_date": "2020-02-01" }),
+            json!({ "modification_number": "P00001", "action_date": "2020-03-01" }),
+            json!({ "modification_number": "P00001", "action_date": "2020-03-01" }),
+            json!({ "id": "T9", "modification_number": "P00002", "action_date": "2020-04-01" }),
+        ];
+        let ids = |raw: &[serde_json::Value]| -> Vec<String> {
+            transactions_from_raw("CONT_AWD_1", raw).into_iter().map(|t| t.transaction_id).collect()
+        };
+        assert_eq!(ids(&first), ["CONT_AWD_1#0#2020-01-01", "CONT_AWD_1#P00001#2020-03-01"]);
+        assert_eq!(ids(&refetched), [
+            "CONT_AWD_1#0#2020-01-01",
+            "CONT_AWD_1#A1#2020-02-01",
+            "CONT_AWD_1#P00001#2020-03-01",
+            "CONT_AWD_1#P00001#2020-03-01#2",
+            "T9",
+        ]);
+    }
+
+    #[test]
+    fn test_cached_search_reports_new_awards_and_works_offline() {
+        let base = std::env::temp_dir().join(format!("os-scout-{}", uuid::Uuid::new_v4()));
+        let cache = ResponseCache::new(&base, crate::crypto::SessionKey([9u8; 32]));
+        let filters = SearchFilters { award_type_groups: vec![AwardTypeGroup::Contracts], ..Default::default() };
+
+        let first = check_target_cached(&spawn_mock_api(2), &cache, "Acme", &filters, 100, false, |_| {}).unwrap();
+        assert!(first.previous_fetched_at.is_none());
+
+        let second = check_target_cached(&spawn_mock_api(3), &cache, " ACME", &filters, 100, false, |_| {}).unwrap();
+        assert_eq!(second.new_award_ids, ["CONT_AWD_2"]);
+        assert!(second.previous_fetched_at.is_some());
+
+        // Offline, or with the API down, the last result comes from the cache
+        let down = spawn_server(|_, _, _| (503, String::new()));
+        let offline = check_target_cached(&down, &cache, "acme", &filters, 100, true, |_| {}).unwrap();
+        assert!(offline.from_cache);
+        assert_eq!(offline.awards.len(), 3);
+        assert!(check_target_cached(&down, &cache, "acme", &filters, 100, false, |_| {}).unwrap().from_cache);
+        assert!(check_target_cached(&down, &cache, "Other", &filters, 100, true, |_| {}).is_err());
+
+        let listed = cached_searches(&cache);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].source, SOURCE_ID);
+        assert_eq!(cached_search(&cache, &listed[0].cache_key).unwrap().awards.len(), 3);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_filters_map_to_api_schema() {
+        let filters = SearchFilters {
+            start_date: Some("2019-01-01".to_string()),
+            end_date: Some("2021-12-31".to_string()),
+            awarding_agency: Some("Department of Agriculture".to_string()),
+            place_of_performance_state: Some("mn".to_string()),
+            naics_codes: vec!["722310".to_string()],
+            recipient_uei: Some("ABC123DEF456".to_string()),
+            min_amount: Some(100_000.0),
+            ..Default::default()
+        };
+        let api = filters.to_api_filters("Feeding Our Future").unwrap();
+        assert_eq!(api["time_period"][0]["start_date"], "2019-01-01");
+        assert_eq!(api["keywords"][0], "Feeding Our Future");
+        assert_eq!(api["agencies"][0]["type"], "awarding");
+        assert_eq!(api["place_of_performance_locations"][0]["state"], "MN");
+        assert_eq!(api["naics_codes"]["require"][0], "722310");
+        assert_eq!(api["recipient_search_text"][0], "ABC123DEF456");
+        assert_eq!(api["award_amounts"][0]["lower_bound"], 100_000.0);
+        assert!(api["award_amounts"][0].get("upper_bound").is_none());
+
+        let backwards = SearchFilters { start_date: Some("2022-01-01".to_string()), end_date: Some("2021-01-01".to_string()), ..Default::default() };
+        assert!(backwards.to_api_filters("Acme").is_err());
+        assert!(SearchFilters::default().to_api_filters(" ").is_err());
+    }
 
     #[test]
     fn test_fetch_feeding_our_future() {
-        let result = check_target("Feeding Our Future");
+        let result = check_target("Feeding Our Future", &SearchFilters::default(), 20, |_| {});
         match result {
-            Ok(summaries) => {
-                println!("Found {} records", summaries.len());
-                for s in summaries {
+            Ok(search) => {
+                println!("Found {} records ({} failed groups)", search.awards.len(), search.failures.len());
+                for s in search.awards {
                     println!(" - {} (${}): {}", s.recipient_name, s.total_obligation, s.description.unwrap_or_default());
                 }
             },